@@ -0,0 +1,316 @@
+use std::{
+  collections::HashMap,
+  io::{self, Write}
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+  error::Error,
+  namespace::{prefix_of, XML_NAMESPACE},
+  tree::{self, Content, Element}
+};
+
+/// Canonical XML flavour, used to write byte-identical XML for equivalent documents.
+///
+/// Canonical output has no XML declaration, writes every element as a start/end tag pair, sorts
+/// namespace declarations and attributes and escapes whitespace in attribute values. Comments are
+/// not part of the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Canonicalization {
+  /// [Canonical XML 1.0](https://www.w3.org/TR/xml-c14n): every namespace in scope is declared on
+  /// the outermost element where it is in scope.
+  Inclusive,
+  /// [Exclusive XML Canonicalization 1.0](https://www.w3.org/TR/xml-exc-c14n/): a namespace is
+  /// only declared on the elements whose name or attributes use it.
+  Exclusive
+}
+
+// Namespace bindings, by prefix. The default namespace has the `""` prefix
+type Bindings = HashMap<String, String>;
+
+// Local part of a qualified name
+fn local_of(name: &str) -> &str {
+  name.split_once(':').map_or(name, |(_, local)| local)
+}
+
+// Write `text` with the characters `reference` returns a reference for replaced
+fn write_escaped<W: Write>(writer: &mut W, text: &str, reference: fn(char) -> Option<&'static str>) -> io::Result<()> {
+  let mut start = 0;
+  for (i, c) in text.char_indices() {
+    if let Some(reference) = reference(c) {
+      writer.write_all(&text.as_bytes()[start .. i])?;
+      writer.write_all(reference.as_bytes())?;
+      start = i + c.len_utf8();
+    }
+  }
+  writer.write_all(&text.as_bytes()[start ..])
+}
+
+fn escape_text<W: Write>(writer: &mut W, text: &str) -> io::Result<()> {
+  write_escaped(writer, text, |c| match c {
+    '&' => Some("&amp;"),
+    '<' => Some("&lt;"),
+    '>' => Some("&gt;"),
+    '\r' => Some("&#xD;"),
+    _ => None
+  })
+}
+
+fn escape_attr<W: Write>(writer: &mut W, value: &str) -> io::Result<()> {
+  write_escaped(writer, value, |c| match c {
+    '&' => Some("&amp;"),
+    '<' => Some("&lt;"),
+    '"' => Some("&quot;"),
+    '\t' => Some("&#x9;"),
+    '\n' => Some("&#xA;"),
+    '\r' => Some("&#xD;"),
+    _ => None
+  })
+}
+
+fn write_attr<W: Write>(writer: &mut W, name: &str, value: &str) -> io::Result<()> {
+  writer.write_all(b" ")?;
+  writer.write_all(name.as_bytes())?;
+  writer.write_all(b"=\"")?;
+  escape_attr(writer, value)?;
+  writer.write_all(b"\"")
+}
+
+fn write_instruction<W: Write>(writer: &mut W, instruction: &str) -> io::Result<()> {
+  writer.write_all(b"<?")?;
+  writer.write_all(instruction.as_bytes())?;
+  writer.write_all(b"?>")
+}
+
+impl Canonicalization {
+  // Namespace declarations to write on `element`, sorted by prefix. `in_scope` holds the bindings
+  // in scope on the element and `rendered` those already written on its ancestors
+  fn declarations(&self, element: &Element, in_scope: &Bindings, rendered: &Bindings) -> Vec<(String, String)> {
+    let mut prefixes: Vec<&str> = match self {
+      Canonicalization::Inclusive => in_scope.keys().map(|p| p.as_str()).collect(),
+      Canonicalization::Exclusive => {
+        // Visibly utilized prefixes
+        let mut prefixes = vec![prefix_of(&element.name)];
+        for (name, _) in &element.attrs {
+          if name != "xmlns" && !name.starts_with("xmlns:") && name.contains(':') {
+            prefixes.push(prefix_of(name));
+          }
+        }
+        prefixes
+      }
+    };
+    prefixes.sort_unstable();
+    prefixes.dedup();
+
+    prefixes
+      .into_iter()
+      .filter(|prefix| *prefix != "xml")
+      .filter_map(|prefix| {
+        let uri = in_scope.get(prefix).map_or("", |u| u.as_str());
+        let inherited = rendered.get(prefix).map_or("", |u| u.as_str());
+        if uri == inherited || (uri.is_empty() && !prefix.is_empty()) {
+          None
+        } else {
+          Some((prefix.to_owned(), uri.to_owned()))
+        }
+      })
+      .collect()
+  }
+
+  fn write_element<W: Write>(&self, writer: &mut W, element: &Element, in_scope: &Bindings, rendered: &Bindings) -> io::Result<()> {
+    let mut in_scope = in_scope.clone();
+    let mut attrs = Vec::new();
+    for (name, value) in &element.attrs {
+      if name == "xmlns" {
+        in_scope.insert(String::new(), value.to_owned());
+      } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+        in_scope.insert(prefix.to_owned(), value.to_owned());
+      } else {
+        attrs.push((name, value));
+      }
+    }
+
+    let declarations = self.declarations(element, &in_scope, rendered);
+    let mut rendered = rendered.clone();
+
+    writer.write_all(b"<")?;
+    writer.write_all(element.name.as_bytes())?;
+    for (prefix, uri) in declarations {
+      if prefix.is_empty() {
+        write_attr(writer, "xmlns", &uri)?;
+      } else {
+        write_attr(writer, &format!("xmlns:{}", prefix), &uri)?;
+      }
+      rendered.insert(prefix, uri);
+    }
+
+    // Attributes are sorted by namespace URI, then by local name. Unqualified attributes have no
+    // namespace, so they come first
+    let namespace_of = |name: &str| -> String {
+      match prefix_of(name) {
+        "" => String::new(),
+        "xml" => XML_NAMESPACE.to_owned(),
+        prefix => in_scope.get(prefix).cloned().unwrap_or_else(|| prefix.to_owned())
+      }
+    };
+    attrs.sort_by_cached_key(|(name, _)| (namespace_of(name), local_of(name).to_owned()));
+    for (name, value) in attrs {
+      write_attr(writer, name, value)?;
+    }
+    writer.write_all(b">")?;
+
+    for child in &element.children {
+      match child {
+        Content::Element(e) => self.write_element(writer, e, &in_scope, &rendered)?,
+        Content::Text(t) | Content::CData(t) => escape_text(writer, t)?,
+        Content::Instruction(i) => write_instruction(writer, i)?,
+        Content::Comment(_) | Content::Declaration(_) | Content::DocType(_) => ()
+      }
+    }
+
+    writer.write_all(b"</")?;
+    writer.write_all(element.name.as_bytes())?;
+    writer.write_all(b">")
+  }
+
+  /// Write top level `nodes` in canonical form. Processing instructions are separated from the
+  /// document element by a line break
+  pub(crate) fn write<W: Write>(&self, writer: &mut W, nodes: &[Content]) -> io::Result<()> {
+    let mut after_root = false;
+    let nodes: Vec<&Content> = nodes
+      .iter()
+      .filter(|n| !matches!(n, Content::Comment(_) | Content::Declaration(_) | Content::DocType(_)))
+      .collect();
+    for (i, node) in nodes.iter().enumerate() {
+      match node {
+        Content::Element(e) => {
+          self.write_element(writer, e, &Bindings::new(), &Bindings::new())?;
+          after_root = true;
+        },
+        Content::Text(t) | Content::CData(t) => escape_text(writer, t)?,
+        Content::Comment(_) | Content::Declaration(_) | Content::DocType(_) => (),
+        Content::Instruction(instruction) => {
+          if after_root {
+            writer.write_all(b"\n")?;
+          }
+          write_instruction(writer, instruction)?;
+          if !after_root && i + 1 < nodes.len() {
+            writer.write_all(b"\n")?;
+          }
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Canonicalize an XML document
+  pub fn canonicalize(&self, xml: &str) -> Result<String, Error> {
+    let mut buffer = Vec::new();
+    self.write(&mut buffer, &tree::parse(xml)?)?;
+    Ok(String::from_utf8(buffer)?)
+  }
+}
+
+/// Canonicalize an XML document according to [Canonical XML 1.0](https://www.w3.org/TR/xml-c14n),
+/// without comments.
+///
+/// ```rust
+/// use xml2json_rs::canonicalize;
+///
+/// let xml = canonicalize(r#"<?xml version="1.0"?><a z="1" b='2'><c/></a>"#).unwrap();
+/// assert_eq!(xml, r#"<a b="2" z="1"><c></c></a>"#);
+/// ```
+pub fn canonicalize(xml: &str) -> Result<String, Error> {
+  Canonicalization::Inclusive.canonicalize(xml)
+}
+
+/// Canonicalize an XML document according to
+/// [Exclusive XML Canonicalization 1.0](https://www.w3.org/TR/xml-exc-c14n/), without comments.
+pub fn canonicalize_exclusive(xml: &str) -> Result<String, Error> {
+  Canonicalization::Exclusive.canonicalize(xml)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn empty_elements() {
+    assert_eq!(canonicalize("<a><b/><c></c></a>").unwrap(), "<a><b></b><c></c></a>");
+  }
+
+  #[test]
+  fn declaration_and_comments() {
+    let xml = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!-- comment -->\n<a><!-- b --></a>\n";
+    assert_eq!(canonicalize(xml).unwrap(), "<a></a>");
+  }
+
+  #[test]
+  fn instructions() {
+    let xml = "<?xml version=\"1.0\"?><?pi-a a?><a><?pi-b?></a><?pi-c c?>";
+    assert_eq!(canonicalize(xml).unwrap(), "<?pi-a a?>\n<a><?pi-b?></a>\n<?pi-c c?>");
+  }
+
+  #[test]
+  fn attribute_order() {
+    let xml = r#"<a xmlns:z="urn:a" xmlns:b="urn:b" xmlns="urn:default" z:x="1" b:y="2" c="3" a="4"></a>"#;
+    assert_eq!(
+      canonicalize(xml).unwrap(),
+      r#"<a xmlns="urn:default" xmlns:b="urn:b" xmlns:z="urn:a" a="4" c="3" z:x="1" b:y="2"></a>"#
+    );
+  }
+
+  #[test]
+  fn attribute_whitespace() {
+    let xml = "<a b=\"  x\ny\tz&#xA;&#x9;&#xD;\" c='\"&amp;&lt;'></a>";
+    assert_eq!(
+      canonicalize(xml).unwrap(),
+      "<a b=\"  x y z&#xA;&#x9;&#xD;\" c=\"&quot;&amp;&lt;\"></a>"
+    );
+  }
+
+  #[test]
+  fn text_escapes() {
+    let xml = "<a>&lt;&amp;&gt;&#xD;\r\n\"'<![CDATA[<b>]]></a>";
+    assert_eq!(canonicalize(xml).unwrap(), "<a>&lt;&amp;&gt;&#xD;\n\"'&lt;b&gt;</a>");
+  }
+
+  #[test]
+  fn inclusive_namespaces() {
+    let xml = r#"<a xmlns="urn:a" xmlns:p="urn:p"><b xmlns:p="urn:p" xmlns:q="urn:q"><c xmlns=""></c></b></a>"#;
+    assert_eq!(
+      canonicalize(xml).unwrap(),
+      r#"<a xmlns="urn:a" xmlns:p="urn:p"><b xmlns:q="urn:q"><c xmlns=""></c></b></a>"#
+    );
+  }
+
+  #[test]
+  fn superfluous_empty_default() {
+    assert_eq!(canonicalize(r#"<a xmlns=""><b xmlns=""></b></a>"#).unwrap(), "<a><b></b></a>");
+  }
+
+  #[test]
+  fn exclusive_namespaces() {
+    let xml = r#"<p:a xmlns:p="urn:p" xmlns:q="urn:q" xmlns:r="urn:r"><b q:x="1"><p:c></p:c></b></p:a>"#;
+    assert_eq!(
+      canonicalize_exclusive(xml).unwrap(),
+      r#"<p:a xmlns:p="urn:p"><b xmlns:q="urn:q" q:x="1"><p:c></p:c></b></p:a>"#
+    );
+  }
+
+  #[test]
+  fn exclusive_default_namespace() {
+    let xml = r#"<a xmlns="urn:a"><b xmlns="urn:a"><c xmlns=""></c></b></a>"#;
+    assert_eq!(
+      canonicalize_exclusive(xml).unwrap(),
+      r#"<a xmlns="urn:a"><b><c xmlns=""></c></b></a>"#
+    );
+  }
+
+  #[test]
+  fn unbalanced() {
+    assert!(canonicalize("<a><b></a>").is_err());
+  }
+}
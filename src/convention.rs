@@ -0,0 +1,268 @@
+use std::fmt;
+
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  error::{Error, ErrorKind},
+  tree::{Content, Element},
+  utils
+};
+
+mod badgerfish;
+mod gdata;
+mod jsonfeed;
+mod jsonml;
+mod parker;
+mod plist;
+mod soap;
+mod xml2js;
+mod xmljs;
+mod xmlrpc;
+mod xmltodict;
+
+pub use badgerfish::BadgerFish;
+pub use gdata::GData;
+pub use jsonfeed::{FeedFormat, JsonFeed};
+pub use jsonml::JsonML;
+pub use parker::Parker;
+pub use plist::Plist;
+pub use soap::{Soap, SoapFault, SoapVersion};
+pub use xml2js::Xml2Js;
+pub(crate) use xml2js::{check_name, JsonOptions, TagAttrs, XmlOptions};
+pub use xmljs::XmlJs;
+pub use xmlrpc::XmlRpc;
+pub use xmltodict::XmlToDict;
+
+/// Mapping convention between XML documents and JSON.
+///
+/// [JsonBuilder] and [XmlBuilder] delegate building nodes to the convention set with
+/// [JsonConfig::convention] and [XmlConfig::convention], and otherwise use the [Xml2Js] convention
+/// configured by their own options. The builders keep parsing, namespace prefixes and rendering.
+///
+/// ```rust
+/// use serde_json::{json, Value};
+/// use xml2json_rs::{Content, Convention, Element, JsonConfig, X2JError, XmlConfig};
+///
+/// // The document element's text as a JSON string
+/// #[derive(Debug)]
+/// struct TextOnly;
+///
+/// impl Convention for TextOnly {
+///   fn to_json(&self, nodes: &[Content]) -> Result<Value, X2JError> {
+///     let text = nodes.iter().find_map(|node| match node {
+///       Content::Element(e) => e.children.iter().find_map(|c| match c {
+///         Content::Text(t) => Some(t.clone()),
+///         _ => None
+///       }),
+///       _ => None
+///     });
+///     Ok(text.map_or(Value::Null, Value::String))
+///   }
+///
+///   fn to_xml(&self, json: &Value) -> Result<Vec<Content>, X2JError> {
+///     let mut element = Element::new("text");
+///     element
+///       .children
+///       .push(Content::Text(json.as_str().unwrap_or_default().to_owned()));
+///     Ok(vec![Content::Element(element)])
+///   }
+/// }
+///
+/// let json = JsonConfig::new()
+///   .convention(TextOnly)
+///   .finalize()
+///   .build_from_xml("<a>b</a>");
+/// assert_eq!(json.unwrap(), json!("b"));
+/// let xml = XmlConfig::new()
+///   .convention(TextOnly)
+///   .finalize()
+///   .build_from_json(&json!("b"));
+/// assert_eq!(xml.unwrap(), r#"<?xml version="1.0"?><text>b</text>"#);
+/// ```
+///
+/// [JsonConfig::convention]: crate::JsonConfig::convention
+/// [XmlConfig::convention]: crate::XmlConfig::convention
+pub trait Convention: fmt::Debug + Send + Sync {
+  /// Build JSON from the top level nodes of a parsed document. The XML declaration is the first
+  /// node, if the document has one, and the doctype is kept in document order.
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error>;
+
+  /// Build the top level nodes of a document from JSON. Clark notation names are given prefixes
+  /// when the document is written, and a first declaration node is written instead of the
+  /// XmlBuilder's declaration.
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error>;
+}
+
+// The document element among the top level nodes of a document
+pub(crate) fn document_element(nodes: &[Content]) -> Result<&Element, Error> {
+  nodes
+    .iter()
+    .find_map(|node| match node {
+      Content::Element(e) => Some(e),
+      _ => None
+    })
+    .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a document element."))
+}
+
+// Text of a scalar found at JSON pointer `path`
+pub(crate) fn text(path: &str, value: &JsonValue) -> Result<String, Error> {
+  match value {
+    JsonValue::Object(_) | JsonValue::Array(_) => Err(Error::new(ErrorKind::Syntax, format!("Expected character data at {}.", path))),
+    JsonValue::Null => Ok(String::new()),
+    _ => Ok(utils::to_string_raw(value))
+  }
+}
+
+// Add `value` under `key`, turning repeated keys, and keys in `arrays`, into arrays
+pub(crate) fn add(object: &mut Map<String, JsonValue>, key: String, value: JsonValue, arrays: &[String]) {
+  match object.get_mut(&key) {
+    Some(JsonValue::Array(array)) => array.push(value),
+    Some(existing) => *existing = JsonValue::Array(vec![existing.take(), value]),
+    None if arrays.contains(&key) => {
+      object.insert(key, JsonValue::Array(vec![value]));
+    },
+    None => {
+      object.insert(key, value);
+    }
+  }
+}
+
+// Type name and text of an object holding a tagged value: a value of one of the types `names`,
+// written as an object of its text under the type name prefixed with `$`
+pub(crate) fn tagged<'a>(object: &'a Map<String, JsonValue>, names: &[&str]) -> Option<(&'a str, &'a str)> {
+  let (key, value) = object.iter().next().filter(|_| object.len() == 1)?;
+  let name = key.strip_prefix('$').filter(|name| names.contains(name))?;
+  Some((name, value.as_str()?))
+}
+
+// JSON built from `xml` with `convention`, and the XML built back from that JSON
+#[cfg(test)]
+pub(crate) fn roundtrip<C: Convention + Clone + 'static>(convention: C, xml: &str) -> (JsonValue, String) {
+  let json = crate::JsonConfig::new()
+    .convention(convention.clone())
+    .finalize()
+    .build_from_xml(xml)
+    .unwrap();
+  let mut builder = crate::XmlConfig::new().convention(convention).finalize();
+  let xml = builder.build_from_json(&json).unwrap();
+  (json, xml)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{json::JsonConfig, tree::Element, xml::XmlConfig};
+  use serde_json::json;
+
+  // Element names and their nesting as arrays, such as `["a", ["b"]]`
+  #[derive(Debug)]
+  struct Names;
+
+  fn names(element: &Element) -> JsonValue {
+    let mut array = vec![JsonValue::String(element.name.clone())];
+    for child in &element.children {
+      if let Content::Element(e) = child {
+        array.push(names(e));
+      }
+    }
+    JsonValue::Array(array)
+  }
+
+  fn element(json: &JsonValue) -> Element {
+    let array = json.as_array().cloned().unwrap_or_default();
+    let mut element = Element::new(array.first().and_then(JsonValue::as_str).unwrap_or("a"));
+    for child in array.iter().skip(1) {
+      element.children.push(Content::Element(self::element(child)));
+    }
+    element
+  }
+
+  impl Convention for Names {
+    fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+      Ok(
+        match nodes.iter().find_map(|n| if let Content::Element(e) = n { Some(e) } else { None }) {
+          Some(e) => names(e),
+          None => JsonValue::Null
+        }
+      )
+    }
+
+    fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+      Ok(vec![Content::Element(element(json))])
+    }
+  }
+
+  #[test]
+  fn custom_convention() {
+    let builder = JsonConfig::new().convention(Names).finalize();
+    let json = builder.build_from_xml(r#"<a x="1"><b>text</b><c><d/></c></a>"#).unwrap();
+    assert_eq!(json, json!(["a", ["b"], ["c", ["d"]]]));
+
+    let mut builder = XmlConfig::new().convention(Names).finalize();
+    let xml = builder.build_from_json(&json).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a><b/><c><d/></c></a>"#);
+  }
+
+  #[test]
+  fn xml2js_convention() {
+    let xml = r#"<?xml version="1.0"?><a x="1"><b>c</b><b/></a>"#;
+    let json = JsonConfig::new().attrkey("@").finalize();
+    let builder = JsonConfig::new()
+      .convention(Xml2Js::new(json, XmlConfig::new().attrkey("@").finalize()))
+      .finalize();
+    let output = builder.build_from_xml(xml).unwrap();
+    assert_eq!(output, json!({"a":{"@":{"x":"1"},"b":["c",""]}}));
+
+    let (json, built) = roundtrip(Xml2Js::default(), xml);
+    assert_eq!(json, json!({"a":{"$":{"x":"1"},"b":["c",""]}}));
+    assert_eq!(built, xml);
+
+    // The builders delegate to an Xml2Js convention with their own options by default
+    let builder = JsonConfig::new().attrkey("@").finalize();
+    let nodes = crate::tree::parse_document(xml).unwrap();
+    assert_eq!(builder.convention().to_json(&nodes).unwrap(), output);
+    let builder = XmlConfig::new().attrkey("@").finalize();
+    assert_eq!(builder.convention().to_xml(&output).unwrap(), nodes[1 ..]);
+  }
+
+  // Build `json` with `convention`, giving the XML or the error details
+  fn build<C: Convention + 'static>(convention: C, policy: crate::NamePolicy, json: JsonValue) -> Result<String, String> {
+    let mut builder = XmlConfig::new().convention(convention).name_policy(policy).finalize();
+    builder.build_from_json(&json).map_err(|e| e.details().to_string())
+  }
+
+  #[test]
+  fn convention_names() {
+    use crate::{GData, JsonML, NamePolicy::*, Parker, Soap, XmlToDict};
+
+    let error = build(Parker::new(), Verbatim, json!({"a b":1})).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "a b" at /root/a b"#);
+    let error = build(Parker::new().root_name("a b"), Verbatim, json!({"a":1})).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "a b" at /a b"#);
+    let error = build(JsonML, Verbatim, json!(["", ""])).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "" at /"#);
+    let error = build(GData::new(), Verbatim, json!({"a":{"x$y$z":{}}})).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "x:y:z" at /a/x:y:z"#);
+    let error = build(XmlToDict::new(), Verbatim, json!({"a":{"@c<":"1"}})).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "c<" at /a/@c<"#);
+    let error = build(Soap::new(), Verbatim, json!({"a b":1})).unwrap_err();
+    assert!(
+      error.starts_with(r#"Invalid XML name "a b" at /soap:Envelope/soap:Body/"#),
+      "{}",
+      error
+    );
+
+    // Names are escaped with NamePolicy::Escape
+    let xml = build(Parker::new(), Escape, json!({"a b":1})).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><root><a_x0020_b>1</a_x0020_b></root>"#);
+    let xml = build(XmlToDict::new(), Escape, json!({"a":{"@c<":"1"}})).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a c_x003C_="1"/>"#);
+    let error = build(GData::new(), Escape, json!({"a":{"x$y$z":{}}})).unwrap_err();
+    assert_eq!(error, r#"Invalid XML name "x:y:z" at /a/x:y:z"#);
+
+    // Serializing with a convention checks the names too
+    let config = XmlConfig::new().convention(Parker::new()).clone();
+    let error = crate::to_string(&json!({"a b":1}), &config).unwrap_err();
+    assert_eq!(error.details(), r#"Invalid XML name "a b" at /root/a b"#);
+  }
+}
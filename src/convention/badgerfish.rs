@@ -0,0 +1,290 @@
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  convention::{add, document_element, text, Convention},
+  error::{Error, ErrorKind},
+  namespace,
+  tree::{Content, Element},
+  utils
+};
+
+/// The [BadgerFish](http://badgerfish.ning.com/) convention.
+///
+/// Text is stored under `$`, attributes under their name prefixed with `@` and the namespaces in
+/// scope of every element under `@xmlns`, with the default namespace under `$`. Elements are
+/// objects, and repeated elements are arrays of them. Text is stored before child elements, as
+/// the order of mixed content isn't kept.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{BadgerFish, JsonConfig, XmlConfig};
+///
+/// let xml = r#"<?xml version="1.0"?><alice charlie="david"><bob>charlie</bob><bob>david</bob></alice>"#;
+/// let json = JsonConfig::new().convention(BadgerFish).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!({"alice":{"bob":[{"$":"charlie"},{"$":"david"}],"@charlie":"david"}}));
+///
+/// let mut builder = XmlConfig::new().convention(BadgerFish).finalize();
+/// assert_eq!(builder.build_from_json(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BadgerFish;
+
+impl BadgerFish {
+  // JSON object for an element, with the namespaces in scope of its parent
+  fn object(&self, element: &Element, scope: &[(String, String)]) -> JsonValue {
+    let scope = namespace::declare(scope, element);
+    let attrs: Vec<_> = element
+      .attrs
+      .iter()
+      .filter(|(name, _)| name != "xmlns" && !name.starts_with("xmlns:"))
+      .map(|(name, value)| (format!("@{}", name), JsonValue::String(value.to_owned())))
+      .collect();
+
+    let mut object = Map::new();
+    // Whitespace between child elements isn't content
+    let text = element.text();
+    let whitespace = element.has_elements() && text.trim().is_empty();
+    if !text.is_empty() && !whitespace {
+      object.insert("$".to_owned(), text.into());
+    }
+    for child in &element.children {
+      if let Content::Element(e) = child {
+        let value = self.object(e, &scope);
+        add(&mut object, e.name.clone(), value, &[]);
+      }
+    }
+    object.extend(attrs);
+    // The last binding of each prefix is in scope, and an empty default namespace is none
+    let namespaces: Map<String, JsonValue> = scope
+      .iter()
+      .enumerate()
+      .filter(|(i, (prefix, _))| !scope[i + 1 ..].iter().any(|(p, _)| p == prefix))
+      .filter(|(_, (prefix, uri))| !(prefix.is_empty() && uri.is_empty()))
+      .map(|(_, (prefix, uri))| (if prefix.is_empty() { "$".to_owned() } else { prefix.clone() }, uri.clone().into()))
+      .collect();
+    if !namespaces.is_empty() {
+      object.insert("@xmlns".to_owned(), JsonValue::Object(namespaces));
+    }
+    JsonValue::Object(object)
+  }
+
+  // Build the element(s) named `name` from a node at JSON pointer `path`. An array node builds one
+  // element per item
+  fn elements(
+    &self,
+    path: &str,
+    name: &str,
+    node: &JsonValue,
+    scope: &[(String, String)],
+    content: &mut Vec<Content>
+  ) -> Result<(), Error> {
+    match node.as_array() {
+      Some(array) => {
+        for (i, child) in array.iter().enumerate() {
+          let element = self.element(&utils::json_pointer(path, &i.to_string()), name, child, scope)?;
+          content.push(Content::Element(element));
+        }
+      },
+      None => content.push(Content::Element(self.element(path, name, node, scope)?))
+    }
+    Ok(())
+  }
+
+  // Build a single element named `name` from its JSON node at JSON pointer `path`. Namespaces are
+  // only declared where they aren't in scope of the parent already
+  fn element(&self, path: &str, name: &str, node: &JsonValue, scope: &[(String, String)]) -> Result<Element, Error> {
+    let mut element = Element::new(name);
+    let object = match node {
+      JsonValue::Object(object) => object,
+      _ => {
+        let text = text(path, node)?;
+        if !text.is_empty() {
+          element.children.push(Content::Text(text));
+        }
+        return Ok(element);
+      }
+    };
+
+    if let Some(namespaces) = object.get("@xmlns") {
+      let path = utils::json_pointer(path, "@xmlns");
+      let namespaces = namespaces
+        .as_object()
+        .ok_or_else(|| Error::new(ErrorKind::Syntax, format!("Expected an object of namespaces at {}.", path)))?;
+      for (key, uri) in namespaces {
+        let uri = text(&utils::json_pointer(&path, key), uri)?;
+        let prefix = if key == "$" { "" } else { key.as_str() };
+        let bound = scope.iter().rev().find(|(p, _)| p == prefix).map(|(_, u)| u);
+        if bound != Some(&uri) {
+          let attr = if prefix.is_empty() {
+            "xmlns".to_owned()
+          } else {
+            format!("xmlns:{}", prefix)
+          };
+          element.attrs.push((attr, uri));
+        }
+      }
+    }
+    let scope = namespace::declare(scope, &element);
+
+    for (key, value) in object {
+      let path = utils::json_pointer(path, key);
+      if key == "$" {
+        let text = text(&path, value)?;
+        if !text.is_empty() {
+          element.children.push(Content::Text(text));
+        }
+      } else if key == "@xmlns" {
+        continue;
+      } else if let Some(attr) = key.strip_prefix('@') {
+        element.attrs.push((attr.to_owned(), text(&path, value)?));
+      } else {
+        self.elements(&path, key, value, &scope, &mut element.children)?;
+      }
+    }
+    Ok(element)
+  }
+}
+
+impl Convention for BadgerFish {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let root = document_element(nodes)?;
+    let mut object = Map::new();
+    object.insert(root.name.clone(), self.object(root, &[]));
+    Ok(JsonValue::Object(object))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    match json.as_object() {
+      Some(object) if object.len() == 1 => {
+        let (name, node) = object.iter().next().unwrap();
+        if node.is_array() {
+          return Err(Error::new(ErrorKind::Syntax, "Expected a single document element."));
+        }
+        let element = self.element(&utils::json_pointer("", name), name, node, &[])?;
+        Ok(vec![Content::Element(element)])
+      },
+      _ => Err(Error::new(ErrorKind::Syntax, "Expected an object with a single document element."))
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, xml::XmlConfig};
+  use serde_json::json;
+
+  // The examples of the BadgerFish rules, at http://badgerfish.ning.com/
+
+  #[test]
+  fn text() {
+    let (json, output) = roundtrip(BadgerFish, "<alice>bob</alice>");
+    assert_eq!(json, json!({"alice":{"$":"bob"}}));
+    assert_eq!(output, r#"<?xml version="1.0"?><alice>bob</alice>"#);
+  }
+
+  #[test]
+  fn nested_elements() {
+    let (json, output) = roundtrip(BadgerFish, "<alice><bob>charlie</bob><david>edgar</david></alice>");
+    assert_eq!(json, json!({"alice":{"bob":{"$":"charlie"},"david":{"$":"edgar"}}}));
+    assert_eq!(
+      output,
+      r#"<?xml version="1.0"?><alice><bob>charlie</bob><david>edgar</david></alice>"#
+    );
+  }
+
+  #[test]
+  fn repeated_elements() {
+    let (json, output) = roundtrip(BadgerFish, "<alice><bob>charlie</bob><bob>david</bob></alice>");
+    assert_eq!(json, json!({"alice":{"bob":[{"$":"charlie"},{"$":"david"}]}}));
+    assert_eq!(output, r#"<?xml version="1.0"?><alice><bob>charlie</bob><bob>david</bob></alice>"#);
+  }
+
+  #[test]
+  fn mixed_content() {
+    let (json, output) = roundtrip(BadgerFish, "<alice>bob<charlie>david</charlie>edgar</alice>");
+    assert_eq!(json, json!({"alice":{"$":"bobedgar","charlie":{"$":"david"}}}));
+    // The text is written before the child elements
+    assert_eq!(output, r#"<?xml version="1.0"?><alice>bobedgar<charlie>david</charlie></alice>"#);
+  }
+
+  #[test]
+  fn attributes() {
+    let (json, output) = roundtrip(BadgerFish, r#"<alice charlie="david">bob</alice>"#);
+    assert_eq!(json, json!({"alice":{"$":"bob","@charlie":"david"}}));
+    assert_eq!(output, r#"<?xml version="1.0"?><alice charlie="david">bob</alice>"#);
+  }
+
+  #[test]
+  fn default_namespace() {
+    let (json, output) = roundtrip(BadgerFish, r#"<alice xmlns="http://some-namespace">bob</alice>"#);
+    assert_eq!(json, json!({"alice":{"$":"bob","@xmlns":{"$":"http://some-namespace"}}}));
+    assert_eq!(output, r#"<?xml version="1.0"?><alice xmlns="http://some-namespace">bob</alice>"#);
+  }
+
+  #[test]
+  fn other_namespaces() {
+    let xml = r#"<alice xmlns="http://some-namespace" xmlns:charlie="http://some-other-namespace">bob</alice>"#;
+    let (json, output) = roundtrip(BadgerFish, xml);
+    assert_eq!(
+      json,
+      json!({"alice":{"$":"bob","@xmlns":{"$":"http://some-namespace","charlie":"http://some-other-namespace"}}})
+    );
+    assert_eq!(output, format!(r#"<?xml version="1.0"?>{}"#, xml));
+  }
+
+  #[test]
+  fn prefixed_elements() {
+    let (json, output) = roundtrip(
+      BadgerFish,
+      r#"<alice xmlns="http://some-namespace" xmlns:charlie="http://some-other-namespace">
+        <bob>david</bob>
+        <charlie:edgar>frank</charlie:edgar>
+      </alice>"#
+    );
+    let xmlns = json!({"charlie":"http://some-other-namespace","$":"http://some-namespace"});
+    assert_eq!(
+      json,
+      json!({"alice":{
+        "bob":{"$":"david","@xmlns":xmlns},
+        "charlie:edgar":{"$":"frank","@xmlns":xmlns},
+        "@xmlns":xmlns
+      }})
+    );
+    assert_eq!(
+      output,
+      r#"<?xml version="1.0"?><alice xmlns="http://some-namespace" xmlns:charlie="http://some-other-namespace"><bob>david</bob><charlie:edgar>frank</charlie:edgar></alice>"#
+    );
+  }
+
+  #[test]
+  fn redeclared_namespaces() {
+    let xml = r#"<a xmlns="urn:x" xmlns:p="urn:p"><b xmlns="urn:y"><c xmlns=""/></b></a>"#;
+    let (json, output) = roundtrip(BadgerFish, xml);
+    assert_eq!(
+      json,
+      json!({"a":{"b":{"c":{"@xmlns":{"p":"urn:p"}},"@xmlns":{"p":"urn:p","$":"urn:y"}},"@xmlns":{"$":"urn:x","p":"urn:p"}}})
+    );
+    assert_eq!(
+      output,
+      r#"<?xml version="1.0"?><a xmlns="urn:x" xmlns:p="urn:p"><b xmlns="urn:y"><c/></b></a>"#
+    );
+  }
+
+  #[test]
+  fn empty_elements() {
+    let (json, output) = roundtrip(BadgerFish, "<alice><bob/></alice>");
+    assert_eq!(json, json!({"alice":{"bob":{}}}));
+    assert_eq!(output, r#"<?xml version="1.0"?><alice><bob/></alice>"#);
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut builder = XmlConfig::new().convention(BadgerFish).finalize();
+    let error = builder.build_from_json(&json!({"a":{},"b":{}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an object with a single document element.");
+
+    let error = builder.build_from_json(&json!({"a":{"@x":{"y":"z"}}})).unwrap_err();
+    assert_eq!(error.details(), "Expected character data at /a/@x.");
+  }
+}
@@ -0,0 +1,239 @@
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  convention::{add, document_element, Convention},
+  error::{Error, ErrorKind},
+  tree::{Content, Element},
+  utils
+};
+
+// Key of text
+const TEXT_KEY: &str = "$t";
+
+// JSON key of an element or attribute name, with `$` instead of the prefix separator
+fn json_key(name: &str) -> String {
+  name.replace(':', "$")
+}
+
+// Element or attribute name of a JSON key
+fn xml_name(key: &str) -> String {
+  key.replace('$', ":")
+}
+
+/// The convention of the Google Data APIs JSON feeds.
+///
+/// Elements are objects of their attributes, as strings, and of their child elements; their text
+/// goes under `$t`. Repeated elements, and elements named with [`GData::array`], are arrays. The
+/// prefix separator of names, including the one of `xmlns` declarations, is written as `$`, and
+/// the `version` and `encoding` of the XML declaration are stored next to the document element.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{GData, JsonConfig, XmlConfig};
+///
+/// let xml = r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns="http://www.w3.org/2005/Atom" xmlns:openSearch="http://a9.com/-/spec/opensearchrss/1.0/"><title type="text">Events</title><openSearch:totalResults>1</openSearch:totalResults><entry><id>1</id></entry></feed>"#;
+/// let builder = JsonConfig::new().convention(GData::new().array("entry")).finalize();
+/// let json = builder.build_from_xml(xml).unwrap();
+/// assert_eq!(
+///   json,
+///   json!({
+///     "version": "1.0",
+///     "encoding": "UTF-8",
+///     "feed": {
+///       "xmlns": "http://www.w3.org/2005/Atom",
+///       "xmlns$openSearch": "http://a9.com/-/spec/opensearchrss/1.0/",
+///       "title": {"type": "text", "$t": "Events"},
+///       "openSearch$totalResults": {"$t": "1"},
+///       "entry": [{"id": {"$t": "1"}}]
+///     }
+///   })
+/// );
+///
+/// let mut builder = XmlConfig::new().convention(GData::new()).finalize();
+/// assert_eq!(builder.build_from_json(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct GData {
+  arrays: Vec<String>
+}
+
+impl GData {
+  /// Initialize the GData convention
+  pub fn new() -> GData {
+    GData::default()
+  }
+
+  /// Always store the elements with JSON key `key`, such as `"entry"` or `"gd$email"`, in an
+  /// array. Can be called multiple times to add keys.
+  pub fn array<T: Into<String>>(mut self, key: T) -> GData {
+    self.arrays.push(key.into());
+    self
+  }
+
+  // JSON object for an element
+  fn object(&self, element: &Element) -> JsonValue {
+    let mut object = Map::new();
+    for (name, value) in &element.attrs {
+      object.insert(json_key(name), value.clone().into());
+    }
+    // Whitespace between child elements isn't content
+    let text = element.text();
+    let whitespace = element.has_elements() && text.trim().is_empty();
+    if !text.is_empty() && !whitespace {
+      object.insert(TEXT_KEY.to_owned(), text.into());
+    }
+    for child in &element.children {
+      if let Content::Element(e) = child {
+        let key = json_key(&e.name);
+        let value = self.object(e);
+        add(&mut object, key, value, &self.arrays);
+      }
+    }
+    JsonValue::Object(object)
+  }
+
+  // Build an element named after `key` from its JSON node at JSON pointer `path`. Scalar nodes are
+  // elements with text only
+  fn element(&self, path: &str, key: &str, node: &JsonValue) -> Result<Element, Error> {
+    let mut element = Element::new(xml_name(key));
+    let object = match node {
+      JsonValue::Object(object) => object,
+      JsonValue::Array(_) => {
+        return Err(Error::new(ErrorKind::Syntax, format!("Expected an element at {}.", path)));
+      },
+      JsonValue::Null => return Ok(element),
+      _ => {
+        element.children.push(Content::Text(utils::to_string_raw(node)));
+        return Ok(element);
+      }
+    };
+
+    let mut text = None;
+    for (key, value) in object {
+      let path = utils::json_pointer(path, key);
+      match value {
+        JsonValue::Object(_) => element.children.push(Content::Element(self.element(&path, key, value)?)),
+        JsonValue::Array(array) => {
+          for (i, item) in array.iter().enumerate() {
+            let element_path = utils::json_pointer(&path, &i.to_string());
+            element.children.push(Content::Element(self.element(&element_path, key, item)?));
+          }
+        },
+        JsonValue::Null => (),
+        _ if key == TEXT_KEY => text = Some(utils::to_string_raw(value)),
+        _ => element.attrs.push((xml_name(key), utils::to_string_raw(value)))
+      }
+    }
+    if let Some(text) = text.filter(|t| !t.is_empty()) {
+      element.children.insert(0, Content::Text(text));
+    }
+    Ok(element)
+  }
+}
+
+impl Convention for GData {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let mut object = Map::new();
+    if let Some(Content::Declaration(attrs)) = nodes.first() {
+      for (key, value) in attrs.iter().filter(|(k, _)| k == "version" || k == "encoding") {
+        object.insert(key.clone(), value.clone().into());
+      }
+    }
+    let root = document_element(nodes)?;
+    object.insert(json_key(&root.name), self.object(root));
+    Ok(JsonValue::Object(object))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let expected = || Error::new(ErrorKind::Syntax, "Expected an object with a single document element.");
+    let object = json.as_object().ok_or_else(expected)?;
+
+    let mut declaration = Vec::new();
+    let mut root = None;
+    for (key, value) in object {
+      match value {
+        JsonValue::Object(_) if root.is_none() => root = Some(self.element(&utils::json_pointer("", key), key, value)?),
+        JsonValue::String(s) if key == "version" || key == "encoding" => declaration.push((key.clone(), s.clone())),
+        _ => return Err(expected())
+      }
+    }
+
+    let mut nodes = Vec::new();
+    if !declaration.is_empty() {
+      nodes.push(Content::Declaration(declaration));
+    }
+    nodes.push(Content::Element(root.ok_or_else(expected)?));
+    Ok(nodes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+  use serde_json::json;
+
+  // Shortened from the calendar feed example of the Google Data APIs JSON documentation
+  const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?><feed xmlns="http://www.w3.org/2005/Atom" xmlns:openSearch="http://a9.com/-/spec/opensearchrss/1.0/" xmlns:gCal="http://schemas.google.com/gCal/2005"><id>http://www.google.com/calendar/feeds/developer-calendar@google.com/public/full</id><title type="text">Google Developer Events</title><link rel="alternate" type="text/html" href="http://www.google.com/calendar/embed"/><link rel="self" type="application/atom+xml" href="http://www.google.com/calendar/feeds/developer-calendar@google.com/public/full"/><author><name>Google Developer Calendar</name></author><generator version="1.0" uri="http://www.google.com/calendar">Google Calendar</generator><openSearch:startIndex>1</openSearch:startIndex><gCal:timezone value="America/Los_Angeles"/></feed>"#;
+
+  fn gdata() -> GData {
+    GData::new().array("link").array("author").array("entry")
+  }
+
+  #[test]
+  fn feed() {
+    let (json, output) = roundtrip(gdata(), FEED);
+    assert_eq!(
+      json,
+      json!({
+        "version": "1.0",
+        "encoding": "UTF-8",
+        "feed": {
+          "xmlns": "http://www.w3.org/2005/Atom",
+          "xmlns$openSearch": "http://a9.com/-/spec/opensearchrss/1.0/",
+          "xmlns$gCal": "http://schemas.google.com/gCal/2005",
+          "id": {"$t": "http://www.google.com/calendar/feeds/developer-calendar@google.com/public/full"},
+          "title": {"type": "text", "$t": "Google Developer Events"},
+          "link": [
+            {"rel": "alternate", "type": "text/html", "href": "http://www.google.com/calendar/embed"},
+            {"rel": "self", "type": "application/atom+xml", "href": "http://www.google.com/calendar/feeds/developer-calendar@google.com/public/full"}
+          ],
+          "author": [{"name": {"$t": "Google Developer Calendar"}}],
+          "generator": {"version": "1.0", "uri": "http://www.google.com/calendar", "$t": "Google Calendar"},
+          "openSearch$startIndex": {"$t": "1"},
+          "gCal$timezone": {"value": "America/Los_Angeles"}
+        }
+      })
+    );
+    assert_eq!(output, FEED);
+  }
+
+  #[test]
+  fn without_declaration() {
+    let json = JsonConfig::new()
+      .convention(GData::new())
+      .finalize()
+      .build_from_xml("<a>\n  <b:c>1</b:c>\n  <b:c>2</b:c>\n</a>")
+      .unwrap();
+    assert_eq!(json, json!({"a": {"b$c": [{"$t": "1"}, {"$t": "2"}]}}));
+
+    let mut builder = XmlConfig::new().convention(GData::new()).finalize();
+    let xml = builder
+      .build_from_json(&json!({"a": {"n": 1, "$t": "x", "b": ["y", null]}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a n="1">x<b>y</b><b/></a>"#);
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut builder = XmlConfig::new().convention(GData::new()).finalize();
+    let error = builder.build_from_json(&json!({"a": {}, "b": {}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an object with a single document element.");
+
+    let error = builder.build_from_json(&json!({"version": "1.0"})).unwrap_err();
+    assert_eq!(error.details(), "Expected an object with a single document element.");
+
+    let error = builder.build_from_json(&json!({"a": {"b": [[]]}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an element at /a/b/0.");
+  }
+}
@@ -0,0 +1,1140 @@
+use std::{
+  iter,
+  time::{SystemTime, UNIX_EPOCH}
+};
+
+use regex::Regex;
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  convention::{document_element, Convention},
+  error::{Error, ErrorKind},
+  namespace::{self, Scope},
+  tree::{Content, Element},
+  utils
+};
+
+// Version URL of JSON Feed documents
+const VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+// Namespaces of feed documents and of the RSS modules
+const ATOM: &str = "http://www.w3.org/2005/Atom";
+const RDF: &str = "http://www.w3.org/1999/02/22-rdf-syntax-ns#";
+const CONTENT: &str = "http://purl.org/rss/1.0/modules/content/";
+const DC: &str = "http://purl.org/dc/elements/1.1/";
+
+// HTML elements without content or end tag
+const VOID_ELEMENTS: [&str; 14] = [
+  "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source", "track", "wbr"
+];
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+lazy_static! {
+  // RFC 822 date of RSS, with optional day of week, seconds and zone. Digits are ASCII, since the
+  // captures are sliced by byte
+  static ref RFC822_RE: Regex = Regex::new(
+    r"^\s*(?:[A-Za-z]{3},\s*)?([0-9]{1,2})\s+([A-Za-z]{3})\s+([0-9]{4}|[0-9]{2})\s+([0-9]{2}):([0-9]{2})(?::([0-9]{2}))?\s*([A-Za-z]{1,3}|[+-][0-9]{4})?\s*$"
+  )
+  .unwrap();
+  // RFC 3339 date of Atom and JSON Feed
+  static ref RFC3339_RE: Regex =
+    Regex::new(r"^\s*([0-9]{4})-([0-9]{2})-([0-9]{2})[Tt ]([0-9]{2}):([0-9]{2}):([0-9]{2})(?:\.[0-9]+)?([Zz]|[+-][0-9]{2}:[0-9]{2})\s*$").unwrap();
+}
+
+// Days from 1970-01-01 to a date of the proleptic Gregorian calendar
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+  let year = if month <= 2 { year - 1 } else { year };
+  let era = year.div_euclid(400);
+  let year_of_era = year - era * 400;
+  let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+  let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+  era * 146_097 + day_of_era - 719_468
+}
+
+// Date of the proleptic Gregorian calendar of a number of days from 1970-01-01
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+  let days = days + 719_468;
+  let era = days.div_euclid(146_097);
+  let day_of_era = days - era * 146_097;
+  let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let month_index = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+  let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+  let year = year_of_era + era * 400;
+  (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+// Seconds from 1970-01-01T00:00:00Z to an RFC 3339 date, unless it can't be read
+fn timestamp(date: &str) -> Option<i64> {
+  let captures = RFC3339_RE.captures(date)?;
+  let number = |i: usize| captures[i].parse::<i64>().ok();
+  let offset = match &captures[7] {
+    "Z" | "z" => 0,
+    zone => {
+      let minutes = zone[1 .. 3].parse::<i64>().ok()? * 60 + zone[4 ..].parse::<i64>().ok()?;
+      if zone.starts_with('-') {
+        -minutes
+      } else {
+        minutes
+      }
+    }
+  };
+  let days = days_from_civil(number(1)?, number(2)?, number(3)?);
+  Some(days * 86_400 + number(4)? * 3600 + (number(5)? - offset) * 60 + number(6)?)
+}
+
+// RFC 3339 form of the current time, in UTC
+fn now() -> String {
+  let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs() as i64);
+  let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+  let time = seconds.rem_euclid(86_400);
+  format!(
+    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+    year,
+    month,
+    day,
+    time / 3600,
+    time / 60 % 60,
+    time % 60
+  )
+}
+
+// Identifier of a feed or item without one: a `urn:uuid` of a version 8 UUID built from FNV-1a
+// hashes of `parts`, so that the same content keeps the same identifier
+fn hashed_id(parts: &[Option<&str>]) -> String {
+  let hash = |basis: u64| {
+    let bytes = parts.iter().flat_map(|part| part.unwrap_or_default().bytes().chain(iter::once(0)));
+    bytes.fold(basis, |hash, b| (hash ^ u64::from(b)).wrapping_mul(0x100_0000_01b3))
+  };
+  let (high, low) = (hash(0xcbf2_9ce4_8422_2325), hash(0x8422_2325_cbf2_9ce4));
+  format!(
+    "urn:uuid:{:08x}-{:04x}-8{:03x}-{:04x}-{:012x}",
+    high >> 32,
+    (high >> 16) & 0xffff,
+    high & 0xfff,
+    0x8000 | ((low >> 48) & 0x3fff),
+    low & 0xffff_ffff_ffff
+  )
+}
+
+// RFC 3339 form of an RFC 822 date. Dates which can't be read are kept as they are
+fn rfc3339(date: &str) -> String {
+  let captures = match RFC822_RE.captures(date) {
+    Some(captures) => captures,
+    None => return date.trim().to_owned()
+  };
+  let month = match MONTHS.iter().position(|m| m.eq_ignore_ascii_case(&captures[2])) {
+    Some(month) => month + 1,
+    None => return date.trim().to_owned()
+  };
+  let year = captures[3].parse::<u32>().unwrap_or_default();
+  let year = match captures[3].len() {
+    2 if year < 50 => year + 2000,
+    2 => year + 1900,
+    _ => year
+  };
+  let zone = captures.get(7).map(|z| z.as_str().to_ascii_uppercase());
+  let offset = match zone.as_deref() {
+    None | Some("GMT") | Some("UT") | Some("UTC") | Some("Z") => "Z".to_owned(),
+    Some("EDT") => "-04:00".to_owned(),
+    Some("EST") | Some("CDT") => "-05:00".to_owned(),
+    Some("CST") | Some("MDT") => "-06:00".to_owned(),
+    Some("MST") | Some("PDT") => "-07:00".to_owned(),
+    Some("PST") => "-08:00".to_owned(),
+    Some(zone) if zone.starts_with(['+', '-']) => format!("{}:{}", &zone[.. 3], &zone[3 ..]),
+    Some(_) => return date.trim().to_owned()
+  };
+  format!(
+    "{:04}-{:02}-{:02}T{}:{}:{}{}",
+    year,
+    month,
+    captures[1].parse::<u32>().unwrap_or_default(),
+    &captures[4],
+    &captures[5],
+    captures.get(6).map_or("00", |s| s.as_str()),
+    offset
+  )
+}
+
+// RFC 822 form of an RFC 3339 date. Dates which can't be read are kept as they are
+fn rfc822(date: &str) -> String {
+  let captures = match RFC3339_RE.captures(date) {
+    Some(captures) => captures,
+    None => return date.trim().to_owned()
+  };
+  let year = captures[1].parse::<i64>().unwrap_or_default();
+  let month = captures[2].parse::<i64>().unwrap_or_default();
+  let day = captures[3].parse::<i64>().unwrap_or_default();
+  if !(1 ..= 12).contains(&month) || !(1 ..= 31).contains(&day) {
+    return date.trim().to_owned();
+  }
+  // 1970-01-01 is a Thursday
+  let weekday = (days_from_civil(year, month, day) + 3).rem_euclid(7) as usize;
+  let zone = match &captures[7] {
+    "Z" | "z" => "GMT".to_owned(),
+    offset => offset.replace(':', "")
+  };
+  format!(
+    "{}, {:02} {} {} {}:{}:{} {}",
+    DAYS[weekday],
+    day,
+    MONTHS[month as usize - 1],
+    year,
+    &captures[4],
+    &captures[5],
+    &captures[6],
+    zone
+  )
+}
+
+// Write XHTML `nodes` as HTML, without namespace prefixes
+fn write_html(buffer: &mut String, nodes: &[Content]) {
+  for node in nodes {
+    match node {
+      Content::Element(element) => {
+        let name = element.local_name();
+        buffer.push('<');
+        buffer.push_str(name);
+        for (key, value) in &element.attrs {
+          if key != "xmlns" && !key.starts_with("xmlns:") {
+            buffer.push(' ');
+            buffer.push_str(key);
+            buffer.push_str("=\"");
+            buffer.push_str(&value.replace('&', "&amp;").replace('"', "&quot;"));
+            buffer.push('"');
+          }
+        }
+        buffer.push('>');
+        if !(element.children.is_empty() && VOID_ELEMENTS.contains(&name)) {
+          write_html(buffer, &element.children);
+          buffer.push_str("</");
+          buffer.push_str(name);
+          buffer.push('>');
+        }
+      },
+      Content::Text(t) | Content::CData(t) => buffer.push_str(&t.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")),
+      _ => ()
+    }
+  }
+}
+
+// Element `name` with HTML `html` in a CDATA section
+fn html_element(name: &str, html: &str) -> Element {
+  let mut element = Element::new(name);
+  element.children.push(Content::CData(html.to_owned()));
+  element
+}
+
+// Element `name` with `attrs` and no content
+fn empty_element(name: &str, attrs: &[(&str, Option<&str>)]) -> Element {
+  let mut element = Element::new(name);
+  for (key, value) in attrs {
+    if let Some(value) = value {
+      element.attrs.push(((*key).to_owned(), (*value).to_owned()));
+    }
+  }
+  element
+}
+
+// Insert `value` under `key` unless it is missing
+fn insert<T: Into<JsonValue>>(object: &mut Map<String, JsonValue>, key: &str, value: Option<T>) {
+  if let Some(value) = value {
+    object.insert(key.to_owned(), value.into());
+  }
+}
+
+// String member `key` of an object
+fn string<'a>(object: &'a Map<String, JsonValue>, key: &str) -> Option<&'a str> {
+  object.get(key).and_then(JsonValue::as_str)
+}
+
+// Names and URLs of the `authors` of a feed or item, or of its JSON Feed 1.0 `author`
+fn authors(object: &Map<String, JsonValue>) -> Vec<(Option<&str>, Option<&str>)> {
+  let authors = match (object.get("authors"), object.get("author")) {
+    (Some(JsonValue::Array(authors)), _) => authors.iter().collect(),
+    (None, Some(author)) => vec![author],
+    _ => Vec::new()
+  };
+  authors
+    .into_iter()
+    .filter_map(JsonValue::as_object)
+    .map(|author| (string(author, "name"), string(author, "url")))
+    .collect()
+}
+
+// An element with the namespace declarations in scope on it
+struct Node<'a> {
+  element: &'a Element,
+  scope:   Scope
+}
+
+impl<'a> Node<'a> {
+  fn new(element: &'a Element, scope: &[(String, String)]) -> Node<'a> {
+    Node {
+      element,
+      scope: namespace::declare(scope, element)
+    }
+  }
+
+  // Namespace URI of the element
+  fn namespace(&self) -> Option<&str> {
+    namespace::resolve(&self.scope, &self.element.name, false).0
+  }
+
+  // Check if the element is `local` in namespace `uri`
+  fn is(&self, uri: Option<&str>, local: &str) -> bool {
+    self.element.local_name() == local && self.namespace() == uri
+  }
+
+  fn children(&self) -> impl Iterator<Item = Node<'a>> + '_ {
+    self.element.children.iter().filter_map(move |c| match c {
+      Content::Element(e) => Some(Node::new(e, &self.scope)),
+      _ => None
+    })
+  }
+
+  // Child elements `local` in namespace `uri`
+  fn all<'b>(&'b self, uri: Option<&'b str>, local: &'b str) -> impl Iterator<Item = Node<'a>> + 'b {
+    self.children().filter(move |c| c.is(uri, local))
+  }
+
+  // First child element `local` in namespace `uri`
+  fn first(&self, uri: Option<&str>, local: &str) -> Option<Node<'a>> {
+    self.all(uri, local).next()
+  }
+
+  // Trimmed text of the first child element `local` in namespace `uri`, unless empty
+  fn text(&self, uri: Option<&str>, local: &str) -> Option<String> {
+    self
+      .first(uri, local)
+      .map(|c| c.element.text().trim().to_owned())
+      .filter(|t| !t.is_empty())
+  }
+
+  fn attr(&self, name: &str) -> Option<&'a str> {
+    self.element.attr(name)
+  }
+}
+
+/// Format of the feeds written by [`JsonFeed`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FeedFormat {
+  /// RSS 2.0
+  RSS,
+  /// Atom 1.0
+  Atom
+}
+
+/// RSS and Atom feeds as [JSON Feed 1.1](https://www.jsonfeed.org/version/1.1/) objects.
+///
+/// RSS 0.9x, 1.0 and 2.0 channels and Atom 1.0 feeds are read into a JSON Feed, with the `title`,
+/// `home_page_url`, `feed_url`, `description`, `icon`, `favicon`, `authors` and `language` of
+/// the feed and its `items`. Items have the `id`, `url`, `external_url`, `title`, `content_html`,
+/// `content_text`, `summary`, dates, `authors`, `tags` and `attachments` the feed provides.
+///
+/// RSS dates are converted from RFC 822 to RFC 3339, and back when writing RSS. HTML content, in
+/// an RSS `description` or `content:encoded` or an Atom `html` or `xhtml` text, is `content_html`;
+/// when an RSS item has both, the description is the `summary`. Enclosures are attachments.
+///
+/// Items always have an `id`: the RSS `guid`, else the `link`, else a `urn:uuid` hashed from the
+/// item's title, description and date, which stays the same while they do.
+///
+/// JSON Feeds are written as RSS 2.0, unless the [`FeedFormat`] is set to Atom. HTML content is
+/// written in CDATA sections. Atom feeds and entries always have the `id` and `updated` Atom
+/// requires. The feed `id` is its `feed_url`, else its `home_page_url`, else a `urn:uuid` hashed
+/// from its title and description, and an entry `id` is the item `id`, else its `url` or
+/// `external_url`, else a `urn:uuid` hashed from its title, content and date. The feed is updated
+/// at the latest date of its items, or now when none has a date, and entries without a date at the
+/// date of the feed.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{FeedFormat, JsonConfig, JsonFeed, XmlConfig};
+///
+/// let xml = r#"<rss version="2.0"><channel><title>News</title><link>https://example.com/</link><description>Daily news</description><item><title>Launch</title><link>https://example.com/launch</link><description><![CDATA[<p>We have <b>liftoff</b></p>]]></description><pubDate>Tue, 03 Jun 2003 09:39:21 GMT</pubDate></item></channel></rss>"#;
+/// let json = JsonConfig::new().convention(JsonFeed::new()).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(
+///   json,
+///   json!({
+///     "version": "https://jsonfeed.org/version/1.1",
+///     "title": "News",
+///     "home_page_url": "https://example.com/",
+///     "description": "Daily news",
+///     "items": [{
+///       "id": "https://example.com/launch",
+///       "url": "https://example.com/launch",
+///       "title": "Launch",
+///       "content_html": "<p>We have <b>liftoff</b></p>",
+///       "date_published": "2003-06-03T09:39:21Z"
+///     }]
+///   })
+/// );
+///
+/// let mut builder = XmlConfig::new().convention(JsonFeed::new().format(FeedFormat::Atom)).finalize();
+/// assert_eq!(
+///   builder.build_from_json(&json).unwrap(),
+///   r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"><title>News</title><subtitle>Daily news</subtitle><link rel="alternate" href="https://example.com/"/><id>https://example.com/</id><updated>2003-06-03T09:39:21Z</updated><entry><id>https://example.com/launch</id><title>Launch</title><link rel="alternate" href="https://example.com/launch"/><published>2003-06-03T09:39:21Z</published><updated>2003-06-03T09:39:21Z</updated><content type="html"><![CDATA[<p>We have <b>liftoff</b></p>]]></content></entry></feed>"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonFeed {
+  format: FeedFormat
+}
+
+impl Default for JsonFeed {
+  fn default() -> JsonFeed {
+    JsonFeed { format: FeedFormat::RSS }
+  }
+}
+
+impl JsonFeed {
+  /// Initialize the JSON Feed convention, writing RSS 2.0 feeds
+  pub fn new() -> JsonFeed {
+    JsonFeed::default()
+  }
+
+  /// Format of the written feeds
+  pub fn format(mut self, format: FeedFormat) -> JsonFeed {
+    self.format = format;
+    self
+  }
+
+  // JSON Feed of an RSS `channel`, whose items are children of `parent`: the channel itself in
+  // RSS 0.91 to 2.0 and the `rdf:RDF` document element in RSS 0.90 and 1.0
+  fn read_rss(&self, channel: &Node, parent: &Node) -> JsonValue {
+    let rss = channel.namespace();
+    let mut feed = Map::new();
+    feed.insert("version".to_owned(), VERSION.into());
+    insert(&mut feed, "title", Some(channel.text(rss, "title").unwrap_or_default()));
+    insert(&mut feed, "home_page_url", channel.text(rss, "link"));
+    let feed_url = channel.all(Some(ATOM), "link").find(|l| l.attr("rel") == Some("self"));
+    insert(&mut feed, "feed_url", feed_url.and_then(|l| l.attr("href")));
+    insert(&mut feed, "description", channel.text(rss, "description"));
+    let image = channel.first(rss, "image").or_else(|| parent.first(rss, "image"));
+    insert(&mut feed, "icon", image.and_then(|i| i.text(rss, "url")));
+    let authors = channel
+      .text(rss, "managingEditor")
+      .into_iter()
+      .chain(channel.all(Some(DC), "creator").map(|c| c.element.text().trim().to_owned()));
+    self.insert_authors(&mut feed, authors.map(|name| (name, None)).collect());
+    insert(&mut feed, "language", channel.text(rss, "language"));
+    let items = parent
+      .all(rss, "item")
+      .map(|item| self.read_rss_item(&item, rss))
+      .collect::<Vec<_>>();
+    feed.insert("items".to_owned(), JsonValue::Array(items));
+    JsonValue::Object(feed)
+  }
+
+  // JSON Feed item of an RSS `item`, with RSS elements in namespace `rss`
+  fn read_rss_item(&self, item: &Node, rss: Option<&str>) -> JsonValue {
+    let mut object = Map::new();
+    let link = item.text(rss, "link");
+    let title = item.text(rss, "title");
+    let description = item.text(rss, "description");
+    let id = item.text(rss, "guid").or_else(|| link.clone()).unwrap_or_else(|| {
+      let date = item.text(rss, "pubDate").or_else(|| item.text(Some(DC), "date"));
+      hashed_id(&[title.as_deref(), description.as_deref(), date.as_deref()])
+    });
+    insert(&mut object, "id", Some(id));
+    insert(&mut object, "url", link);
+    insert(&mut object, "title", title);
+    match item.text(Some(CONTENT), "encoded") {
+      Some(html) => {
+        insert(&mut object, "content_html", Some(html));
+        insert(&mut object, "summary", description);
+      },
+      None => insert(&mut object, "content_html", description)
+    }
+    let date = item.text(rss, "pubDate").map(|d| rfc3339(&d));
+    insert(&mut object, "date_published", date.or_else(|| item.text(Some(DC), "date")));
+    let authors = item
+      .all(rss, "author")
+      .chain(item.all(Some(DC), "creator"))
+      .map(|a| (a.element.text().trim().to_owned(), None));
+    self.insert_authors(&mut object, authors.collect());
+    let tags = item.all(rss, "category").map(|c| c.element.text().trim().to_owned());
+    self.insert_array(&mut object, "tags", tags.map(JsonValue::String).collect());
+    let attachments = item.all(rss, "enclosure").filter_map(|enclosure| {
+      let mut attachment = Map::new();
+      attachment.insert("url".to_owned(), enclosure.attr("url")?.into());
+      insert(&mut attachment, "mime_type", enclosure.attr("type"));
+      insert(
+        &mut attachment,
+        "size_in_bytes",
+        enclosure.attr("length").and_then(|l| l.trim().parse::<u64>().ok())
+      );
+      Some(JsonValue::Object(attachment))
+    });
+    self.insert_array(&mut object, "attachments", attachments.collect());
+    JsonValue::Object(object)
+  }
+
+  // JSON Feed of an Atom `feed`
+  fn read_atom(&self, feed: &Node) -> JsonValue {
+    let mut object = Map::new();
+    object.insert("version".to_owned(), VERSION.into());
+    insert(
+      &mut object,
+      "title",
+      Some(self.atom_text(feed, "title").map_or_else(String::new, |(t, _)| t))
+    );
+    insert(&mut object, "home_page_url", self.atom_link(feed, "alternate"));
+    insert(&mut object, "feed_url", self.atom_link(feed, "self"));
+    insert(&mut object, "description", self.atom_text(feed, "subtitle").map(|(t, _)| t));
+    insert(&mut object, "icon", feed.text(Some(ATOM), "logo"));
+    insert(&mut object, "favicon", feed.text(Some(ATOM), "icon"));
+    self.insert_authors(&mut object, self.atom_authors(feed));
+    insert(&mut object, "language", feed.attr("xml:lang"));
+    let items = feed.all(Some(ATOM), "entry").map(|e| self.read_atom_entry(&e)).collect::<Vec<_>>();
+    object.insert("items".to_owned(), JsonValue::Array(items));
+    JsonValue::Object(object)
+  }
+
+  // JSON Feed item of an Atom `entry`
+  fn read_atom_entry(&self, entry: &Node) -> JsonValue {
+    let mut object = Map::new();
+    insert(&mut object, "id", entry.text(Some(ATOM), "id"));
+    insert(&mut object, "url", self.atom_link(entry, "alternate"));
+    insert(&mut object, "external_url", self.atom_link(entry, "related"));
+    insert(&mut object, "title", self.atom_text(entry, "title").map(|(t, _)| t));
+    match self.atom_text(entry, "content") {
+      Some((html, true)) => insert(&mut object, "content_html", Some(html)),
+      Some((text, false)) => insert(&mut object, "content_text", Some(text)),
+      None => ()
+    }
+    insert(&mut object, "summary", self.atom_text(entry, "summary").map(|(t, _)| t));
+    insert(&mut object, "date_published", entry.text(Some(ATOM), "published"));
+    insert(&mut object, "date_modified", entry.text(Some(ATOM), "updated"));
+    self.insert_authors(&mut object, self.atom_authors(entry));
+    let tags = entry.all(Some(ATOM), "category").filter_map(|c| c.attr("term"));
+    self.insert_array(&mut object, "tags", tags.map(JsonValue::from).collect());
+    let attachments = entry
+      .all(Some(ATOM), "link")
+      .filter(|l| l.attr("rel") == Some("enclosure"))
+      .filter_map(|link| {
+        let mut attachment = Map::new();
+        attachment.insert("url".to_owned(), link.attr("href")?.into());
+        insert(&mut attachment, "mime_type", link.attr("type"));
+        insert(&mut attachment, "title", link.attr("title"));
+        insert(
+          &mut attachment,
+          "size_in_bytes",
+          link.attr("length").and_then(|l| l.trim().parse::<u64>().ok())
+        );
+        Some(JsonValue::Object(attachment))
+      });
+    self.insert_array(&mut object, "attachments", attachments.collect());
+    JsonValue::Object(object)
+  }
+
+  // Text of the Atom text construct `local` of `parent`, and whether it is HTML. XHTML is written
+  // as HTML. Content with a `src` or a media type has no text
+  fn atom_text(&self, parent: &Node, local: &str) -> Option<(String, bool)> {
+    let node = parent.first(Some(ATOM), local)?;
+    match node.attr("type").unwrap_or("text") {
+      _ if node.attr("src").is_some() => None,
+      "text" => Some((node.element.text().trim().to_owned(), false)),
+      "html" => Some((node.element.text().trim().to_owned(), true)),
+      "xhtml" => {
+        let mut html = String::new();
+        if let Some(div) = node.children().next() {
+          write_html(&mut html, &div.element.children);
+        }
+        Some((html.trim().to_owned(), true))
+      },
+      _ => None
+    }
+  }
+
+  // Address of the first Atom link of `parent` with relation `rel`
+  fn atom_link<'a>(&self, parent: &Node<'a>, rel: &str) -> Option<&'a str> {
+    parent
+      .all(Some(ATOM), "link")
+      .find(|l| l.attr("rel").unwrap_or("alternate") == rel)
+      .and_then(|l| l.attr("href"))
+  }
+
+  // Names and URIs of the Atom authors of `parent`
+  fn atom_authors(&self, parent: &Node) -> Vec<(String, Option<String>)> {
+    parent
+      .all(Some(ATOM), "author")
+      .map(|a| (a.text(Some(ATOM), "name").unwrap_or_default(), a.text(Some(ATOM), "uri")))
+      .collect()
+  }
+
+  // Insert JSON Feed `authors` of names and URLs, unless there are none
+  fn insert_authors(&self, object: &mut Map<String, JsonValue>, authors: Vec<(String, Option<String>)>) {
+    let authors = authors.into_iter().filter(|(name, _)| !name.is_empty()).map(|(name, url)| {
+      let mut author = Map::new();
+      author.insert("name".to_owned(), name.into());
+      insert(&mut author, "url", url);
+      JsonValue::Object(author)
+    });
+    self.insert_array(object, "authors", authors.collect());
+  }
+
+  // Insert array `values` under `key`, unless it is empty
+  fn insert_array(&self, object: &mut Map<String, JsonValue>, key: &str, values: Vec<JsonValue>) {
+    if !values.is_empty() {
+      object.insert(key.to_owned(), JsonValue::Array(values));
+    }
+  }
+
+  // RSS 2.0 document of a JSON Feed
+  fn write_rss(&self, feed: &Map<String, JsonValue>, items: &[&Map<String, JsonValue>]) -> Element {
+    let (mut content, mut dc, mut atom) = (false, false, false);
+    let mut channel = Element::new("channel");
+    let push = |element: &mut Element, child: Element| element.children.push(Content::Element(child));
+
+    push(&mut channel, Element::with_text("title", string(feed, "title").unwrap_or_default()));
+    if let Some(url) = string(feed, "home_page_url") {
+      push(&mut channel, Element::with_text("link", url));
+    }
+    push(
+      &mut channel,
+      Element::with_text("description", string(feed, "description").unwrap_or_default())
+    );
+    if let Some(url) = string(feed, "feed_url") {
+      atom = true;
+      let link = [("href", Some(url)), ("rel", Some("self")), ("type", Some("application/rss+xml"))];
+      push(&mut channel, empty_element("atom:link", &link));
+    }
+    if let Some(language) = string(feed, "language") {
+      push(&mut channel, Element::with_text("language", language));
+    }
+    for (name, _) in authors(feed) {
+      dc = true;
+      push(&mut channel, Element::with_text("dc:creator", name.unwrap_or_default()));
+    }
+    if let Some(url) = string(feed, "icon") {
+      let mut image = Element::new("image");
+      push(&mut image, Element::with_text("url", url));
+      push(&mut image, Element::with_text("title", string(feed, "title").unwrap_or_default()));
+      push(
+        &mut image,
+        Element::with_text("link", string(feed, "home_page_url").unwrap_or_default())
+      );
+      push(&mut channel, image);
+    }
+
+    for item in items {
+      let mut element = Element::new("item");
+      let url = string(item, "url");
+      if let Some(title) = string(item, "title") {
+        push(&mut element, Element::with_text("title", title));
+      }
+      if let Some(url) = url {
+        push(&mut element, Element::with_text("link", url));
+      }
+      let html = string(item, "content_html");
+      match (html, string(item, "content_text"), string(item, "summary")) {
+        (Some(html), _, Some(summary)) => {
+          content = true;
+          push(&mut element, Element::with_text("description", summary));
+          push(&mut element, html_element("content:encoded", html));
+        },
+        (Some(html), ..) => push(&mut element, html_element("description", html)),
+        (None, Some(text), _) | (None, None, Some(text)) => push(&mut element, Element::with_text("description", text)),
+        (None, None, None) => ()
+      }
+      if let Some(date) = string(item, "date_published") {
+        push(&mut element, Element::with_text("pubDate", rfc822(date)));
+      }
+      if let Some(id) = item.get("id").filter(|id| !id.is_null()) {
+        let id = utils::to_string_raw(id);
+        let mut guid = Element::with_text("guid", &id);
+        if url != Some(id.as_str()) {
+          guid.attrs.push(("isPermaLink".to_owned(), "false".to_owned()));
+        }
+        push(&mut element, guid);
+      }
+      for (name, _) in authors(item) {
+        dc = true;
+        push(&mut element, Element::with_text("dc:creator", name.unwrap_or_default()));
+      }
+      for tag in item.get("tags").and_then(JsonValue::as_array).into_iter().flatten() {
+        push(&mut element, Element::with_text("category", utils::to_string_raw(tag)));
+      }
+      for attachment in self.attachments(item) {
+        let length = attachment.get("size_in_bytes").map(utils::to_string_raw);
+        let attrs = [
+          ("url", string(attachment, "url")),
+          ("length", length.as_deref()),
+          ("type", string(attachment, "mime_type"))
+        ];
+        push(&mut element, empty_element("enclosure", &attrs));
+      }
+      push(&mut channel, element);
+    }
+
+    let mut rss = Element::new("rss");
+    rss.attrs.push(("version".to_owned(), "2.0".to_owned()));
+    let modules = [(content, "content", CONTENT), (dc, "dc", DC), (atom, "atom", ATOM)];
+    for (_, prefix, uri) in modules.iter().filter(|(used, ..)| *used) {
+      rss.attrs.push((format!("xmlns:{}", prefix), (*uri).to_owned()));
+    }
+    push(&mut rss, channel);
+    rss
+  }
+
+  // Atom 1.0 document of a JSON Feed
+  fn write_atom(&self, feed: &Map<String, JsonValue>, items: &[&Map<String, JsonValue>]) -> Element {
+    let mut element = Element::new("feed");
+    let push = |element: &mut Element, child: Element| element.children.push(Content::Element(child));
+    let link = |rel: &str, href: &str| empty_element("link", &[("rel", Some(rel)), ("href", Some(href))]);
+    let author = |(name, url): (Option<&str>, Option<&str>)| {
+      let mut author = Element::new("author");
+      push(&mut author, Element::with_text("name", name.unwrap_or_default()));
+      if let Some(url) = url {
+        push(&mut author, Element::with_text("uri", url));
+      }
+      author
+    };
+
+    element.attrs.push(("xmlns".to_owned(), ATOM.to_owned()));
+    if let Some(language) = string(feed, "language") {
+      element.attrs.push(("xml:lang".to_owned(), language.to_owned()));
+    }
+    push(&mut element, Element::with_text("title", string(feed, "title").unwrap_or_default()));
+    if let Some(description) = string(feed, "description") {
+      push(&mut element, Element::with_text("subtitle", description));
+    }
+    let home_page_url = string(feed, "home_page_url");
+    let feed_url = string(feed, "feed_url");
+    if let Some(url) = home_page_url {
+      push(&mut element, link("alternate", url));
+    }
+    if let Some(url) = feed_url {
+      push(&mut element, link("self", url));
+    }
+    let id = feed_url
+      .or(home_page_url)
+      .map_or_else(|| hashed_id(&[string(feed, "title"), string(feed, "description")]), str::to_owned);
+    push(&mut element, Element::with_text("id", id));
+    // The feed was last updated with its latest item, or now when no item has a date
+    let updated = items
+      .iter()
+      .filter_map(|item| string(item, "date_modified").or_else(|| string(item, "date_published")))
+      .filter_map(|date| Some((timestamp(date)?, date)))
+      .max_by_key(|(timestamp, _)| *timestamp)
+      .map_or_else(now, |(_, date)| date.to_owned());
+    push(&mut element, Element::with_text("updated", &updated));
+    if let Some(favicon) = string(feed, "favicon") {
+      push(&mut element, Element::with_text("icon", favicon));
+    }
+    if let Some(icon) = string(feed, "icon") {
+      push(&mut element, Element::with_text("logo", icon));
+    }
+    for a in authors(feed) {
+      push(&mut element, author(a));
+    }
+
+    for item in items {
+      let mut entry = Element::new("entry");
+      let id = match item.get("id").filter(|id| !id.is_null()) {
+        Some(id) => utils::to_string_raw(id),
+        None => string(item, "url").or_else(|| string(item, "external_url")).map_or_else(
+          || {
+            let content = string(item, "content_html").or_else(|| string(item, "content_text"));
+            hashed_id(&[string(item, "title"), content, string(item, "date_published")])
+          },
+          str::to_owned
+        )
+      };
+      push(&mut entry, Element::with_text("id", id));
+      if let Some(title) = string(item, "title") {
+        push(&mut entry, Element::with_text("title", title));
+      }
+      if let Some(url) = string(item, "url") {
+        push(&mut entry, link("alternate", url));
+      }
+      if let Some(url) = string(item, "external_url") {
+        push(&mut entry, link("related", url));
+      }
+      let published = string(item, "date_published");
+      if let Some(date) = published {
+        push(&mut entry, Element::with_text("published", date));
+      }
+      let date = string(item, "date_modified").or(published).unwrap_or(&updated);
+      push(&mut entry, Element::with_text("updated", date));
+      for a in authors(item) {
+        push(&mut entry, author(a));
+      }
+      for tag in item.get("tags").and_then(JsonValue::as_array).into_iter().flatten() {
+        let term = utils::to_string_raw(tag);
+        push(&mut entry, empty_element("category", &[("term", Some(&term))]));
+      }
+      for attachment in self.attachments(item) {
+        let length = attachment.get("size_in_bytes").map(utils::to_string_raw);
+        let attrs = [
+          ("rel", Some("enclosure")),
+          ("href", string(attachment, "url")),
+          ("type", string(attachment, "mime_type")),
+          ("length", length.as_deref()),
+          ("title", string(attachment, "title"))
+        ];
+        push(&mut entry, empty_element("link", &attrs));
+      }
+      if let Some(summary) = string(item, "summary") {
+        push(&mut entry, Element::with_text("summary", summary));
+      }
+      if let Some(html) = string(item, "content_html") {
+        let mut content = html_element("content", html);
+        content.attrs.push(("type".to_owned(), "html".to_owned()));
+        push(&mut entry, content);
+      } else if let Some(text) = string(item, "content_text") {
+        push(&mut entry, Element::with_text("content", text));
+      }
+      push(&mut element, entry);
+    }
+    element
+  }
+
+  // Attachment objects of a JSON Feed item
+  fn attachments<'a>(&self, item: &'a Map<String, JsonValue>) -> impl Iterator<Item = &'a Map<String, JsonValue>> {
+    item
+      .get("attachments")
+      .and_then(JsonValue::as_array)
+      .into_iter()
+      .flatten()
+      .filter_map(JsonValue::as_object)
+  }
+}
+
+impl Convention for JsonFeed {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let root = Node::new(document_element(nodes)?, &[]);
+    if root.is(None, "rss") {
+      let channel = root.first(None, "channel");
+      let channel = channel.ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a <channel> in <rss>."))?;
+      Ok(self.read_rss(&channel, &channel))
+    } else if root.is(Some(RDF), "RDF") {
+      let channel = root.children().find(|c| c.element.local_name() == "channel");
+      let channel = channel.ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a <channel> in <rdf:RDF>."))?;
+      Ok(self.read_rss(&channel, &root))
+    } else if root.is(Some(ATOM), "feed") {
+      Ok(self.read_atom(&root))
+    } else {
+      Err(Error::new(ErrorKind::Syntax, "Expected an RSS or Atom document element."))
+    }
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let feed = json
+      .as_object()
+      .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a JSON Feed object."))?;
+    let items = match feed.get("items") {
+      Some(JsonValue::Array(items)) => items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+          item.as_object().ok_or_else(|| {
+            let path = utils::json_pointer("/items", &i.to_string());
+            Error::new(ErrorKind::Syntax, format!("Expected an item object at {}.", path))
+          })
+        })
+        .collect::<Result<Vec<_>, _>>()?,
+      None | Some(JsonValue::Null) => Vec::new(),
+      Some(_) => return Err(Error::new(ErrorKind::Syntax, "Expected an array of items at /items."))
+    };
+
+    let element = match self.format {
+      FeedFormat::RSS => self.write_rss(feed, &items),
+      FeedFormat::Atom => self.write_atom(feed, &items)
+    };
+    Ok(vec![Content::Element(element)])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+  use serde_json::json;
+
+  // Shortened from the sample of the RSS 2.0 specification, with RSS modules added
+  const RSS: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>Liftoff News</title>
+    <link>http://liftoff.msfc.nasa.gov/</link>
+    <atom:link href="http://liftoff.msfc.nasa.gov/rss.xml" rel="self" type="application/rss+xml"/>
+    <description>Liftoff to Space Exploration.</description>
+    <language>en-us</language>
+    <dc:creator>Jane Doe</dc:creator>
+    <item>
+      <title>Star City</title>
+      <link>http://liftoff.msfc.nasa.gov/news/2003/news-starcity.asp</link>
+      <description>How do Americans get ready to work with Russians?</description>
+      <content:encoded><![CDATA[<p>How do Americans get ready to work with <a href="http://howe.iki.rssi.ru/GCTC/gctc_e.htm">Russians</a>?</p>]]></content:encoded>
+      <pubDate>Tue, 03 Jun 2003 09:39:21 GMT</pubDate>
+      <guid>http://liftoff.msfc.nasa.gov/2003/06/03.html#item573</guid>
+      <dc:creator>John Doe</dc:creator>
+      <category>Space</category>
+      <enclosure url="http://liftoff.msfc.nasa.gov/media/starcity.mp3" length="12216320" type="audio/mpeg"/>
+    </item>
+    <item>
+      <description>Sky watchers will experience a &lt;a href="http://science.nasa.gov/"&gt;partial eclipse of the Sun&lt;/a&gt; on Saturday.</description>
+      <pubDate>Fri, 30 May 2003 11:06:42 -0400</pubDate>
+      <guid isPermaLink="false">tag:liftoff,2003:572</guid>
+    </item>
+  </channel>
+</rss>"#;
+
+  // The extensive example of RFC 4287
+  const ATOM_FEED: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title type="text">dive into mark</title>
+  <subtitle type="html">A &lt;em&gt;lot&lt;/em&gt; of effort went into making this effortless</subtitle>
+  <updated>2005-07-31T12:29:29Z</updated>
+  <id>tag:example.org,2003:3</id>
+  <link rel="alternate" type="text/html" hreflang="en" href="http://example.org/"/>
+  <link rel="self" type="application/atom+xml" href="http://example.org/feed.atom"/>
+  <rights>Copyright (c) 2003, Mark Pilgrim</rights>
+  <entry>
+    <title>Atom draft-07 snapshot</title>
+    <link rel="alternate" type="text/html" href="http://example.org/2005/04/02/atom"/>
+    <link rel="enclosure" type="audio/mpeg" length="1337" href="http://example.org/audio/ph34r_my_podcast.mp3"/>
+    <id>tag:example.org,2003:3.2397</id>
+    <updated>2005-07-31T12:29:29Z</updated>
+    <published>2003-12-13T08:29:29-04:00</published>
+    <author>
+      <name>Mark Pilgrim</name>
+      <uri>http://example.org/</uri>
+      <email>f8dy@example.com</email>
+    </author>
+    <content type="xhtml" xml:lang="en" xml:base="http://diveintomark.org/">
+      <div xmlns="http://www.w3.org/1999/xhtml">
+        <p><i>[Update: The Atom draft is finished.]</i><br/>R&amp;D</p>
+      </div>
+    </content>
+  </entry>
+</feed>"#;
+
+  fn to_json(xml: &str) -> Result<JsonValue, Error> {
+    JsonConfig::new().convention(JsonFeed::new()).finalize().build_from_xml(xml)
+  }
+
+  fn to_xml(format: FeedFormat, json: &JsonValue) -> String {
+    let mut builder = XmlConfig::new().convention(JsonFeed::new().format(format)).finalize();
+    builder.build_from_json(json).unwrap()
+  }
+
+  fn rss_feed() -> JsonValue {
+    json!({
+      "version": "https://jsonfeed.org/version/1.1",
+      "title": "Liftoff News",
+      "home_page_url": "http://liftoff.msfc.nasa.gov/",
+      "feed_url": "http://liftoff.msfc.nasa.gov/rss.xml",
+      "description": "Liftoff to Space Exploration.",
+      "authors": [{"name": "Jane Doe"}],
+      "language": "en-us",
+      "items": [
+        {
+          "id": "http://liftoff.msfc.nasa.gov/2003/06/03.html#item573",
+          "url": "http://liftoff.msfc.nasa.gov/news/2003/news-starcity.asp",
+          "title": "Star City",
+          "content_html": "<p>How do Americans get ready to work with <a href=\"http://howe.iki.rssi.ru/GCTC/gctc_e.htm\">Russians</a>?</p>",
+          "summary": "How do Americans get ready to work with Russians?",
+          "date_published": "2003-06-03T09:39:21Z",
+          "authors": [{"name": "John Doe"}],
+          "tags": ["Space"],
+          "attachments": [{"url": "http://liftoff.msfc.nasa.gov/media/starcity.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 12216320}]
+        },
+        {
+          "id": "tag:liftoff,2003:572",
+          "content_html": "Sky watchers will experience a <a href=\"http://science.nasa.gov/\">partial eclipse of the Sun</a> on Saturday.",
+          "date_published": "2003-05-30T11:06:42-04:00"
+        }
+      ]
+    })
+  }
+
+  #[test]
+  fn dates() {
+    assert_eq!(rfc3339("Sat, 07 Sep 2002 00:00:01 GMT"), "2002-09-07T00:00:01Z");
+    assert_eq!(rfc3339("7 Sep 02 09:30 EST"), "2002-09-07T09:30:00-05:00");
+    assert_eq!(rfc3339("Tue, 10 Jun 2003 04:00:00 +0530"), "2003-06-10T04:00:00+05:30");
+    assert_eq!(rfc3339("yesterday"), "yesterday");
+    assert_eq!(rfc3339("Tue, 03 Jun 2003 09:39:21 +००००"), "Tue, 03 Jun 2003 09:39:21 +००००");
+
+    assert_eq!(rfc822("2002-09-07T00:00:01Z"), "Sat, 07 Sep 2002 00:00:01 GMT");
+    assert_eq!(rfc822("2000-02-29T23:59:59.5+05:30"), "Tue, 29 Feb 2000 23:59:59 +0530");
+    assert_eq!(rfc822("1969-12-31T12:00:00-08:00"), "Wed, 31 Dec 1969 12:00:00 -0800");
+    assert_eq!(rfc822("2003-13-01T00:00:00Z"), "2003-13-01T00:00:00Z");
+    assert_eq!(rfc822("2003-12-13T08:29:29+००:००"), "2003-12-13T08:29:29+००:००");
+
+    assert_eq!(timestamp("1970-01-01T00:00:00Z"), Some(0));
+    assert_eq!(timestamp("2003-12-13T08:29:29-04:00"), timestamp("2003-12-13T12:29:29Z"));
+    assert_eq!(timestamp("2000-02-29T23:59:59.5+05:30"), Some(951_848_999));
+    assert_eq!(timestamp("yesterday"), None);
+    assert_eq!(timestamp("2003-12-13T08:29:29+००:००"), None);
+    for days in [-719_468, -1, 0, 11_016, 19_782, 2_932_896].iter() {
+      let (year, month, day) = civil_from_days(*days);
+      assert_eq!(days_from_civil(year, month, day), *days);
+    }
+    assert!(RFC3339_RE.is_match(&now()));
+  }
+
+  #[test]
+  fn read_rss() {
+    let json = to_json(RSS).unwrap();
+    assert_eq!(json, rss_feed());
+    // Members are in JSON Feed order
+    assert_eq!(json.to_string(), rss_feed().to_string());
+
+    let rdf = r#"<rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns="http://purl.org/rss/1.0/" xmlns:dc="http://purl.org/dc/elements/1.1/"><channel rdf:about="http://xml.com/xml/news.rss"><title>XML.com</title><link>http://xml.com/pub</link><description>XML.com features</description></channel><image rdf:about="http://xml.com/logo.gif"><url>http://xml.com/logo.gif</url></image><item rdf:about="http://xml.com/pub/2000/08/09/xslt/xslt.html"><title>Processing Inclusions with XSLT</title><link>http://xml.com/pub/2000/08/09/xslt/xslt.html</link><dc:date>2000-08-09T12:00:00Z</dc:date></item></rdf:RDF>"#;
+    assert_eq!(
+      to_json(rdf).unwrap(),
+      json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "XML.com",
+        "home_page_url": "http://xml.com/pub",
+        "description": "XML.com features",
+        "icon": "http://xml.com/logo.gif",
+        "items": [{
+          "id": "http://xml.com/pub/2000/08/09/xslt/xslt.html",
+          "url": "http://xml.com/pub/2000/08/09/xslt/xslt.html",
+          "title": "Processing Inclusions with XSLT",
+          "date_published": "2000-08-09T12:00:00Z"
+        }]
+      })
+    );
+  }
+
+  #[test]
+  fn read_atom() {
+    assert_eq!(
+      to_json(ATOM_FEED).unwrap(),
+      json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": "dive into mark",
+        "home_page_url": "http://example.org/",
+        "feed_url": "http://example.org/feed.atom",
+        "description": "A <em>lot</em> of effort went into making this effortless",
+        "items": [{
+          "id": "tag:example.org,2003:3.2397",
+          "url": "http://example.org/2005/04/02/atom",
+          "title": "Atom draft-07 snapshot",
+          "content_html": "<p><i>[Update: The Atom draft is finished.]</i><br>R&amp;D</p>",
+          "date_published": "2003-12-13T08:29:29-04:00",
+          "date_modified": "2005-07-31T12:29:29Z",
+          "authors": [{"name": "Mark Pilgrim", "url": "http://example.org/"}],
+          "attachments": [{"url": "http://example.org/audio/ph34r_my_podcast.mp3", "mime_type": "audio/mpeg", "size_in_bytes": 1337}]
+        }]
+      })
+    );
+  }
+
+  #[test]
+  fn write_rss() {
+    let xml = to_xml(FeedFormat::RSS, &rss_feed());
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/" xmlns:dc="http://purl.org/dc/elements/1.1/" xmlns:atom="http://www.w3.org/2005/Atom"><channel><title>Liftoff News</title><link>http://liftoff.msfc.nasa.gov/</link><description>Liftoff to Space Exploration.</description><atom:link href="http://liftoff.msfc.nasa.gov/rss.xml" rel="self" type="application/rss+xml"/><language>en-us</language><dc:creator>Jane Doe</dc:creator><item><title>Star City</title><link>http://liftoff.msfc.nasa.gov/news/2003/news-starcity.asp</link><description>How do Americans get ready to work with Russians?</description><content:encoded><![CDATA[<p>How do Americans get ready to work with <a href="http://howe.iki.rssi.ru/GCTC/gctc_e.htm">Russians</a>?</p>]]></content:encoded><pubDate>Tue, 03 Jun 2003 09:39:21 GMT</pubDate><guid isPermaLink="false">http://liftoff.msfc.nasa.gov/2003/06/03.html#item573</guid><dc:creator>John Doe</dc:creator><category>Space</category><enclosure url="http://liftoff.msfc.nasa.gov/media/starcity.mp3" length="12216320" type="audio/mpeg"/></item><item><description><![CDATA[Sky watchers will experience a <a href="http://science.nasa.gov/">partial eclipse of the Sun</a> on Saturday.]]></description><pubDate>Fri, 30 May 2003 11:06:42 -0400</pubDate><guid isPermaLink="false">tag:liftoff,2003:572</guid></item></channel></rss>"#
+    );
+    assert_eq!(to_json(&xml).unwrap(), rss_feed());
+
+    let feed = json!({"items": [{"id": "1", "url": "1", "content_text": "a < b", "summary": "s"}]});
+    assert_eq!(
+      to_xml(FeedFormat::RSS, &feed),
+      r#"<?xml version="1.0"?><rss version="2.0"><channel><title/><description/><item><link>1</link><description>a &lt; b</description><guid>1</guid></item></channel></rss>"#
+    );
+  }
+
+  #[test]
+  fn write_atom() {
+    let (json, xml) = roundtrip(JsonFeed::new().format(FeedFormat::Atom), ATOM_FEED);
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"><title>dive into mark</title><subtitle>A &lt;em&gt;lot&lt;/em&gt; of effort went into making this effortless</subtitle><link rel="alternate" href="http://example.org/"/><link rel="self" href="http://example.org/feed.atom"/><id>http://example.org/feed.atom</id><updated>2005-07-31T12:29:29Z</updated><entry><id>tag:example.org,2003:3.2397</id><title>Atom draft-07 snapshot</title><link rel="alternate" href="http://example.org/2005/04/02/atom"/><published>2003-12-13T08:29:29-04:00</published><updated>2005-07-31T12:29:29Z</updated><author><name>Mark Pilgrim</name><uri>http://example.org/</uri></author><link rel="enclosure" href="http://example.org/audio/ph34r_my_podcast.mp3" type="audio/mpeg" length="1337"/><content type="html"><![CDATA[<p><i>[Update: The Atom draft is finished.]</i><br>R&amp;D</p>]]></content></entry></feed>"#
+    );
+
+    // Subtitles are text, and HTML content round trips
+    let mut feed = json;
+    feed["description"] = json!("A <em>lot</em> of effort went into making this effortless");
+    assert_eq!(to_json(&xml).unwrap(), feed);
+
+    let feed = json!({"title": "T", "language": "en", "favicon": "f.ico", "icon": "i.png", "items": [{"id": 1, "content_text": "Hi", "tags": ["a"], "date_published": "2003-12-13T08:29:29Z"}]});
+    assert_eq!(
+      to_xml(FeedFormat::Atom, &feed),
+      r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom" xml:lang="en"><title>T</title><id>urn:uuid:6b9d9619-e0ee-8a7b-b170-5cf815554990</id><updated>2003-12-13T08:29:29Z</updated><icon>f.ico</icon><logo>i.png</logo><entry><id>1</id><published>2003-12-13T08:29:29Z</published><updated>2003-12-13T08:29:29Z</updated><category term="a"/><content>Hi</content></entry></feed>"#
+    );
+  }
+
+  #[test]
+  fn atom_fallbacks() {
+    // The feed is updated with the item updated last, whatever the offsets of the dates
+    let feed = json!({"home_page_url": "h", "items": [
+      {"url": "u", "date_published": "2003-12-13T08:29:29-04:00"},
+      {"external_url": "e", "date_modified": "2003-12-13T10:00:00Z"},
+      {"title": "t", "content_text": "c"},
+      {"date_published": "yesterday"}
+    ]});
+    assert_eq!(
+      to_xml(FeedFormat::Atom, &feed),
+      r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"><title/><link rel="alternate" href="h"/><id>h</id><updated>2003-12-13T08:29:29-04:00</updated><entry><id>u</id><link rel="alternate" href="u"/><published>2003-12-13T08:29:29-04:00</published><updated>2003-12-13T08:29:29-04:00</updated></entry><entry><id>e</id><link rel="related" href="e"/><updated>2003-12-13T10:00:00Z</updated></entry><entry><id>urn:uuid:dbe6b624-dcba-80de-a30c-9502d9cda7d1</id><title>t</title><updated>2003-12-13T08:29:29-04:00</updated><content>c</content></entry><entry><id>urn:uuid:c8530a3a-2acb-8a0d-b713-f76e669f7b38</id><published>yesterday</published><updated>yesterday</updated></entry></feed>"#
+    );
+
+    // Without dates the feed is updated now
+    let xml = to_xml(FeedFormat::Atom, &json!({"items": [{"id": "1"}]}));
+    let json = to_json(&xml).unwrap();
+    let updated = json["items"][0]["date_modified"].as_str().unwrap();
+    assert!(RFC3339_RE.is_match(updated));
+    assert!(xml.contains(&format!("<updated>{}</updated></entry>", updated)));
+  }
+
+  #[test]
+  fn rss_item_ids() {
+    let rss = r#"<rss version="2.0"><channel><title>T</title><item><title>A</title><description>x</description></item><item><title>A</title><description>y</description></item></channel></rss>"#;
+    let json = to_json(rss).unwrap();
+    let ids = json["items"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|i| i["id"].clone())
+      .collect::<Vec<_>>();
+    assert_eq!(ids, vec![
+      json!("urn:uuid:1337df50-4876-87d4-9998-5e7b8bda5b07"),
+      json!("urn:uuid:1be15a50-4d5e-867f-90ee-e37b86f25c5c")
+    ]);
+    assert_eq!(to_json(rss).unwrap(), json);
+  }
+
+  #[test]
+  fn non_ascii_digits() {
+    // Dates with digits of other scripts are kept as they are
+    let rss = r#"<rss version="2.0"><channel><title>T</title><item><guid>1</guid><pubDate>Tue, 03 Jun 2003 09:39:21 +००००</pubDate></item></channel></rss>"#;
+    let json = to_json(rss).unwrap();
+    assert_eq!(json["items"][0]["date_published"], "Tue, 03 Jun 2003 09:39:21 +००००");
+
+    let xml = to_xml(
+      FeedFormat::Atom,
+      &json!({"items": [{"id": "1", "date_published": "2003-12-13T08:29:29+००:००"}]})
+    );
+    assert!(xml.contains("<published>2003-12-13T08:29:29+००:००</published>"));
+  }
+
+  #[test]
+  fn invalid_feed() {
+    let error = to_json("<channel/>").unwrap_err();
+    assert_eq!(error.details(), "Expected an RSS or Atom document element.");
+
+    let error = to_json("<rss/>").unwrap_err();
+    assert_eq!(error.details(), "Expected a <channel> in <rss>.");
+
+    let mut builder = XmlConfig::new().convention(JsonFeed::new()).finalize();
+    let error = builder.build_from_json(&json!([])).unwrap_err();
+    assert_eq!(error.details(), "Expected a JSON Feed object.");
+
+    let error = builder.build_from_json(&json!({"items": {}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an array of items at /items.");
+
+    let error = builder.build_from_json(&json!({"items": [{}, 1]})).unwrap_err();
+    assert_eq!(error.details(), "Expected an item object at /items/1.");
+  }
+}
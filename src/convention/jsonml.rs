@@ -0,0 +1,172 @@
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  convention::{document_element, Convention},
+  error::{Error, ErrorKind},
+  tree::{Content, Element},
+  utils
+};
+
+/// The [JsonML](http://www.jsonml.org/) convention.
+///
+/// Elements are arrays of their name, an object of attributes when there are any, and their
+/// content in document order: text as strings and child elements as arrays. Mixed content and all
+/// whitespace are kept. Comments and processing instructions have no JsonML form and are left out.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{JsonConfig, JsonML, XmlConfig};
+///
+/// let xml = r#"<?xml version="1.0"?><p class="note">Some <b>bold</b> text</p>"#;
+/// let json = JsonConfig::new().convention(JsonML).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!(["p", {"class":"note"}, "Some ", ["b", "bold"], " text"]));
+///
+/// let mut builder = XmlConfig::new().convention(JsonML).finalize();
+/// assert_eq!(builder.build_from_json(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct JsonML;
+
+impl JsonML {
+  // JsonML array for an element
+  fn array(&self, element: &Element) -> JsonValue {
+    let mut array = vec![JsonValue::String(element.name.clone())];
+    if !element.attrs.is_empty() {
+      let attrs: Map<String, JsonValue> = element.attrs.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect();
+      array.push(JsonValue::Object(attrs));
+    }
+    // CDATA sections, and text around left out comments, are merged with the text before them
+    let mut after_text = false;
+    for child in &element.children {
+      match child {
+        Content::Element(e) => {
+          array.push(self.array(e));
+          after_text = false;
+        },
+        Content::Text(t) | Content::CData(t) => {
+          match array.last_mut() {
+            Some(JsonValue::String(last)) if after_text => last.push_str(t),
+            _ => array.push(JsonValue::String(t.clone()))
+          }
+          after_text = true;
+        },
+        _ => ()
+      }
+    }
+    JsonValue::Array(array)
+  }
+
+  // Build an element from its JsonML array at JSON pointer `path`
+  fn element(&self, path: &str, node: &JsonValue) -> Result<Element, Error> {
+    let expected = || match path {
+      "" => Error::new(ErrorKind::Syntax, "Expected a JsonML element."),
+      _ => Error::new(ErrorKind::Syntax, format!("Expected a JsonML element at {}.", path))
+    };
+    let array = node.as_array().ok_or_else(expected)?;
+    let name = array.first().and_then(JsonValue::as_str).ok_or_else(expected)?;
+
+    let mut element = Element::new(name);
+    let mut children = array.iter().enumerate().skip(1).peekable();
+    if let Some((i, JsonValue::Object(attrs))) = children.peek() {
+      let path = utils::json_pointer(path, &i.to_string());
+      for (key, value) in attrs {
+        match value {
+          JsonValue::Object(_) | JsonValue::Array(_) => {
+            let path = utils::json_pointer(&path, key);
+            return Err(Error::new(
+              ErrorKind::Syntax,
+              format!("Expected attribute to be a string at {}.", path)
+            ));
+          },
+          JsonValue::Null => (),
+          _ => element.attrs.push((key.clone(), utils::to_string_raw(value)))
+        }
+      }
+      children.next();
+    }
+
+    for (i, child) in children {
+      let path = utils::json_pointer(path, &i.to_string());
+      match child {
+        JsonValue::Array(_) => element.children.push(Content::Element(self.element(&path, child)?)),
+        JsonValue::Object(_) => {
+          return Err(Error::new(ErrorKind::Syntax, format!("Expected content at {}.", path)));
+        },
+        JsonValue::Null => (),
+        _ => element.children.push(Content::Text(utils::to_string_raw(child)))
+      }
+    }
+    Ok(element)
+  }
+}
+
+impl Convention for JsonML {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    Ok(self.array(document_element(nodes)?))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    Ok(vec![Content::Element(self.element("", json)?)])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, xml::XmlConfig};
+  use serde_json::json;
+
+  fn to_xml(json: &JsonValue) -> String {
+    let mut builder = XmlConfig::new().convention(JsonML).finalize();
+    builder.build_from_json(json).unwrap()
+  }
+
+  #[test]
+  fn elements_and_attributes() {
+    let xml = r#"<?xml version="1.0"?><ul><li style="color:red">First Item</li><li title="Some hover text." style="color:green">Second Item</li><li><span class="code-example-third">Third</span> Item</li></ul>"#;
+    let (json, output) = roundtrip(JsonML, xml);
+    assert_eq!(
+      json,
+      json!(["ul",
+        ["li", {"style":"color:red"}, "First Item"],
+        ["li", {"title":"Some hover text.", "style":"color:green"}, "Second Item"],
+        ["li", ["span", {"class":"code-example-third"}, "Third"], " Item"]
+      ])
+    );
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn whitespace_and_order() {
+    let xml = "<?xml version=\"1.0\"?><a>\n  <b/>text<c x=\"1\"/>\n</a>";
+    let (json, output) = roundtrip(JsonML, xml);
+    assert_eq!(json, json!(["a", "\n  ", ["b"], "text", ["c", {"x":"1"}], "\n"]));
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn comments_and_cdata() {
+    let (json, output) = roundtrip(JsonML, "<a><!-- b --><![CDATA[<c>]]><d/>e<![CDATA[f]]><!-- g -->h</a>");
+    assert_eq!(json, json!(["a", "<c>", ["d"], "efh"]));
+    assert_eq!(output, r#"<?xml version="1.0"?><a>&lt;c&gt;<d/>efh</a>"#);
+  }
+
+  #[test]
+  fn scalars() {
+    let xml = to_xml(&json!(["a", {"n":1,"b":true,"z":null}, 2, null, false]));
+    assert_eq!(xml, r#"<?xml version="1.0"?><a n="1" b="true">2false</a>"#);
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut builder = XmlConfig::new().convention(JsonML).finalize();
+    let error = builder.build_from_json(&json!({"a":[]})).unwrap_err();
+    assert_eq!(error.details(), "Expected a JsonML element.");
+
+    let error = builder.build_from_json(&json!(["a", ["b", [1]]])).unwrap_err();
+    assert_eq!(error.details(), "Expected a JsonML element at /1/1.");
+
+    let error = builder.build_from_json(&json!(["a", "b", {"c":"d"}])).unwrap_err();
+    assert_eq!(error.details(), "Expected content at /2.");
+  }
+}
@@ -0,0 +1,226 @@
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::{
+  convention::{add, document_element, Convention},
+  error::Error,
+  tree::{Content, Element},
+  utils
+};
+
+/// The Parker convention, for compact JSON.
+///
+/// The document element and all attributes are left out. Elements with text become its value,
+/// typed as a number or boolean when the text is a JSON literal, and empty elements become `null`.
+/// Repeated elements are arrays, and an element whose children all have the same name is an array
+/// of their values. Text is ignored in elements with child elements.
+///
+/// Building XML is lossy: the document element is named `root_name`, and the values of arrays
+/// which aren't under an object key are named `item_name`.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{JsonConfig, Parker, XmlConfig};
+///
+/// let xml = r#"<?xml version="1.0"?><order id="7"><total>9.5</total><paid>true</paid><note/></order>"#;
+/// let json = JsonConfig::new().convention(Parker::new()).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!({"total":9.5,"paid":true,"note":null}));
+///
+/// let mut builder = XmlConfig::new().convention(Parker::new().root_name("order")).finalize();
+/// assert_eq!(
+///   builder.build_from_json(&json).unwrap(),
+///   r#"<?xml version="1.0"?><order><total>9.5</total><paid>true</paid><note/></order>"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Parker {
+  root_name: String,
+  item_name: String
+}
+
+impl Parker {
+  /// Initialize the Parker convention
+  pub fn new() -> Parker {
+    Parker {
+      root_name: "root".to_owned(),
+      item_name: "item".to_owned()
+    }
+  }
+
+  /// Name of the document element written by the XmlBuilder.
+  ///
+  /// (`"root"` by default)
+  pub fn root_name<T: Into<String>>(mut self, name: T) -> Parker {
+    self.root_name = name.into();
+    self
+  }
+
+  /// Name of the elements written for the values of arrays nested in arrays, or of a document
+  /// array.
+  ///
+  /// (`"item"` by default)
+  pub fn item_name<T: Into<String>>(mut self, name: T) -> Parker {
+    self.item_name = name.into();
+    self
+  }
+
+  // JSON value of an element
+  fn value(&self, element: &Element) -> JsonValue {
+    let children: Vec<&Element> = element
+      .children
+      .iter()
+      .filter_map(|c| match c {
+        Content::Element(e) => Some(e),
+        _ => None
+      })
+      .collect();
+
+    if children.is_empty() {
+      let text = element.text();
+      return if text.trim().is_empty() { JsonValue::Null } else { typed(&text) };
+    }
+    if children.len() > 1 && children.iter().all(|c| c.name == children[0].name) {
+      return JsonValue::Array(children.iter().map(|c| self.value(c)).collect());
+    }
+
+    let mut object = Map::new();
+    for child in children {
+      let value = self.value(child);
+      add(&mut object, child.name.clone(), value, &[]);
+    }
+    JsonValue::Object(object)
+  }
+
+  // Build an element named `name` from its JSON value
+  fn element(&self, name: &str, node: &JsonValue) -> Element {
+    let mut element = Element::new(name);
+    match node {
+      JsonValue::Object(object) => {
+        for (key, value) in object {
+          match value {
+            JsonValue::Array(array) => {
+              for item in array {
+                element.children.push(Content::Element(self.element(key, item)));
+              }
+            },
+            _ => element.children.push(Content::Element(self.element(key, value)))
+          }
+        }
+      },
+      JsonValue::Array(array) => {
+        for item in array {
+          element.children.push(Content::Element(self.element(&self.item_name, item)));
+        }
+      },
+      JsonValue::Null => (),
+      _ => element.children.push(Content::Text(utils::to_string_raw(node)))
+    }
+    element
+  }
+}
+
+impl Default for Parker {
+  fn default() -> Parker {
+    Parker::new()
+  }
+}
+
+// Typed value of text: a number or boolean if the text is written as its JSON literal. Numbers
+// which can't be stored exactly, such as `1e5` or very large integers, are kept as strings
+fn typed(text: &str) -> JsonValue {
+  match text {
+    "true" => return JsonValue::Bool(true),
+    "false" => return JsonValue::Bool(false),
+    _ => ()
+  }
+  match serde_json::from_str::<Number>(text) {
+    Ok(number) if number.to_string() == text => JsonValue::Number(number),
+    _ => JsonValue::String(text.to_owned())
+  }
+}
+
+impl Convention for Parker {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    Ok(self.value(document_element(nodes)?))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    Ok(vec![Content::Element(self.element(&self.root_name, json))])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+  use serde_json::json;
+
+  fn to_json(xml: &str) -> JsonValue {
+    JsonConfig::new().convention(Parker::new()).finalize().build_from_xml(xml).unwrap()
+  }
+
+  fn to_xml(json: &JsonValue) -> String {
+    let mut builder = XmlConfig::new().convention(Parker::new()).finalize();
+    builder.build_from_json(json).unwrap()
+  }
+
+  #[test]
+  fn root_is_absorbed() {
+    assert_eq!(to_json("<root><a>x</a><b>y</b></root>"), json!({"a":"x","b":"y"}));
+    assert_eq!(to_json("<root>text</root>"), json!("text"));
+  }
+
+  #[test]
+  fn attributes_are_ignored() {
+    assert_eq!(to_json(r#"<root a="1"><b c="2">d</b></root>"#), json!({"b":"d"}));
+  }
+
+  #[test]
+  fn typed_values() {
+    let xml = r#"<?xml version="1.0"?><root><a>1</a><b>-2.5</b><c>true</c><d>false</d><e>007</e><f>1e5</f><g>1 </g></root>"#;
+    let (json, output) = roundtrip(Parker::new(), xml);
+    assert_eq!(json, json!({"a":1,"b":-2.5,"c":true,"d":false,"e":"007","f":"1e5","g":"1 "}));
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn empty_elements() {
+    assert_eq!(to_json("<root><a/><b> </b></root>"), json!({"a":null,"b":null}));
+  }
+
+  #[test]
+  fn arrays() {
+    assert_eq!(
+      to_json("<root><item>1</item><item>2</item><item>three</item></root>"),
+      json!([1, 2, "three"])
+    );
+    assert_eq!(
+      to_json("<root><a>1</a><a>2</a><b><c>3</c><c>4</c></b><d/></root>"),
+      json!({"a":[1,2],"b":[3,4],"d":null})
+    );
+  }
+
+  #[test]
+  fn mixed_content() {
+    assert_eq!(to_json("<root>text<a>1</a>more</root>"), json!({"a":1}));
+  }
+
+  #[test]
+  fn build_xml() {
+    assert_eq!(
+      to_xml(&json!({"a":[1,2],"b":{"c":true},"d":null})),
+      r#"<?xml version="1.0"?><root><a>1</a><a>2</a><b><c>true</c></b><d/></root>"#
+    );
+    assert_eq!(
+      to_xml(&json!([1, [2, 3]])),
+      r#"<?xml version="1.0"?><root><item>1</item><item><item>2</item><item>3</item></item></root>"#
+    );
+
+    let mut builder = XmlConfig::new()
+      .convention(Parker::new().root_name("list").item_name("entry"))
+      .finalize();
+    assert_eq!(
+      builder.build_from_json(&json!(["x"])).unwrap(),
+      r#"<?xml version="1.0"?><list><entry>x</entry></list>"#
+    );
+  }
+}
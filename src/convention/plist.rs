@@ -0,0 +1,325 @@
+use regex::Regex;
+use serde_json::{json, Map, Number, Value as JsonValue};
+
+use crate::{
+  convention::{document_element, tagged, Convention},
+  error::{Error, ErrorKind},
+  tree::{Content, Element}
+};
+
+// Doctype of property lists
+const DOCTYPE: &str = r#"plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd""#;
+
+// Types of tagged values
+const TAGGED: [&str; 2] = ["date", "data"];
+
+lazy_static! {
+  static ref DATE_RE: Regex = Regex::new(r"^[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}Z$").unwrap();
+  static ref BASE64_RE: Regex = Regex::new(r"^(?:[A-Za-z0-9+/]{4})*(?:[A-Za-z0-9+/]{2}==|[A-Za-z0-9+/]{3}=)?$").unwrap();
+}
+
+// Type name and text of an object holding a tagged value whose text is a valid date or Base64
+fn tagged_value(object: &Map<String, JsonValue>) -> Option<(&str, &str)> {
+  tagged(object, &TAGGED).filter(|(name, text)| match *name {
+    "date" => DATE_RE.is_match(text),
+    _ => BASE64_RE.is_match(text)
+  })
+}
+
+/// The Apple property list format.
+///
+/// `<dict>` is an object, `<array>` an array, `<integer>` and `<real>` numbers and `<true/>` and
+/// `<false/>` booleans and `<string>` strings. `<date>` and `<data>` are tagged objects of their
+/// text, with the whitespace of `<data>` removed from its Base64 text:
+/// `{"$date": "1965-05-12T00:00:00Z"}` and `{"$data": "PEKBpYGlmYFCPA=="}`.
+///
+/// Any JSON value builds a valid property list, with the plist doctype. Strings are always written
+/// as `<string>`, integers as `<integer>`, other numbers as `<real>` and tagged objects back as
+/// their type, so a dictionary with a single entry named `$date` holding a plist date
+/// `YYYY-MM-DDTHH:MM:SSZ`, or `$data` holding Base64 text, is written as that type. Other values
+/// of these entries are written as a `<dict>`. `null` has no plist form and is left out, a `null`
+/// document being an empty `<dict>`.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{JsonConfig, Plist, XmlConfig};
+///
+/// let xml = r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd"><plist version="1.0"><dict><key>Name</key><string>John Doe</string><key>Year Of Birth</key><integer>1965</integer><key>Pets</key><array/></dict></plist>"#;
+/// let json = JsonConfig::new().convention(Plist).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!({"Name": "John Doe", "Year Of Birth": 1965, "Pets": []}));
+///
+/// let mut builder = XmlConfig::new().convention(Plist).finalize();
+/// assert_eq!(builder.build_from_json(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Plist;
+
+impl Plist {
+  // JSON value of a plist value element
+  fn value(&self, element: &Element) -> Result<JsonValue, Error> {
+    let invalid = |kind: &str| Error::new(ErrorKind::Syntax, format!("Invalid {} \"{}\".", kind, element.text().trim()));
+
+    Ok(match element.name.as_str() {
+      "dict" => {
+        let mut object = Map::new();
+        let mut children = element.elements();
+        while let Some(key) = children.next() {
+          if key.name != "key" {
+            return Err(Error::new(
+              ErrorKind::Syntax,
+              format!("Expected <key> in <dict>, found <{}>.", key.name)
+            ));
+          }
+          let key = key.text();
+          let value = children
+            .next()
+            .ok_or_else(|| Error::new(ErrorKind::Syntax, format!("Expected a value for key \"{}\".", key)))?;
+          object.insert(key, self.value(value)?);
+        }
+        JsonValue::Object(object)
+      },
+      "array" => JsonValue::Array(element.elements().map(|e| self.value(e)).collect::<Result<_, _>>()?),
+      "string" => element.text().into(),
+      "date" => json!({"$date": element.text().trim()}),
+      "data" => json!({"$data": element.text().split_whitespace().collect::<String>()}),
+      "integer" => {
+        let text = element.text();
+        let text = text.trim();
+        match text.parse::<i64>() {
+          Ok(n) => n.into(),
+          Err(_) => text.parse::<u64>().map_err(|_| invalid("integer"))?.into()
+        }
+      },
+      "real" => {
+        let real = element.text().trim().parse::<f64>().map_err(|_| invalid("real"))?;
+        JsonValue::Number(Number::from_f64(real).ok_or_else(|| invalid("real"))?)
+      },
+      "true" => JsonValue::Bool(true),
+      "false" => JsonValue::Bool(false),
+      name => {
+        return Err(Error::new(ErrorKind::Syntax, format!("Unknown plist value <{}>.", name)));
+      }
+    })
+  }
+
+  // Plist value element of a JSON value. `null` has none
+  fn element(&self, value: &JsonValue) -> Option<Element> {
+    Some(match value {
+      JsonValue::Null => return None,
+      JsonValue::Bool(b) => Element::new(if *b { "true" } else { "false" }),
+      JsonValue::Number(n) if n.is_f64() => Element::with_text("real", n.to_string()),
+      JsonValue::Number(n) => Element::with_text("integer", n.to_string()),
+      JsonValue::String(s) => Element::with_text("string", s.clone()),
+      JsonValue::Array(array) => {
+        let mut element = Element::new("array");
+        element
+          .children
+          .extend(array.iter().filter_map(|v| self.element(v)).map(Content::Element));
+        element
+      },
+      JsonValue::Object(object) => match tagged_value(object) {
+        Some((name, text)) => Element::with_text(name, text),
+        None => {
+          let mut element = Element::new("dict");
+          for (key, value) in object {
+            if let Some(value) = self.element(value) {
+              element.children.push(Content::Element(Element::with_text("key", key.clone())));
+              element.children.push(Content::Element(value));
+            }
+          }
+          element
+        }
+      }
+    })
+  }
+}
+
+impl Convention for Plist {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let root = document_element(nodes)?;
+    if root.name != "plist" {
+      return Err(Error::new(ErrorKind::Syntax, "Expected a plist document element."));
+    }
+    let mut values = root.elements();
+    match (values.next(), values.next()) {
+      (Some(value), None) => self.value(value),
+      _ => Err(Error::new(ErrorKind::Syntax, "Expected a single value in <plist>."))
+    }
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let mut plist = Element::new("plist");
+    plist.attrs.push(("version".to_owned(), "1.0".to_owned()));
+    let value = self.element(json).unwrap_or_else(|| Element::new("dict"));
+    plist.children.push(Content::Element(value));
+
+    let declaration = vec![
+      ("version".to_owned(), "1.0".to_owned()),
+      ("encoding".to_owned(), "UTF-8".to_owned()),
+    ];
+    Ok(vec![
+      Content::Declaration(declaration),
+      Content::DocType(DOCTYPE.to_owned()),
+      Content::Element(plist),
+    ])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    convention::roundtrip,
+    json::JsonConfig,
+    xml::{Indentation, XmlConfig}
+  };
+
+  // The example of the plist manual page, with a real and booleans added
+  const PLIST: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+	<dict>
+		<key>Year Of Birth</key>
+		<integer>1965</integer>
+		<key>Pets Names</key>
+		<array/>
+		<key>Picture</key>
+		<data>PEKBpYGlmYFCPA==</data>
+		<key>City of Birth</key>
+		<string>Springfield</string>
+		<key>Name</key>
+		<string>John Doe</string>
+		<key>Kids Names</key>
+		<array>
+			<string>John</string>
+			<string>Kyra</string>
+		</array>
+		<key>Birthday</key>
+		<date>1965-05-12T00:00:00Z</date>
+		<key>Height</key>
+		<real>1.82</real>
+		<key>Married</key>
+		<true/>
+		<key>Retired</key>
+		<false/>
+	</dict>
+</plist>"#;
+
+  fn to_json(xml: &str) -> Result<JsonValue, Error> {
+    JsonConfig::new().convention(Plist).finalize().build_from_xml(xml)
+  }
+
+  fn to_xml(json: &JsonValue) -> String {
+    let mut builder = XmlConfig::new().convention(Plist).finalize();
+    builder.build_from_json(json).unwrap()
+  }
+
+  #[test]
+  fn typed_values() {
+    assert_eq!(
+      to_json(PLIST).unwrap(),
+      json!({
+        "Year Of Birth": 1965,
+        "Pets Names": [],
+        "Picture": {"$data": "PEKBpYGlmYFCPA=="},
+        "City of Birth": "Springfield",
+        "Name": "John Doe",
+        "Kids Names": ["John", "Kyra"],
+        "Birthday": {"$date": "1965-05-12T00:00:00Z"},
+        "Height": 1.82,
+        "Married": true,
+        "Retired": false
+      })
+    );
+
+    let (json, output) = roundtrip(Plist, "<plist><data>\n  PEKBpYGl\n  mYFCPA==\n</data></plist>");
+    assert_eq!(json, json!({"$data": "PEKBpYGlmYFCPA=="}));
+    assert_eq!(
+      output,
+      format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE {}><plist version="1.0"><data>PEKBpYGlmYFCPA==</data></plist>"#,
+        DOCTYPE
+      )
+    );
+  }
+
+  #[test]
+  fn write_plist() {
+    let json = to_json(PLIST).unwrap();
+    let mut builder = XmlConfig::new().convention(Plist).rendering(Indentation::new(b'\t', 1)).finalize();
+    assert_eq!(builder.build_from_json(&json).unwrap(), PLIST);
+  }
+
+  #[test]
+  fn arbitrary_json() {
+    assert_eq!(
+      to_xml(&json!({"a": null, "b": [1, -2.5, null, {}], "c": "", "d": {"$date": 1}})),
+      format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE {}><plist version="1.0"><dict><key>b</key><array><integer>1</integer><real>-2.5</real><dict/></array><key>c</key><string/><key>d</key><dict><key>$date</key><integer>1</integer></dict></dict></plist>"#,
+        DOCTYPE
+      )
+    );
+    assert_eq!(
+      to_xml(&JsonValue::Null),
+      format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE {}><plist version="1.0"><dict/></plist>"#,
+        DOCTYPE
+      )
+    );
+  }
+
+  #[test]
+  fn tagged_values() {
+    let xml = |value| {
+      format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?><!DOCTYPE {}><plist version="1.0">{}</plist>"#,
+        DOCTYPE, value
+      )
+    };
+    assert_eq!(
+      to_xml(&json!({"$date": "1965-05-12T00:00:00Z"})),
+      xml("<date>1965-05-12T00:00:00Z</date>")
+    );
+    assert_eq!(to_xml(&json!({"$data": "PEKBpYGlmYFCPA=="})), xml("<data>PEKBpYGlmYFCPA==</data>"));
+    assert_eq!(to_xml(&json!({"$data": ""})), xml("<data/>"));
+
+    // Text which isn't a plist date or Base64 is written as a dictionary
+    assert_eq!(
+      to_xml(&json!({"$date": "tomorrow"})),
+      xml("<dict><key>$date</key><string>tomorrow</string></dict>")
+    );
+    assert_eq!(
+      to_xml(&json!({"$date": "1965-05-12"})),
+      xml("<dict><key>$date</key><string>1965-05-12</string></dict>")
+    );
+    assert_eq!(
+      to_xml(&json!({"$data": "not base64!"})),
+      xml("<dict><key>$data</key><string>not base64!</string></dict>")
+    );
+    assert_eq!(
+      to_xml(&json!({"$data": "PEKBpYG"})),
+      xml("<dict><key>$data</key><string>PEKBpYG</string></dict>")
+    );
+  }
+
+  #[test]
+  fn invalid_plist() {
+    let error = to_json("<dict/>").unwrap_err();
+    assert_eq!(error.details(), "Expected a plist document element.");
+
+    let error = to_json("<plist><dict/><dict/></plist>").unwrap_err();
+    assert_eq!(error.details(), "Expected a single value in <plist>.");
+
+    let error = to_json("<plist><dict><key>a</key></dict></plist>").unwrap_err();
+    assert_eq!(error.details(), "Expected a value for key \"a\".");
+
+    let error = to_json("<plist><dict><string>a</string></dict></plist>").unwrap_err();
+    assert_eq!(error.details(), "Expected <key> in <dict>, found <string>.");
+
+    let error = to_json("<plist><integer>1.5</integer></plist>").unwrap_err();
+    assert_eq!(error.details(), "Invalid integer \"1.5\".");
+
+    let error = to_json("<plist><uid>1</uid></plist>").unwrap_err();
+    assert_eq!(error.details(), "Unknown plist value <uid>.");
+  }
+}
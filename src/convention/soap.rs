@@ -0,0 +1,550 @@
+use std::fmt;
+
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::{
+  convention::{document_element, Convention},
+  error::{Error, ErrorKind},
+  namespace::{declare, resolve, XSD_NAMESPACE, XSI_NAMESPACE},
+  tree::{Content, Element},
+  utils
+};
+
+// Namespaces of the SOAP envelope
+const ENVELOPE_11: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+const ENVELOPE_12: &str = "http://www.w3.org/2003/05/soap-envelope";
+
+// Namespaces of the SOAP encoding
+const ENCODING_11: &str = "http://schemas.xmlsoap.org/soap/encoding/";
+const ENCODING_12: &str = "http://www.w3.org/2003/05/soap-encoding";
+
+// First child element of `parent` with local name `local`
+fn child<'a>(parent: &'a Element, local: &str) -> Option<&'a Element> {
+  parent.elements().find(|e| e.local_name() == local)
+}
+
+// JSON value of the text of an element with XML Schema type `kind`
+fn typed(text: String, kind: Option<&str>) -> Result<JsonValue, Error> {
+  let kind = match kind {
+    Some(kind) => kind,
+    None => return Ok(text.into())
+  };
+  let trimmed = text.trim();
+  let invalid = || Error::new(ErrorKind::Syntax, format!("Invalid {} \"{}\".", kind, trimmed));
+
+  Ok(match kind {
+    "int" | "integer" | "long" | "short" | "byte" | "unsignedInt" | "unsignedLong" | "unsignedShort" | "unsignedByte"
+    | "nonNegativeInteger" | "positiveInteger" | "negativeInteger" | "nonPositiveInteger" => match trimmed.parse::<i64>() {
+      Ok(n) => n.into(),
+      Err(_) => trimmed.parse::<u64>().map_err(|_| invalid())?.into()
+    },
+    "double" | "float" | "decimal" => {
+      let number = trimmed.parse::<f64>().map_err(|_| invalid())?;
+      JsonValue::Number(Number::from_f64(number).ok_or_else(invalid)?)
+    },
+    "boolean" => match trimmed {
+      "true" | "1" => JsonValue::Bool(true),
+      "false" | "0" => JsonValue::Bool(false),
+      _ => return Err(invalid())
+    },
+    _ => text.into()
+  })
+}
+
+/// Version of the SOAP protocol
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SoapVersion {
+  /// SOAP 1.1
+  SOAP11,
+  /// SOAP 1.2
+  SOAP12
+}
+
+impl SoapVersion {
+  // Namespace of the envelope
+  fn namespace(self) -> &'static str {
+    match self {
+      SoapVersion::SOAP11 => ENVELOPE_11,
+      SoapVersion::SOAP12 => ENVELOPE_12
+    }
+  }
+
+  // Namespace of the encoding
+  fn encoding(self) -> &'static str {
+    match self {
+      SoapVersion::SOAP11 => ENCODING_11,
+      SoapVersion::SOAP12 => ENCODING_12
+    }
+  }
+}
+
+/// A SOAP Fault, read from the Body of an envelope by [`Soap`].
+///
+/// The fault is the error of the conversion, of kind `fault`, and is available with
+/// [`X2JError::fault`](crate::X2JError::fault).
+#[derive(Clone, Debug, PartialEq)]
+pub struct SoapFault {
+  code:   String,
+  reason: String,
+  actor:  Option<String>,
+  detail: Option<JsonValue>
+}
+
+impl SoapFault {
+  /// Fault code, such as `soap:Server` or `env:Sender`
+  pub fn code(&self) -> &str {
+    &self.code
+  }
+
+  /// Human readable reason: the `faultstring` in SOAP 1.1 and the first `Reason/Text` in SOAP 1.2
+  pub fn reason(&self) -> &str {
+    &self.reason
+  }
+
+  /// Node which caused the fault: the `faultactor` in SOAP 1.1 and the `Role` in SOAP 1.2
+  pub fn actor(&self) -> Option<&str> {
+    self.actor.as_deref()
+  }
+
+  /// Detail, as plain JSON like the Body
+  pub fn detail(&self) -> Option<&JsonValue> {
+    self.detail.as_ref()
+  }
+}
+
+impl fmt::Display for SoapFault {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: {}", self.code, self.reason)
+  }
+}
+
+/// SOAP 1.1 and 1.2 envelopes, with the content of their Body as plain JSON.
+///
+/// The Envelope and Body are unwrapped and the content of the Body is an object keyed by the local
+/// names of its elements. Elements with child elements or typed `SOAP-ENC:Struct` are objects,
+/// repeated elements arrays, and attributes are dropped. SOAP-encoded arrays, typed
+/// `SOAP-ENC:Array` or with an `arrayType`, `itemType` or `arraySize` attribute, are arrays of
+/// their items whatever the item names. The text of leaf elements is a string unless their
+/// `xsi:type` is an XML Schema integer, floating point or boolean type, and elements with
+/// `xsi:nil="true"` are `null`. Envelopes of both versions are read. A Body with a `Fault` is an
+/// error of kind `fault` carrying a [`SoapFault`].
+///
+/// JSON objects are wrapped into a SOAP-encoded envelope of the configured [`SoapVersion`], which
+/// declares the `xsi`, `xsd` and `SOAP-ENC` prefixes. Strings, numbers and booleans are typed with
+/// their XML Schema type, objects are `SOAP-ENC:Struct` and arrays `SOAP-ENC:Array` of `item`
+/// elements, with an `arrayType` in SOAP 1.1 and an `itemType` and `arraySize` in SOAP 1.2. `null`
+/// is an element with `xsi:nil="true"`. Reading the envelope back gives the same JSON.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{JsonConfig, Soap, XmlConfig};
+///
+/// let xml = r#"<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:m="https://example.com/stock"><soap:Body><m:GetStockPrice><m:StockName>IBM</m:StockName></m:GetStockPrice></soap:Body></soap:Envelope>"#;
+/// let json = JsonConfig::new().convention(Soap::new()).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!({"GetStockPrice": {"StockName": "IBM"}}));
+///
+/// let soap = Soap::new().namespace("m", "https://example.com/stock");
+/// let mut builder = XmlConfig::new().convention(soap).finalize();
+/// assert_eq!(
+///   builder.build_from_json(&json).unwrap(),
+///   r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:SOAP-ENC="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:m="https://example.com/stock"><soap:Body><m:GetStockPrice xsi:type="SOAP-ENC:Struct"><StockName xsi:type="xsd:string">IBM</StockName></m:GetStockPrice></soap:Body></soap:Envelope>"#
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Soap {
+  version:   SoapVersion,
+  namespace: Option<(String, String)>
+}
+
+impl Default for Soap {
+  fn default() -> Soap {
+    Soap {
+      version:   SoapVersion::SOAP11,
+      namespace: None
+    }
+  }
+}
+
+impl Soap {
+  /// Initialize the SOAP convention, writing SOAP 1.1 envelopes
+  pub fn new() -> Soap {
+    Soap::default()
+  }
+
+  /// Version of the written envelopes
+  pub fn version(mut self, version: SoapVersion) -> Soap {
+    self.version = version;
+    self
+  }
+
+  /// Qualify the elements of the Body, but not their descendants, with `prefix` bound to `uri` on
+  /// the Envelope. An empty prefix declares `uri` as the default namespace of each element of the
+  /// Body instead, which their descendants inherit
+  pub fn namespace<P: Into<String>, U: Into<String>>(mut self, prefix: P, uri: U) -> Soap {
+    self.namespace = Some((prefix.into(), uri.into()));
+    self
+  }
+
+  // JSON value of a payload element
+  fn value(&self, element: &Element, scope: &[(String, String)]) -> Result<JsonValue, Error> {
+    let scope = declare(scope, element);
+    let mut kind = None;
+    let mut array = false;
+    for (name, value) in &element.attrs {
+      match resolve(&scope, name, true) {
+        (Some(XSI_NAMESPACE), "nil") if value == "true" || value == "1" => return Ok(JsonValue::Null),
+        (Some(XSI_NAMESPACE), "type") => kind = Some(resolve(&scope, value, false)),
+        (Some(ENCODING_11), "arrayType") | (Some(ENCODING_12), "itemType") | (Some(ENCODING_12), "arraySize") => array = true,
+        _ => ()
+      }
+    }
+
+    match kind {
+      Some((Some(ENCODING_11), "Array")) | Some((Some(ENCODING_12), "Array")) => array = true,
+      Some((Some(ENCODING_11), "Struct")) | Some((Some(ENCODING_12), "Struct")) => return self.object(element, &scope),
+      _ => ()
+    }
+    if array {
+      let items = element.elements().map(|e| self.value(e, &scope));
+      Ok(JsonValue::Array(items.collect::<Result<_, _>>()?))
+    } else if element.has_elements() {
+      self.object(element, &scope)
+    } else {
+      // XML Schema types, whose `xsd` prefix is sometimes left undeclared
+      let kind = kind.filter(|(uri, _)| uri.map_or(true, |uri| uri == XSD_NAMESPACE));
+      typed(element.text(), kind.map(|(_, local)| local))
+    }
+  }
+
+  // JSON object of the child elements of `element`, whose declarations are in `scope`. Repeated
+  // elements are arrays
+  fn object(&self, element: &Element, scope: &[(String, String)]) -> Result<JsonValue, Error> {
+    let children = element.elements().collect::<Vec<_>>();
+    let mut object = Map::new();
+    for child in &children {
+      let key = child.local_name();
+      let value = self.value(child, scope)?;
+      if children.iter().filter(|e| e.local_name() == key).nth(1).is_none() {
+        object.insert(key.to_owned(), value);
+      } else if let JsonValue::Array(array) = object.entry(key.to_owned()).or_insert_with(|| JsonValue::Array(Vec::new())) {
+        array.push(value);
+      }
+    }
+    Ok(JsonValue::Object(object))
+  }
+
+  // Fault of a `Fault` element
+  fn fault(&self, version: SoapVersion, fault: &Element, scope: &[(String, String)]) -> Result<SoapFault, Error> {
+    let scope = declare(scope, fault);
+    let text = |e: Option<&Element>| e.map(|e| e.text().trim().to_owned());
+    let (code, reason, actor, detail) = match version {
+      SoapVersion::SOAP11 => (
+        text(child(fault, "faultcode")),
+        text(child(fault, "faultstring")),
+        text(child(fault, "faultactor")),
+        child(fault, "detail")
+      ),
+      SoapVersion::SOAP12 => (
+        text(child(fault, "Code").and_then(|c| child(c, "Value"))),
+        text(child(fault, "Reason").and_then(|r| child(r, "Text"))),
+        text(child(fault, "Role")),
+        child(fault, "Detail")
+      )
+    };
+    Ok(SoapFault {
+      code: code.unwrap_or_default(),
+      reason: reason.unwrap_or_default(),
+      actor,
+      detail: detail.map(|d| self.value(d, &scope)).transpose()?
+    })
+  }
+
+  // SOAP-encoded element named `name` for a JSON value
+  fn element(&self, name: &str, value: &JsonValue) -> Element {
+    let mut element = Element::new(name);
+    let mut attr = |name: &str, value: String| element.attrs.push((name.to_owned(), value));
+    match value {
+      JsonValue::Null => attr("xsi:nil", "true".to_owned()),
+      JsonValue::Bool(_) => attr("xsi:type", "xsd:boolean".to_owned()),
+      JsonValue::Number(n) if n.is_f64() => attr("xsi:type", "xsd:double".to_owned()),
+      JsonValue::Number(n) if n.is_i64() => attr("xsi:type", "xsd:long".to_owned()),
+      JsonValue::Number(_) => attr("xsi:type", "xsd:unsignedLong".to_owned()),
+      JsonValue::String(_) => attr("xsi:type", "xsd:string".to_owned()),
+      JsonValue::Array(items) => {
+        attr("xsi:type", "SOAP-ENC:Array".to_owned());
+        match self.version {
+          SoapVersion::SOAP11 => attr("SOAP-ENC:arrayType", format!("xsd:anyType[{}]", items.len())),
+          SoapVersion::SOAP12 => {
+            attr("SOAP-ENC:itemType", "xsd:anyType".to_owned());
+            attr("SOAP-ENC:arraySize", items.len().to_string());
+          }
+        }
+      },
+      JsonValue::Object(_) => attr("xsi:type", "SOAP-ENC:Struct".to_owned())
+    }
+
+    match value {
+      JsonValue::Null => (),
+      JsonValue::Array(items) => {
+        for item in items {
+          element.children.push(Content::Element(self.element("item", item)));
+        }
+      },
+      JsonValue::Object(object) => {
+        for (key, value) in object {
+          element.children.push(Content::Element(self.element(key, value)));
+        }
+      },
+      _ => {
+        let text = utils::to_string_raw(value);
+        if !text.is_empty() {
+          element.children.push(Content::Text(text));
+        }
+      }
+    }
+    element
+  }
+}
+
+impl Convention for Soap {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let root = document_element(nodes)?;
+    let scope = declare(&[], root);
+    let version = match resolve(&scope, &root.name, false) {
+      (Some(ENVELOPE_11), "Envelope") => SoapVersion::SOAP11,
+      (Some(ENVELOPE_12), "Envelope") => SoapVersion::SOAP12,
+      _ => return Err(Error::new(ErrorKind::Syntax, "Expected a SOAP Envelope document element."))
+    };
+
+    let namespace = version.namespace();
+    let body = root
+      .elements()
+      .map(|e| (e, declare(&scope, e)))
+      .find(|(e, scope)| resolve(scope, &e.name, false) == (Some(namespace), "Body"));
+    let (body, scope) = body.ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a SOAP Body in the Envelope."))?;
+
+    let fault = body
+      .elements()
+      .find(|e| resolve(&declare(&scope, e), &e.name, false) == (Some(namespace), "Fault"));
+    match fault {
+      Some(fault) => Err(self.fault(version, fault, &scope)?.into()),
+      None => self.object(body, &scope)
+    }
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let object = json
+      .as_object()
+      .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected an object of the SOAP Body content."))?;
+
+    let mut body = Element::new("soap:Body");
+    for (key, value) in object {
+      let name = match &self.namespace {
+        Some((prefix, _)) if !prefix.is_empty() => format!("{}:{}", prefix, key),
+        _ => key.clone()
+      };
+      let mut element = self.element(&name, value);
+      if let Some((prefix, uri)) = &self.namespace {
+        if prefix.is_empty() {
+          element.attrs.insert(0, ("xmlns".to_owned(), uri.clone()));
+        }
+      }
+      body.children.push(Content::Element(element));
+    }
+
+    let mut envelope = Element::new("soap:Envelope");
+    let mut declare = |prefix: &str, uri: &str| envelope.attrs.push((format!("xmlns:{}", prefix), uri.to_owned()));
+    declare("soap", self.version.namespace());
+    declare("SOAP-ENC", self.version.encoding());
+    declare("xsi", XSI_NAMESPACE);
+    declare("xsd", XSD_NAMESPACE);
+    if let Some((prefix, uri)) = self.namespace.as_ref().filter(|(p, _)| !p.is_empty()) {
+      declare(prefix, uri);
+    }
+    envelope.children.push(Content::Element(body));
+    Ok(vec![Content::Element(envelope)])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+  use serde_json::json;
+
+  // The envelope of tests/data/soap.json, with its arrays SOAP-encoded
+  const ENCODED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/" xmlns:SOAP-ENC="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:ns1="https://paper-street.soap.com/">
+  <SOAP-ENV:Body>
+    <ns1:createWorkflowProof>
+      <SessionID xsi:type="xsd:string">sid</SessionID>
+      <Stages SOAP-ENC:arrayType="ns1:Stage[2]" xsi:type="SOAP-ENC:Array">
+        <item xsi:type="ns1:Stage">
+          <stage_reviewers SOAP-ENC:arrayType="ns1:Reviewer[1]" xsi:type="SOAP-ENC:Array">
+            <item><email>robert.paulson@gmail.com</email></item>
+          </stage_reviewers>
+          <name>Stage 1</name>
+          <deadline xsi:nil="true"/>
+        </item>
+        <item xsi:type="ns1:Stage">
+          <stage_reviewers xsi:type="SOAP-ENC:Array">
+            <item><email>tyler.durden@gmail.com</email></item>
+          </stage_reviewers>
+          <name>Stage 2</name>
+          <deadline xsi:type="xsd:int">14</deadline>
+        </item>
+      </Stages>
+      <Urgent xsi:type="xsd:boolean">1</Urgent>
+      <Budget xsi:type="xsd:double">1.5</Budget>
+    </ns1:createWorkflowProof>
+  </SOAP-ENV:Body>
+</SOAP-ENV:Envelope>"#;
+
+  // Fault examples of the SOAP 1.1 note and the SOAP 1.2 primer
+  const FAULT_11: &str = r#"<SOAP-ENV:Envelope xmlns:SOAP-ENV="http://schemas.xmlsoap.org/soap/envelope/">
+  <SOAP-ENV:Body>
+    <SOAP-ENV:Fault>
+      <faultcode>SOAP-ENV:Server</faultcode>
+      <faultstring>Server Error</faultstring>
+      <detail>
+        <e:myfaultdetails xmlns:e="Some-URI">
+          <message>My application didn't work</message>
+          <errorcode xsi:type="xsd:int" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance">1001</errorcode>
+        </e:myfaultdetails>
+      </detail>
+    </SOAP-ENV:Fault>
+  </SOAP-ENV:Body>
+</SOAP-ENV:Envelope>"#;
+
+  const FAULT_12: &str = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope" xmlns:m="http://www.example.org/timeouts">
+  <env:Body>
+    <env:Fault>
+      <env:Code>
+        <env:Value>env:Sender</env:Value>
+        <env:Subcode><env:Value>m:MessageTimeout</env:Value></env:Subcode>
+      </env:Code>
+      <env:Reason>
+        <env:Text xml:lang="en">Sender Timeout</env:Text>
+      </env:Reason>
+      <env:Role>http://example.org/ultimateReceiver</env:Role>
+      <env:Detail>
+        <m:MaxTime>P5M</m:MaxTime>
+      </env:Detail>
+    </env:Fault>
+  </env:Body>
+</env:Envelope>"#;
+
+  fn to_json(xml: &str) -> Result<JsonValue, Error> {
+    JsonConfig::new().convention(Soap::new()).finalize().build_from_xml(xml)
+  }
+
+  fn to_xml(soap: Soap, json: &JsonValue) -> String {
+    XmlConfig::new().convention(soap).finalize().build_from_json(json).unwrap()
+  }
+
+  #[test]
+  fn encoded_body() {
+    let (json, output) = roundtrip(Soap::new(), ENCODED);
+    assert_eq!(
+      json,
+      json!({
+        "createWorkflowProof": {
+          "SessionID": "sid",
+          "Stages": [
+            {"stage_reviewers": [{"email": "robert.paulson@gmail.com"}], "name": "Stage 1", "deadline": null},
+            {"stage_reviewers": [{"email": "tyler.durden@gmail.com"}], "name": "Stage 2", "deadline": 14}
+          ],
+          "Urgent": true,
+          "Budget": 1.5
+        }
+      })
+    );
+    // The body is written back with its own SOAP encoding
+    assert_eq!(to_json(&output).unwrap(), json);
+  }
+
+  #[test]
+  fn literal_body() {
+    let xml = r#"<env:Envelope xmlns:env="http://www.w3.org/2003/05/soap-envelope"><env:Header><t:id xmlns:t="urn:t">1</t:id></env:Header><env:Body><r xmlns="urn:r" id="2"><a>1</a><a>2</a><b/></r></env:Body></env:Envelope>"#;
+    assert_eq!(to_json(xml).unwrap(), json!({"r": {"a": ["1", "2"], "b": ""}}));
+    assert_eq!(
+      to_json(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><s:Body/></s:Envelope>"#).unwrap(),
+      json!({})
+    );
+  }
+
+  #[test]
+  fn faults() {
+    let error = to_json(FAULT_11).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Fault);
+    assert_eq!(error.to_string(), "fault: SOAP-ENV:Server: Server Error");
+    let fault = error.fault().unwrap();
+    assert_eq!(fault.code(), "SOAP-ENV:Server");
+    assert_eq!(fault.reason(), "Server Error");
+    assert_eq!(fault.actor(), None);
+    assert_eq!(
+      fault.detail(),
+      Some(&json!({"myfaultdetails": {"message": "My application didn't work", "errorcode": 1001}}))
+    );
+
+    let error = to_json(FAULT_12).unwrap_err();
+    let fault = error.fault().unwrap();
+    assert_eq!(fault.code(), "env:Sender");
+    assert_eq!(fault.reason(), "Sender Timeout");
+    assert_eq!(fault.actor(), Some("http://example.org/ultimateReceiver"));
+    assert_eq!(fault.detail(), Some(&json!({"MaxTime": "P5M"})));
+
+    assert!(to_json("<a/>").unwrap_err().fault().is_none());
+  }
+
+  #[test]
+  fn write_envelope() {
+    let json = json!({"createWorkflowProof": {
+      "SessionID": "sid",
+      "Stages": [{"name": "Stage 1", "deadline": null}, {"name": "Stage 2", "deadline": 14}],
+      "Matrix": [[1, 2], [], [{}]],
+      "Budget": 1.5,
+      "Size": 18446744073709551615u64,
+      "Urgent": false
+    }});
+    let soap = Soap::new().namespace("ns1", "https://paper-street.soap.com/");
+    let xml = to_xml(soap, &json);
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/" xmlns:SOAP-ENC="http://schemas.xmlsoap.org/soap/encoding/" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xsd="http://www.w3.org/2001/XMLSchema" xmlns:ns1="https://paper-street.soap.com/"><soap:Body><ns1:createWorkflowProof xsi:type="SOAP-ENC:Struct"><SessionID xsi:type="xsd:string">sid</SessionID><Stages xsi:type="SOAP-ENC:Array" SOAP-ENC:arrayType="xsd:anyType[2]"><item xsi:type="SOAP-ENC:Struct"><name xsi:type="xsd:string">Stage 1</name><deadline xsi:nil="true"/></item><item xsi:type="SOAP-ENC:Struct"><name xsi:type="xsd:string">Stage 2</name><deadline xsi:type="xsd:long">14</deadline></item></Stages><Matrix xsi:type="SOAP-ENC:Array" SOAP-ENC:arrayType="xsd:anyType[3]"><item xsi:type="SOAP-ENC:Array" SOAP-ENC:arrayType="xsd:anyType[2]"><item xsi:type="xsd:long">1</item><item xsi:type="xsd:long">2</item></item><item xsi:type="SOAP-ENC:Array" SOAP-ENC:arrayType="xsd:anyType[0]"/><item xsi:type="SOAP-ENC:Array" SOAP-ENC:arrayType="xsd:anyType[1]"><item xsi:type="SOAP-ENC:Struct"/></item></Matrix><Budget xsi:type="xsd:double">1.5</Budget><Size xsi:type="xsd:unsignedLong">18446744073709551615</Size><Urgent xsi:type="xsd:boolean">false</Urgent></ns1:createWorkflowProof></soap:Body></soap:Envelope>"#
+    );
+    assert_eq!(to_json(&xml).unwrap(), json);
+
+    let json = json!({"a": 1, "b": [true, ""], "c": {}});
+    let soap = Soap::new().version(SoapVersion::SOAP12).namespace("", "urn:r");
+    let xml = to_xml(soap, &json);
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://www.w3.org/2003/05/soap-envelope" xmlns:SOAP-ENC="http://www.w3.org/2003/05/soap-encoding" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xmlns:xsd="http://www.w3.org/2001/XMLSchema"><soap:Body><a xmlns="urn:r" xsi:type="xsd:long">1</a><b xmlns="urn:r" xsi:type="SOAP-ENC:Array" SOAP-ENC:itemType="xsd:anyType" SOAP-ENC:arraySize="2"><item xsi:type="xsd:boolean">true</item><item xsi:type="xsd:string"/></b><c xmlns="urn:r" xsi:type="SOAP-ENC:Struct"/></soap:Body></soap:Envelope>"#
+    );
+    assert_eq!(to_json(&xml).unwrap(), json);
+  }
+
+  #[test]
+  fn prefixed_types() {
+    // The encoding namespace is resolved whatever its prefix, and other namespaces aren't it
+    let xml = r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/" xmlns:i="http://www.w3.org/2001/XMLSchema-instance" xmlns:enc="http://www.w3.org/2003/05/soap-encoding" xmlns:o="urn:other"><s:Body><a i:type="enc:Array"><x>1</x></a><b i:type="o:Array"><x>1</x></b><c i:type="o:int">1</c></s:Body></s:Envelope>"#;
+    assert_eq!(to_json(xml).unwrap(), json!({"a": ["1"], "b": {"x": "1"}, "c": "1"}));
+  }
+
+  #[test]
+  fn invalid_envelope() {
+    let error = to_json("<Envelope/>").unwrap_err();
+    assert_eq!(error.details(), "Expected a SOAP Envelope document element.");
+
+    let error = to_json(r#"<s:Envelope xmlns:s="http://schemas.xmlsoap.org/soap/envelope/"><Body/></s:Envelope>"#).unwrap_err();
+    assert_eq!(error.details(), "Expected a SOAP Body in the Envelope.");
+
+    let error = to_json(&ENCODED.replace(">14<", ">x<")).unwrap_err();
+    assert_eq!(error.details(), "Invalid int \"x\".");
+
+    let mut builder = XmlConfig::new().convention(Soap::new()).finalize();
+    let error = builder.build_from_json(&json!([1])).unwrap_err();
+    assert_eq!(error.details(), "Expected an object of the SOAP Body content.");
+  }
+}
@@ -0,0 +1,941 @@
+use regex::{NoExpand, Regex};
+use serde_json::{json, Value as JsonValue};
+
+use crate::{
+  convention::Convention,
+  error::{Error, ErrorKind},
+  json::{CollisionPolicy, JsonBuilder},
+  name,
+  namespace,
+  tree::{self, Content, Element},
+  utils,
+  xml::{NamePolicy, NullPolicy, ValuePolicy, XmlBuilder}
+};
+
+lazy_static! {
+  static ref WHITESPACE_RE: Regex = Regex::new(r"^\s*$").unwrap();
+  static ref TWO_OR_MORE_WHITESPACE_RE: Regex = Regex::new(r"\s{2,}").unwrap();
+}
+
+/// The [node-xml2js](https://github.com/Leonidas-from-XIV/node-xml2js) convention: attributes
+/// under `$`, text under `_` and child elements in arrays, as configured by a [JsonBuilder] and an
+/// [XmlBuilder]. The builders use it when no other convention is set.
+#[derive(Clone, Debug, Default)]
+pub struct Xml2Js {
+  pub(crate) json: JsonOptions,
+  pub(crate) xml:  XmlOptions
+}
+
+impl Xml2Js {
+  /// Initialize the convention with the options of `json` and `xml`. Their own conventions, if
+  /// set, are not used
+  pub fn new(json: JsonBuilder, xml: XmlBuilder) -> Xml2Js {
+    Xml2Js {
+      json: json.options().clone(),
+      xml:  xml.options().clone()
+    }
+  }
+}
+
+impl Convention for Xml2Js {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    self.json.document(nodes)
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    self.xml.document(json)
+  }
+}
+
+// Options of the JSON built from XML, set with a JsonConfig
+#[derive(Clone, Debug)]
+pub(crate) struct JsonOptions {
+  pub(crate) charkey:                 String,
+  pub(crate) attrkey:                 String,
+  pub(crate) empty_tag:               String,
+  pub(crate) explicit_root:           bool,
+  pub(crate) trim:                    bool,
+  pub(crate) ignore_attrs:            bool,
+  pub(crate) merge_attrs:             bool,
+  pub(crate) attr_prefix:             String,
+  pub(crate) normalize_text:          bool,
+  pub(crate) lowercase_tags:          bool,
+  pub(crate) explicit_array:          bool,
+  pub(crate) explicit_charkey:        bool,
+  pub(crate) decode_names:            bool,
+  pub(crate) collisions:              CollisionPolicy,
+  pub(crate) childkey:                String,
+  pub(crate) explicit_children:       bool,
+  pub(crate) preserve_children_order: bool,
+  pub(crate) chars_as_children:       bool,
+  pub(crate) include_white_chars:     bool,
+  pub(crate) include_comments:        bool,
+  pub(crate) include_instructions:    bool,
+  pub(crate) include_prolog:          bool,
+  pub(crate) xml_normalization:       bool
+}
+
+impl Default for JsonOptions {
+  fn default() -> JsonOptions {
+    JsonOptions {
+      charkey:                 "_".to_owned(),
+      attrkey:                 "$".to_owned(),
+      empty_tag:               "".to_owned(),
+      explicit_root:           true,
+      trim:                    false,
+      ignore_attrs:            false,
+      merge_attrs:             false,
+      attr_prefix:             "".to_owned(),
+      normalize_text:          false,
+      lowercase_tags:          false,
+      explicit_array:          true,
+      explicit_charkey:        false,
+      decode_names:            false,
+      collisions:              CollisionPolicy::Merge,
+      childkey:                "$$".to_owned(),
+      explicit_children:       false,
+      preserve_children_order: false,
+      chars_as_children:       false,
+      include_white_chars:     false,
+      include_comments:        false,
+      include_instructions:    false,
+      include_prolog:          false,
+      xml_normalization:       false
+    }
+  }
+}
+
+// Text storage with state to distingiush between text in elements and text in CDATA sections
+// CDATA (literal) text will be added to JSON even when it is whitespace.
+struct Text {
+  data:    String,
+  literal: bool
+}
+
+impl Default for Text {
+  fn default() -> Text {
+    Text {
+      data:    "".to_owned(),
+      literal: false
+    }
+  }
+}
+
+// The JSON value of an element while its content is read
+struct Node {
+  value: JsonValue,
+  text:  Text
+}
+
+impl Node {
+  fn new() -> Node {
+    Node {
+      value: json!({}),
+      text:  Text::default()
+    }
+  }
+}
+
+impl JsonOptions {
+  // If text matches only newlines, spaces and tabs
+  pub(crate) fn is_whitespace(&self, value: &str) -> bool {
+    WHITESPACE_RE.is_match(value)
+  }
+
+  // This function is used to build out the JSON object.
+  // the behavior depends on the `explicit_array` setting. When this value is
+  // - true: an array will be created at `key` if it doesn't exist and new values will be pushed
+  // - false: `value` is assigned at `key` and converted into an array if there are multiple values
+  // at that key
+  fn assign_or_push(&self, object: &mut JsonValue, key: &str, value: JsonValue) {
+    if object.get(key).is_none() {
+      if self.explicit_array {
+        object[key] = json!([value]);
+      } else {
+        object[key] = value;
+      }
+    } else {
+      // Wrap object[key] in an array if it isn't one already
+      if !object[key].is_array() {
+        let current = object[key].take();
+        object[key] = json!([current]);
+      }
+      if let Some(array) = object[key].as_array_mut() {
+        array.push(value);
+      }
+    }
+  }
+
+  // Element or attribute name as it is used for JSON keys
+  pub(crate) fn decoded_name(&self, name: String) -> String {
+    if self.decode_names {
+      name::decode(&name).into_owned()
+    } else {
+      name
+    }
+  }
+
+  // Check if children are stored as an ordered array under `childkey`
+  fn ordered(&self) -> bool {
+    self.explicit_children && self.preserve_children_order
+  }
+
+  // Push a text, CDATA or comment child named `name` onto an ordered element
+  fn push_child_text(&self, node: &mut Node, name: &str, text: &str) {
+    let mut child = json!({ "#name": name });
+    child[&self.charkey] = text.into();
+    self.push_child(node, child);
+  }
+
+  // Push a child object onto an ordered element
+  fn push_child(&self, node: &mut Node, child: JsonValue) {
+    if node.value.get(&self.childkey).is_none() {
+      node.value[&self.childkey] = json!([]);
+    }
+    if let Some(children) = node.value[&self.childkey].as_array_mut() {
+      children.push(child);
+    }
+  }
+
+  // JSON key for an element or merged attribute name, following the collision policy
+  pub(crate) fn json_key(&self, name: String) -> Result<String, Error> {
+    let collides = name == self.attrkey || name == self.charkey || (self.explicit_children && name == self.childkey);
+    match self.collisions {
+      CollisionPolicy::Escape(ref prefix) if collides || name.starts_with(prefix.as_str()) => Ok(format!("{}{}", prefix, name)),
+      CollisionPolicy::Reject if collides => Err(Error::new(
+        ErrorKind::Syntax,
+        format!("Name {:?} collides with attrkey or charkey.", name)
+      )),
+      _ => Ok(name)
+    }
+  }
+
+  // JSON key for an element's tag name
+  pub(crate) fn tag_key(&self, tag: &str) -> Result<String, Error> {
+    self.json_key(self.decoded_name(if self.lowercase_tags { tag.to_lowercase() } else { tag.to_owned() }))
+  }
+
+  // Trim and normalize text, as configured
+  pub(crate) fn processed_text(&self, text: &str) -> String {
+    if self.normalize_text {
+      TWO_OR_MORE_WHITESPACE_RE.replace_all(text, NoExpand(" ")).trim().to_owned()
+    } else if self.trim {
+      text.trim().to_owned()
+    } else {
+      text.to_owned()
+    }
+  }
+
+  // Build JSON from the top level nodes of a document. As with node-xml2js, comments and
+  // processing instructions outside of the document element are skipped
+  fn document(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    if self.ordered() && self.include_prolog {
+      return self.prolog(nodes);
+    }
+    let element = match nodes.iter().rev().find_map(|node| match node {
+      Content::Element(e) => Some(e),
+      _ => None
+    }) {
+      Some(element) => element,
+      None => return Ok(JsonValue::Null)
+    };
+
+    let (key, node) = self.element(element)?;
+    if !self.explicit_root {
+      return Ok(node);
+    }
+    // The name of an ordered element is left out of an explicit root
+    let value = match node {
+      JsonValue::Object(object) if self.ordered() => JsonValue::Object(object.into_iter().filter(|(k, _)| k != "#name").collect()),
+      node => node
+    };
+    Ok(json!({ key: value }))
+  }
+
+  // Build the top level nodes of a document as ordered children
+  fn prolog(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let mut document = Node::new();
+    for node in nodes {
+      match node {
+        Content::Element(e) => {
+          let (_, value) = self.element(e)?;
+          self.push_child(&mut document, value);
+        },
+        Content::Declaration(attrs) => {
+          let mut child = json!({ "#name": "__declaration__" });
+          child[&self.attrkey] = attrs.iter().map(|(k, v)| (k.to_owned(), JsonValue::from(v.as_str()))).collect();
+          self.push_child(&mut document, child);
+        },
+        Content::DocType(doctype) => self.push_child_text(&mut document, "__doctype__", doctype),
+        Content::Comment(comment) => self.push_child_text(&mut document, "__comment__", comment),
+        Content::Instruction(pi) => self.push_child_text(&mut document, "__instruction__", pi),
+        _ => ()
+      }
+    }
+    Ok(document.value)
+  }
+
+  // Build the JSON node of an element, with the key it is stored under by its parent
+  fn element(&self, element: &Element) -> Result<(String, JsonValue), Error> {
+    let key = self.tag_key(&element.name)?;
+    let mut node = Node::new();
+    if self.ordered() {
+      node.value["#name"] = key.clone().into();
+    }
+
+    // Add any attributes
+    if !self.ignore_attrs {
+      if !element.attrs.is_empty() && !self.merge_attrs {
+        node.value[&self.attrkey] = json!({});
+      }
+      for (name, value) in &element.attrs {
+        let name = self.decoded_name(name.to_owned());
+        if self.merge_attrs {
+          let name = self.json_key(format!("{}{}", self.attr_prefix, name))?;
+          self.assign_or_push(&mut node.value, &name, value.as_str().into());
+        } else {
+          node.value[&self.attrkey][&name] = value.as_str().into();
+        }
+      }
+    }
+
+    for child in &element.children {
+      match child {
+        Content::Element(e) => {
+          let (key, value) = self.element(e)?;
+          if self.ordered() {
+            self.push_child(&mut node, value);
+          } else if self.explicit_children {
+            if node.value.get(&self.childkey).is_none() {
+              node.value[&self.childkey] = json!({});
+            }
+            self.assign_or_push(&mut node.value[&self.childkey], &key, value);
+          } else {
+            self.assign_or_push(&mut node.value, &key, value);
+          }
+        },
+        Content::Text(text) => self.text(&mut node, text),
+        Content::CData(text) if self.ordered() && self.chars_as_children => self.push_child_text(&mut node, "__cdata__", text),
+        Content::CData(text) => {
+          self.text(&mut node, text);
+          node.text.literal = true;
+        },
+        Content::Comment(comment) if self.ordered() && self.include_comments => self.push_child_text(&mut node, "__comment__", comment),
+        Content::Instruction(pi) if self.ordered() && self.include_instructions => self.push_child_text(&mut node, "__instruction__", pi),
+        _ => ()
+      }
+    }
+
+    let value = if self.ordered() {
+      self.ordered_value(node)
+    } else {
+      self.value(node)
+    };
+    Ok((key, value))
+  }
+
+  // Add character data to an element, as a child with `chars_as_children`
+  fn text(&self, node: &mut Node, text: &str) {
+    if self.ordered() && self.chars_as_children {
+      if !text.is_empty() && (self.include_white_chars || !self.is_whitespace(text)) {
+        let text = self.processed_text(text);
+        self.push_child_text(node, "__text__", &text);
+      }
+    } else {
+      node.text.data.push_str(text);
+    }
+  }
+
+  // The JSON value of an element once its content is read
+  fn value(&self, mut node: Node) -> JsonValue {
+    // This can grow to contain other whitespace characters ('\s')
+    let mut whitespace = "".to_owned();
+    let text = node.text.data.as_str();
+
+    if self.is_whitespace(text) && !node.text.literal {
+      whitespace.push_str(text);
+    } else {
+      let text = self.processed_text(text);
+      if utils::json_is_empty(&node.value) && !self.explicit_charkey {
+        node.value = JsonValue::String(text);
+      } else {
+        node.value[&self.charkey] = text.into();
+      }
+    }
+
+    if utils::json_is_empty(&node.value) {
+      if !self.empty_tag.is_empty() {
+        node.value = JsonValue::String(self.empty_tag.clone());
+      } else {
+        node.value = JsonValue::String(whitespace);
+      }
+    }
+    node.value
+  }
+
+  // The JSON value of an element with ordered children. Elements are always objects holding their
+  // name under `#name`
+  fn ordered_value(&self, mut node: Node) -> JsonValue {
+    let text = &node.text.data;
+    if !self.chars_as_children && (node.text.literal || !self.is_whitespace(text)) {
+      node.value[&self.charkey] = self.processed_text(text).into();
+    }
+    node.value
+  }
+}
+
+// Options of the XML built from JSON, set with an XmlConfig
+#[derive(Clone, Debug)]
+pub(crate) struct XmlOptions {
+  pub(crate) attrkey:     String,
+  pub(crate) charkey:     String,
+  pub(crate) nskey:       String,
+  pub(crate) childkey:    String,
+  pub(crate) empty_tag:   String,
+  pub(crate) root_name:   String,
+  pub(crate) names:       NamePolicy,
+  pub(crate) values:      ValuePolicy,
+  pub(crate) collisions:  CollisionPolicy,
+  pub(crate) attr_prefix: String,
+  pub(crate) attr_hints:  Vec<String>
+}
+
+impl Default for XmlOptions {
+  fn default() -> XmlOptions {
+    XmlOptions {
+      attrkey:     "$".to_owned(),
+      charkey:     "_".to_owned(),
+      nskey:       "$ns".to_owned(),
+      childkey:    "$$".to_owned(),
+      empty_tag:   "".to_owned(),
+      root_name:   "root".to_owned(),
+      names:       NamePolicy::Verbatim,
+      values:      ValuePolicy::default(),
+      collisions:  CollisionPolicy::Merge,
+      attr_prefix: "".to_owned(),
+      attr_hints:  Vec::new()
+    }
+  }
+}
+
+// Local part of a qualified or Clark notation name
+fn local_name(name: &str) -> &str {
+  match namespace::parse_clark(name) {
+    Some((_, local)) => local,
+    None => name.rsplit(':').next().unwrap_or(name)
+  }
+}
+
+// Check if a node only holds character data: scalars or arrays of them
+fn is_text(node: &JsonValue) -> bool {
+  match node {
+    JsonValue::Object(_) => false,
+    JsonValue::Array(array) => array.iter().all(is_text),
+    _ => true
+  }
+}
+
+/// Tag attributes type. A vector of (name, value) tuples
+pub(crate) type TagAttrs = Vec<(String, String)>;
+
+impl XmlOptions {
+  // Check if key is an attribute key
+  pub(crate) fn is_attrkey(&self, key: &str) -> bool {
+    self.attrkey == *key
+  }
+
+  // Check if key is a character key
+  pub(crate) fn is_charkey(&self, key: &str) -> bool {
+    self.charkey == *key
+  }
+
+  // Attribute name for a merged attribute key: a key with the attribute prefix, or a hinted key
+  fn merged_attr<'k>(&self, key: &'k str) -> Option<&'k str> {
+    if !self.attr_prefix.is_empty() {
+      if let Some(name) = key.strip_prefix(self.attr_prefix.as_str()) {
+        return Some(name);
+      }
+    }
+    self.attr_hints.iter().find(|hint| *hint == key).map(|_| key)
+  }
+
+  // Element name for a key, without the collision escape prefix
+  pub(crate) fn element_key<'k>(&self, key: &'k str) -> &'k str {
+    match self.collisions {
+      CollisionPolicy::Escape(ref prefix) if !prefix.is_empty() => key.strip_prefix(prefix.as_str()).unwrap_or(key),
+      _ => key
+    }
+  }
+
+  // Apply the name policy to an element or attribute name found at JSON pointer `path`
+  pub(crate) fn xml_name(&self, name: String, path: &str) -> Result<String, Error> {
+    check_name(&self.names, name, path)
+  }
+
+  // Get all a attributes at node. If successful, returns a vector of (name, value) attributes
+  fn tag_attributes(&self, path: &str, node: &JsonValue) -> Result<TagAttrs, Error> {
+    // Node should either be an object {} or a wrapped object [{}]
+    // If it's an array, unwrap it and call self recursively
+    if let Some(array) = node.as_array() {
+      if array.len() == 1 {
+        let child = array.iter().next().unwrap_or(&JsonValue::Null);
+        self.tag_attributes(&utils::json_pointer(path, "0"), child)
+      } else {
+        Ok(Vec::new())
+      }
+    } else {
+      let mut attrs = Vec::new();
+      if let Some(attrs_value) = node.get(&self.attrkey) {
+        self.attrkey_attributes(path, attrs_value, &mut attrs)?;
+      }
+      if let Some(object) = node.as_object() {
+        for (key, value) in object {
+          self.merged_attribute(path, key, value, &mut attrs)?;
+        }
+      }
+      Ok(attrs)
+    }
+  }
+
+  // Add the attributes of the `attrkey` value of the node at `path` to `attrs`
+  pub(crate) fn attrkey_attributes(&self, path: &str, node: &JsonValue, attrs: &mut TagAttrs) -> Result<(), Error> {
+    let path = utils::json_pointer(path, &self.attrkey);
+    if self.collisions == CollisionPolicy::Reject && !node.is_object() {
+      return Err(Error::new(
+        ErrorKind::Syntax,
+        format!("Expected an object of attributes at {}.", path)
+      ));
+    }
+    if let Some(object) = node.as_object() {
+      for (key, value) in object {
+        if let Some((name, value)) = self.tag_attribute(key, value)? {
+          attrs.push((self.xml_name(name, &utils::json_pointer(&path, key))?, value));
+        }
+      }
+    }
+    Ok(())
+  }
+
+  // Add the attribute of `key` of the node at `path` to `attrs`, if it is a merged attribute key
+  pub(crate) fn merged_attribute(&self, path: &str, key: &str, value: &JsonValue, attrs: &mut TagAttrs) -> Result<(), Error> {
+    let name = match self.merged_attr(key) {
+      Some(name) => name,
+      None => return Ok(())
+    };
+    // Merged attributes are wrapped in an array with `explicit_array`
+    let value = match value.as_array() {
+      Some(array) if array.len() == 1 => &array[0],
+      _ => value
+    };
+    if let Some((name, value)) = self.tag_attribute(name, value)? {
+      attrs.push((self.xml_name(name, &utils::json_pointer(path, key))?, value));
+    }
+    Ok(())
+  }
+
+  // Text of an attribute value, following the value policy. `None` leaves the attribute out
+  fn attribute_value(&self, value: &JsonValue) -> Result<Option<String>, Error> {
+    let scalar_attrs = self.values.scalar_attrs;
+    match value {
+      JsonValue::String(s) => return Ok(Some(s.to_owned())),
+      JsonValue::Number(_) | JsonValue::Bool(_) if scalar_attrs => return Ok(Some(value.to_string())),
+      JsonValue::Null => match self.values.null {
+        NullPolicy::Text if scalar_attrs => return Ok(Some("null".to_owned())),
+        NullPolicy::Omit | NullPolicy::Nil => return Ok(None),
+        NullPolicy::Empty => return Ok(Some(String::new())),
+        NullPolicy::Text => ()
+      },
+      _ => ()
+    }
+    Err(Error::new(ErrorKind::Syntax, "Expected attribute to be a string."))
+  }
+
+  // Get a single (name, value) attribute. Besides strings, the value may be an object with `value`
+  // and `uri` / `local` keys, as produced by node-xml2js with its `xmlns` option
+  fn tag_attribute(&self, name: &str, value: &JsonValue) -> Result<Option<(String, String)>, Error> {
+    if let Some(object) = value.as_object() {
+      let attr = match self.attribute_value(object.get("value").unwrap_or(&JsonValue::Null))? {
+        Some(attr) => attr,
+        None => return Ok(None)
+      };
+      let uri = object.get("uri").and_then(JsonValue::as_str).unwrap_or("");
+      // xmlns attributes are reported with a namespace URI of their own, but are written as is
+      if uri.is_empty() || name == "xmlns" || name.starts_with("xmlns:") {
+        return Ok(Some((name.to_owned(), attr)));
+      }
+      let local = object.get("local").and_then(JsonValue::as_str).unwrap_or_else(|| local_name(name));
+      Ok(Some((namespace::to_clark(uri, local), attr)))
+    } else {
+      Ok(self.attribute_value(value)?.map(|attr| (name.to_owned(), attr)))
+    }
+  }
+
+  // Name of the element at `key`. A namespace object turns it into a Clark notation name
+  fn element_name(&self, key: &str, node: &JsonValue) -> String {
+    self.namespaced_name(key, node.get(&self.nskey))
+  }
+
+  // Name of the element at `key` with the namespace object `ns`, if any
+  pub(crate) fn namespaced_name(&self, key: &str, ns: Option<&JsonValue>) -> String {
+    let ns = match ns {
+      Some(ns) => ns,
+      None => return key.to_owned()
+    };
+    match ns.get("uri").and_then(JsonValue::as_str) {
+      Some(uri) => {
+        let local = ns.get("local").and_then(JsonValue::as_str).unwrap_or_else(|| local_name(key));
+        namespace::to_clark(uri, local)
+      },
+      None => key.to_owned()
+    }
+  }
+
+  // Add character data to `content`. Empty strings add nothing, so that elements without any
+  // other content are self-closing
+  pub(crate) fn push_text(&self, node: &JsonValue, content: &mut Vec<Content>) {
+    match node {
+      JsonValue::Array(array) => array.iter().for_each(|child| self.push_text(child, content)),
+      JsonValue::Object(_) => (),
+      JsonValue::Null if self.values.null != NullPolicy::Text => (),
+      _ => {
+        let text = utils::to_string_raw(node);
+        if !text.is_empty() {
+          content.push(Content::Text(text));
+        }
+      }
+    }
+  }
+
+  // Add the character data held under `charkey` at `path` to `content`
+  pub(crate) fn char_data(&self, path: &str, node: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    if self.collisions == CollisionPolicy::Reject && !is_text(node) {
+      return Err(Error::new(ErrorKind::Syntax, format!("Expected character data at {}.", path)));
+    }
+    self.push_text(node, content);
+    Ok(())
+  }
+
+  // Check if the key of an object builds child elements, rather than attributes or text
+  pub(crate) fn is_element_key(&self, key: &str) -> bool {
+    !self.is_attrkey(key) && *key != self.nskey && key != "#name" && self.merged_attr(key).is_none()
+  }
+
+  // Build the content of an element from its JSON node. Keys become child elements, with arrays
+  // repeating the element, `charkey` becomes text and `attrkey` is skipped as it was already used
+  // for the element's start tag
+  pub(crate) fn content(&self, path: &str, node: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    match node {
+      JsonValue::Object(object) => {
+        for (key, child) in object {
+          let path = utils::json_pointer(path, key);
+          if self.is_charkey(key) {
+            self.char_data(&path, child, content)?;
+          } else if *key == self.childkey {
+            self.children(&path, child, content)?;
+          } else if self.is_element_key(key) {
+            self.elements(&path, self.element_key(key), child, content)?;
+          }
+        }
+      },
+      JsonValue::Array(array) => {
+        for (i, child) in array.iter().enumerate() {
+          self.content(&utils::json_pointer(path, &i.to_string()), child, content)?;
+        }
+      },
+      _ => self.push_text(node, content)
+    }
+    Ok(())
+  }
+
+  // Pseudo-attributes of a `__declaration__` child, held under `attrkey`
+  fn declaration(&self, node: &JsonValue) -> Vec<(String, String)> {
+    match node.get(&self.attrkey).and_then(JsonValue::as_object) {
+      Some(object) => object.iter().map(|(k, v)| (k.to_owned(), utils::to_string_raw(v))).collect(),
+      None => Vec::new()
+    }
+  }
+
+  // Build the content held under `childkey`: an array of children in document order, or an object
+  // keyed by element names
+  pub(crate) fn children(&self, path: &str, node: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    let array = match node.as_array() {
+      Some(array) => array,
+      None => return self.content(path, node, content)
+    };
+    for (i, child) in array.iter().enumerate() {
+      let path = utils::json_pointer(path, &i.to_string());
+      let text = || child.get(&self.charkey).map(utils::to_string_raw).unwrap_or_default();
+      match child.get("#name").and_then(JsonValue::as_str) {
+        Some("__text__") => self.push_text(&JsonValue::String(text()), content),
+        Some("__cdata__") => content.push(Content::CData(text())),
+        Some("__comment__") => content.push(tree::comment(&utils::json_pointer(&path, &self.charkey), text())?),
+        Some("__instruction__") => content.push(tree::instruction(&utils::json_pointer(&path, &self.charkey), text())?),
+        Some("__doctype__") => content.push(tree::doctype(&utils::json_pointer(&path, &self.charkey), text())?),
+        Some("__declaration__") => content.push(Content::Declaration(self.declaration(child))),
+        Some(name) => self.push_element(&path, name, child, content)?,
+        None => self.content(&path, child, content)?
+      }
+    }
+    Ok(())
+  }
+
+  // Build the element(s) named `key` from a node at JSON pointer `path`. An array node builds one
+  // element per item
+  pub(crate) fn elements(&self, path: &str, key: &str, node: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    if let Some(array) = node.as_array() {
+      for (i, child) in array.iter().enumerate() {
+        self.push_element(&utils::json_pointer(path, &i.to_string()), key, child, content)?;
+      }
+    } else {
+      self.push_element(path, key, node, content)?;
+    }
+    Ok(())
+  }
+
+  // Build a single element named `key` and add it to `content`, unless `null` elements are omitted
+  pub(crate) fn push_element(&self, path: &str, key: &str, node: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    if !(node.is_null() && self.values.null == NullPolicy::Omit) {
+      content.push(Content::Element(self.element(path, key, node)?));
+    }
+    Ok(())
+  }
+
+  // Build a single element named `key` from its JSON node at JSON pointer `path`
+  fn element(&self, path: &str, key: &str, node: &JsonValue) -> Result<Element, Error> {
+    let mut element = Element::new(self.xml_name(self.element_name(key, node), path)?);
+    match (node, &self.values.array_item) {
+      // A nested array, with one item element per entry
+      (JsonValue::Array(_), Some(item)) => self.elements(path, item, node, &mut element.children)?,
+      (JsonValue::Null, _) if self.values.null == NullPolicy::Nil => {
+        element
+          .attrs
+          .push((namespace::to_clark(namespace::XSI_NAMESPACE, "nil"), "true".to_owned()));
+      },
+      (JsonValue::String(s), _) if !self.empty_tag.is_empty() && *s == self.empty_tag => (),
+      _ => {
+        element.attrs = self.tag_attributes(path, node)?;
+        self.content(path, node, &mut element.children)?;
+      }
+    }
+    Ok(element)
+  }
+
+  // Build the top level nodes of a document
+  pub(crate) fn document(&self, root: &JsonValue) -> Result<Vec<Content>, Error> {
+    // As per node-xml2js - if the root name "root" is used, then it is not added to the produced xml
+    // document. It's unclear if this is a bug or not. Keeping this behavior for now for parity reasons
+    let explicit_root = self.root_name != *"root" || utils::json_object_key_len(root) > 1;
+
+    let mut nodes = Vec::new();
+    if explicit_root {
+      nodes.push(Content::Element(self.element("", &self.root_name, root)?));
+    } else if root.is_array() {
+      self.elements("", &self.root_name, root, &mut nodes)?;
+    } else {
+      self.content("", root, &mut nodes)?;
+    }
+    Ok(nodes)
+  }
+}
+
+// Apply `policy` to an element or attribute name found at `path`. Only the local part of Clark
+// notation names is checked
+pub(crate) fn check_name(policy: &NamePolicy, name: String, path: &str) -> Result<String, Error> {
+  let (uri, local) = match namespace::parse_clark(&name) {
+    Some((uri, local)) => (Some(uri), local),
+    None => (None, name.as_str())
+  };
+  match policy {
+    NamePolicy::Verbatim => return Ok(name),
+    NamePolicy::Reject if name::is_valid(local) => return Ok(name),
+    NamePolicy::Escape if !local.is_empty() => (),
+    _ => return Err(Error::new(ErrorKind::Syntax, format!("Invalid XML name {:?} at {}", local, path)))
+  }
+  let local = name::encode(local);
+  Ok(match uri {
+    Some(uri) => namespace::to_clark(uri, &local),
+    None => local.into_owned()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{json::JsonConfig, xml::XmlConfig};
+
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn is_whitespace1() {
+    let options = JsonOptions::default();
+    assert!(options.is_whitespace(" \t \n "));
+  }
+
+  #[test]
+  fn is_whitespace2() {
+    let options = JsonOptions::default();
+    assert!(!options.is_whitespace(" \t A \n "));
+  }
+
+  #[test]
+  fn assign_or_push1() {
+    let options = JsonOptions::default();
+    let mut actual = json!({});
+    options.assign_or_push(&mut actual, "A", "B".into());
+    options.assign_or_push(&mut actual, "C", "D".into());
+    options.assign_or_push(&mut actual, "C", "E".into());
+    let expected: JsonValue = serde_json::from_str(r#"{"A":["B"],"C":["D","E"]}"#).unwrap();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn assign_or_push2() {
+    let options = JsonConfig::new().explicit_array(false).finalize().options().clone();
+    let mut actual = json!({});
+    options.assign_or_push(&mut actual, "A", "B".into());
+    options.assign_or_push(&mut actual, "C", "D".into());
+    options.assign_or_push(&mut actual, "C", "E".into());
+    let expected: JsonValue = serde_json::from_str(r#"{"A":"B","C":["D","E"]}"#).unwrap();
+    assert_eq!(actual, expected);
+  }
+
+  #[test]
+  fn leaf_node1() {
+    let options = XmlOptions::default();
+    let node = json!({});
+    let is_leaf = !options.element("", "a", &node).unwrap().has_elements();
+    assert!(is_leaf);
+  }
+
+  #[test]
+  fn leaf_node2() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"{"$": {}, "_": {}}"#).unwrap();
+    let is_leaf = !options.element("", "a", &node).unwrap().has_elements();
+    assert!(is_leaf);
+  }
+
+  #[test]
+  fn leaf_node3() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"{"a": {}}"#).unwrap();
+    let is_leaf = !options.element("", "a", &node).unwrap().has_elements();
+    assert!(!is_leaf);
+  }
+
+  #[test]
+  fn leaf_node4() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"{"$": {}, "_": {}, "a": "b"}"#).unwrap();
+    let is_leaf = !options.element("", "a", &node).unwrap().has_elements();
+    assert!(!is_leaf);
+  }
+
+  #[test]
+  fn empty_tag1() {
+    let options = XmlOptions::default();
+    let node = json!({});
+    let is_empty = options.element("", "a", &node).unwrap().is_empty();
+    assert!(is_empty);
+  }
+
+  #[test]
+  fn empty_tag2() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"}}]"#).unwrap();
+    let is_empty = options.element("", "a", &node).unwrap().is_empty();
+    assert!(is_empty);
+  }
+
+  #[test]
+  fn empty_tag3() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"{"$":{"desc":"nodata"},"_":""}"#).unwrap();
+    let is_empty = options.element("", "a", &node).unwrap().is_empty();
+    assert!(is_empty);
+  }
+
+  #[test]
+  fn empty_tag4() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"},"_":"A"}]"#).unwrap();
+    let is_empty = options.element("", "a", &node).unwrap().is_empty();
+    assert!(!is_empty);
+  }
+
+  #[test]
+  fn empty_tag5() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"},"A":{"B":"C"}}]"#).unwrap();
+    let is_empty = options.element("", "a", &node).unwrap().is_empty();
+    assert!(!is_empty);
+  }
+
+  #[test]
+  fn tag_attributes1() {
+    let options = XmlOptions::default();
+    let node = json!({});
+    let attrs = options.tag_attributes("", &node).unwrap();
+    assert!(attrs.is_empty());
+  }
+
+  #[test]
+  fn tag_attributes2() {
+    let options = XmlOptions::default();
+    let node = serde_json::from_str(r#"{"$":{"A":"B","C":"D"}}"#).unwrap();
+    let attrs = options.tag_attributes("", &node).unwrap();
+    assert!(!attrs.is_empty());
+    let mut expected = vec![("A", "B"), ("C", "D")];
+    expected.reverse(); // lazy alternative to a veqdeque
+    for attr in attrs {
+      let (k, v) = attr;
+      let (e_key, e_val) = expected.pop().unwrap();
+      assert_eq!(e_key, k);
+      assert_eq!(e_val, v);
+    }
+  }
+
+  #[test]
+  fn tag_attributes3() {
+    let options = XmlConfig::new().attrkey("^").finalize().options().clone();
+    let node = serde_json::from_str(r#"{"^":{"A":"B","C":"D"}}"#).unwrap();
+    let attrs = options.tag_attributes("", &node).unwrap();
+    assert!(!attrs.is_empty());
+    let mut expected = vec![("A", "B"), ("C", "D")];
+    expected.reverse(); // lazy alternative to a veqdeque
+    for attr in attrs {
+      let (k, v) = attr;
+      let (e_key, e_val) = expected.pop().unwrap();
+      assert_eq!(e_key, k);
+      assert_eq!(e_val, v);
+    }
+  }
+
+  #[test]
+  fn attrkey1() {
+    let options = XmlOptions::default();
+    let is_key = options.is_attrkey("$");
+    assert!(is_key);
+  }
+
+  #[test]
+  fn attrkey2() {
+    let options = XmlConfig::new().attrkey("^").finalize().options().clone();
+    assert!(options.is_attrkey("^"));
+    assert!(!options.is_attrkey("$"));
+  }
+
+  #[test]
+  fn charkey1() {
+    let options = XmlOptions::default();
+    let is_key = options.is_charkey("_");
+    assert!(is_key);
+  }
+
+  #[test]
+  fn charkey2() {
+    let options = XmlConfig::new().charkey("^").finalize().options().clone();
+    assert!(options.is_charkey("^"));
+    assert!(!options.is_charkey("_"));
+  }
+}
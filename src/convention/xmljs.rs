@@ -0,0 +1,441 @@
+use serde_json::{json, Map, Value as JsonValue};
+
+use crate::{
+  convention::{add, text, Convention},
+  error::{Error, ErrorKind},
+  tree::{self, Content, Element},
+  utils
+};
+
+/// The formats of the [xml-js](https://github.com/nashwaan/xml-js) package, with its default
+/// options.
+///
+/// The non-compact format stores every node as an object with a `type`, and the content of
+/// elements in order under `elements`. The compact format stores elements under their names,
+/// attributes under `_attributes` and text, CDATA sections, comments, processing instructions and
+/// the doctype under `_text`, `_cdata`, `_comment`, `_instruction` and `_doctype`; repeated names
+/// become arrays. In both formats whitespace-only text is left out. A document without declaration
+/// is written with the XmlBuilder's declaration, where xml-js writes none.
+///
+/// The formats follow the xml-js documentation, but the tests aren't generated from the package
+/// itself, so the JSON and XML aren't guaranteed to match its output byte for byte.
+///
+/// ```rust
+/// use xml2json_rs::{JsonConfig, XmlConfig, XmlJs};
+///
+/// let xml = r#"<?xml version="1.0" encoding="utf-8"?><note importance="high"><todo>Work</todo><todo>Play</todo></note>"#;
+/// let builder = JsonConfig::new().convention(XmlJs::new().compact(true)).finalize();
+/// let json = builder.build_string_from_xml(xml).unwrap();
+/// assert_eq!(
+///   json,
+///   r#"{"_declaration":{"_attributes":{"version":"1.0","encoding":"utf-8"}},"note":{"_attributes":{"importance":"high"},"todo":[{"_text":"Work"},{"_text":"Play"}]}}"#
+/// );
+///
+/// let mut builder = XmlConfig::new().convention(XmlJs::new().compact(true)).finalize();
+/// assert_eq!(builder.build_from_json_string(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XmlJs {
+  compact: bool
+}
+
+// Object of attributes
+fn attributes(attrs: &[(String, String)]) -> JsonValue {
+  JsonValue::Object(attrs.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect())
+}
+
+// Target and data of a processing instruction
+fn instruction(instruction: &str) -> (&str, &str) {
+  match instruction.split_once(char::is_whitespace) {
+    Some((target, data)) => (target, data.trim_start()),
+    None => (instruction, "")
+  }
+}
+
+// Attributes from an object at JSON pointer `path`. `null` values are left out
+fn attrs(path: &str, value: Option<&JsonValue>) -> Result<Vec<(String, String)>, Error> {
+  let object = match value {
+    Some(JsonValue::Object(object)) => object,
+    None | Some(JsonValue::Null) => return Ok(Vec::new()),
+    Some(_) => {
+      return Err(Error::new(
+        ErrorKind::Syntax,
+        format!("Expected an object of attributes at {}.", path)
+      ))
+    },
+  };
+  let mut attrs = Vec::new();
+  for (key, value) in object {
+    let path = utils::json_pointer(path, key);
+    match value {
+      JsonValue::Null => (),
+      JsonValue::Object(_) | JsonValue::Array(_) => {
+        return Err(Error::new(
+          ErrorKind::Syntax,
+          format!("Expected attribute to be a string at {}.", path)
+        ));
+      },
+      _ => attrs.push((key.clone(), utils::to_string_raw(value)))
+    }
+  }
+  Ok(attrs)
+}
+
+impl XmlJs {
+  /// Initialize the non-compact xml-js format
+  pub fn new() -> XmlJs {
+    XmlJs::default()
+  }
+
+  /// Use the compact format, the `compact` option of xml-js.
+  ///
+  /// (`false` by default)
+  pub fn compact(mut self, flag: bool) -> XmlJs {
+    self.compact = flag;
+    self
+  }
+
+  // Non-compact JSON for a node. Whitespace-only text and declarations have none
+  fn node(&self, content: &Content) -> Option<JsonValue> {
+    Some(match content {
+      Content::Element(e) => {
+        let mut object = Map::new();
+        object.insert("type".to_owned(), "element".into());
+        object.insert("name".to_owned(), e.name.clone().into());
+        if !e.attrs.is_empty() {
+          object.insert("attributes".to_owned(), attributes(&e.attrs));
+        }
+        let elements: Vec<JsonValue> = e.children.iter().filter_map(|c| self.node(c)).collect();
+        if !elements.is_empty() {
+          object.insert("elements".to_owned(), elements.into());
+        }
+        JsonValue::Object(object)
+      },
+      Content::Text(t) if t.trim().is_empty() => return None,
+      Content::Text(t) => json!({"type": "text", "text": t}),
+      Content::CData(t) => json!({"type": "cdata", "cdata": t}),
+      Content::Comment(c) => json!({"type": "comment", "comment": c}),
+      Content::Instruction(i) => {
+        let (target, data) = instruction(i);
+        json!({"type": "instruction", "name": target, "instruction": data})
+      },
+      Content::DocType(d) => json!({"type": "doctype", "doctype": d}),
+      Content::Declaration(_) => return None
+    })
+  }
+
+  // Add compact JSON for `nodes` to `object`
+  fn compact_nodes(&self, nodes: &[Content], object: &mut Map<String, JsonValue>) {
+    for node in nodes {
+      match node {
+        Content::Element(e) => {
+          let mut element = Map::new();
+          if !e.attrs.is_empty() {
+            element.insert("_attributes".to_owned(), attributes(&e.attrs));
+          }
+          self.compact_nodes(&e.children, &mut element);
+          add(object, e.name.clone(), JsonValue::Object(element), &[]);
+        },
+        Content::Text(t) if t.trim().is_empty() => (),
+        Content::Text(t) => add(object, "_text".to_owned(), t.clone().into(), &[]),
+        Content::CData(t) => add(object, "_cdata".to_owned(), t.clone().into(), &[]),
+        Content::Comment(c) => add(object, "_comment".to_owned(), c.clone().into(), &[]),
+        Content::Instruction(i) => {
+          let (target, data) = instruction(i);
+          add(object, "_instruction".to_owned(), json!({ target: data }), &[]);
+        },
+        Content::DocType(d) => add(object, "_doctype".to_owned(), d.clone().into(), &[]),
+        Content::Declaration(attrs) => {
+          let mut declaration = Map::new();
+          if !attrs.is_empty() {
+            declaration.insert("_attributes".to_owned(), attributes(attrs));
+          }
+          object.insert("_declaration".to_owned(), JsonValue::Object(declaration));
+        }
+      }
+    }
+  }
+
+  // Build a node from its non-compact JSON at JSON pointer `path`
+  fn content(&self, path: &str, node: &JsonValue) -> Result<Content, Error> {
+    let field = |key: &str| text(&utils::json_pointer(path, key), node.get(key).unwrap_or(&JsonValue::Null));
+    Ok(match node.get("type").and_then(JsonValue::as_str) {
+      Some("element") => {
+        let name = node
+          .get("name")
+          .and_then(JsonValue::as_str)
+          .ok_or_else(|| Error::new(ErrorKind::Syntax, format!("Expected an element name at {}.", path)))?;
+        let mut element = Element::new(name);
+        element.attrs = attrs(&utils::json_pointer(path, "attributes"), node.get("attributes"))?;
+        self.elements(&utils::json_pointer(path, "elements"), node.get("elements"), &mut element.children)?;
+        Content::Element(element)
+      },
+      Some("text") => Content::Text(field("text")?),
+      Some("cdata") => Content::CData(field("cdata")?),
+      Some("comment") => tree::comment(&utils::json_pointer(path, "comment"), field("comment")?)?,
+      Some("instruction") => {
+        let (target, data) = (field("name")?, field("instruction")?);
+        let instruction = if data.is_empty() { target } else { format!("{} {}", target, data) };
+        tree::instruction(path, instruction)?
+      },
+      Some("doctype") => tree::doctype(&utils::json_pointer(path, "doctype"), field("doctype")?)?,
+      _ => return Err(Error::new(ErrorKind::Syntax, format!("Unknown node type at {}.", path)))
+    })
+  }
+
+  // Build the nodes of an `elements` array at JSON pointer `path`
+  fn elements(&self, path: &str, elements: Option<&JsonValue>, content: &mut Vec<Content>) -> Result<(), Error> {
+    let array = match elements {
+      Some(JsonValue::Array(array)) => array,
+      None | Some(JsonValue::Null) => return Ok(()),
+      Some(_) => return Err(Error::new(ErrorKind::Syntax, format!("Expected an array of nodes at {}.", path)))
+    };
+    for (i, node) in array.iter().enumerate() {
+      content.push(self.content(&utils::json_pointer(path, &i.to_string()), node)?);
+    }
+    Ok(())
+  }
+
+  // Build the nodes of a compact JSON object at JSON pointer `path`. The `_attributes` and
+  // `_declaration` keys are read by the callers
+  fn compact_content(&self, path: &str, object: &Map<String, JsonValue>, content: &mut Vec<Content>) -> Result<(), Error> {
+    for (key, value) in object {
+      let path = utils::json_pointer(path, key);
+      let values: Vec<(String, &JsonValue)> = match value {
+        JsonValue::Array(array) => array
+          .iter()
+          .enumerate()
+          .map(|(i, v)| (utils::json_pointer(&path, &i.to_string()), v))
+          .collect(),
+        _ => vec![(path.clone(), value)]
+      };
+      for (path, value) in values {
+        match key.as_str() {
+          "_attributes" | "_declaration" => (),
+          "_doctype" => content.push(tree::doctype(&path, text(&path, value)?)?),
+          "_text" => content.push(Content::Text(text(&path, value)?)),
+          "_cdata" => content.push(Content::CData(text(&path, value)?)),
+          "_comment" => content.push(tree::comment(&path, text(&path, value)?)?),
+          "_instruction" => {
+            for (target, data) in attrs(&path, Some(value))? {
+              let path = utils::json_pointer(&path, &target);
+              let instruction = if data.is_empty() { target } else { format!("{} {}", target, data) };
+              content.push(tree::instruction(&path, instruction)?);
+            }
+          },
+          name => content.push(Content::Element(self.compact_element(&path, name, value)?))
+        }
+      }
+    }
+    Ok(())
+  }
+
+  // Build an element named `name` from its compact JSON at JSON pointer `path`
+  fn compact_element(&self, path: &str, name: &str, node: &JsonValue) -> Result<Element, Error> {
+    let mut element = Element::new(name);
+    match node {
+      JsonValue::Object(object) => {
+        element.attrs = attrs(&utils::json_pointer(path, "_attributes"), object.get("_attributes"))?;
+        self.compact_content(path, object, &mut element.children)?;
+      },
+      _ => {
+        let text = text(path, node)?;
+        if !text.is_empty() {
+          element.children.push(Content::Text(text));
+        }
+      }
+    }
+    Ok(element)
+  }
+}
+
+impl Convention for XmlJs {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let mut object = Map::new();
+    if self.compact {
+      self.compact_nodes(nodes, &mut object);
+    } else {
+      if let Some(Content::Declaration(attrs)) = nodes.first() {
+        let declaration = if attrs.is_empty() {
+          json!({})
+        } else {
+          json!({ "attributes": attributes(attrs) })
+        };
+        object.insert("declaration".to_owned(), declaration);
+      }
+      let elements: Vec<JsonValue> = nodes.iter().filter_map(|n| self.node(n)).collect();
+      object.insert("elements".to_owned(), elements.into());
+    }
+    Ok(JsonValue::Object(object))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let object = json
+      .as_object()
+      .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected an object of the document."))?;
+    let (declaration, path) = if self.compact {
+      (object.get("_declaration"), "/_declaration/_attributes")
+    } else {
+      (object.get("declaration"), "/declaration/attributes")
+    };
+
+    let mut nodes = Vec::new();
+    if let Some(declaration) = declaration {
+      let key = if self.compact { "_attributes" } else { "attributes" };
+      nodes.push(Content::Declaration(attrs(path, declaration.get(key))?));
+    }
+    if self.compact {
+      self.compact_content("", object, &mut nodes)?;
+    } else {
+      self.elements("/elements", object.get("elements"), &mut nodes)?;
+    }
+    Ok(nodes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, xml::XmlConfig};
+
+  // The example document of the xml-js README
+  const XML: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<note importance="high" logged="true">
+    <title>Happy</title>
+    <todo>Work</todo>
+    <todo>Play</todo>
+</note>"#;
+
+  const COMPACT_XML: &str = r#"<?xml version="1.0" encoding="utf-8"?><note importance="high" logged="true"><title>Happy</title><todo>Work</todo><todo>Play</todo></note>"#;
+
+  fn to_xml(convention: XmlJs, json: &str) -> String {
+    let mut builder = XmlConfig::new().convention(convention).finalize();
+    builder.build_from_json_string(json).unwrap()
+  }
+
+  #[test]
+  fn non_compact() {
+    let (json, output) = roundtrip(XmlJs::new(), XML);
+    assert_eq!(
+      json.to_string(),
+      r#"{"declaration":{"attributes":{"version":"1.0","encoding":"utf-8"}},"elements":[{"type":"element","name":"note","attributes":{"importance":"high","logged":"true"},"elements":[{"type":"element","name":"title","elements":[{"type":"text","text":"Happy"}]},{"type":"element","name":"todo","elements":[{"type":"text","text":"Work"}]},{"type":"element","name":"todo","elements":[{"type":"text","text":"Play"}]}]}]}"#
+    );
+    assert_eq!(output, COMPACT_XML);
+  }
+
+  #[test]
+  fn compact() {
+    let (json, output) = roundtrip(XmlJs::new().compact(true), XML);
+    assert_eq!(
+      json.to_string(),
+      r#"{"_declaration":{"_attributes":{"version":"1.0","encoding":"utf-8"}},"note":{"_attributes":{"importance":"high","logged":"true"},"title":{"_text":"Happy"},"todo":[{"_text":"Work"},{"_text":"Play"}]}}"#
+    );
+    assert_eq!(output, COMPACT_XML);
+  }
+
+  #[test]
+  fn other_nodes() {
+    let xml = r#"<?xml version="1.0"?><!--top--><a>x<![CDATA[<y>]]><?go there?><!-- z --><b/></a>"#;
+    let (json, output) = roundtrip(XmlJs::new(), xml);
+    assert_eq!(
+      json.to_string(),
+      r#"{"declaration":{"attributes":{"version":"1.0"}},"elements":[{"type":"comment","comment":"top"},{"type":"element","name":"a","elements":[{"type":"text","text":"x"},{"type":"cdata","cdata":"<y>"},{"type":"instruction","name":"go","instruction":"there"},{"type":"comment","comment":" z "},{"type":"element","name":"b"}]}]}"#
+    );
+    assert_eq!(output, xml);
+
+    let (json, output) = roundtrip(XmlJs::new().compact(true), xml);
+    assert_eq!(
+      json.to_string(),
+      r#"{"_declaration":{"_attributes":{"version":"1.0"}},"_comment":"top","a":{"_text":"x","_cdata":"<y>","_instruction":{"go":"there"},"_comment":" z ","b":{}}}"#
+    );
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn doctype() {
+    let xml = r#"<?xml version="1.0"?><!DOCTYPE note SYSTEM "Note.dtd"><note/>"#;
+    let (json, output) = roundtrip(XmlJs::new(), xml);
+    assert_eq!(
+      json.to_string(),
+      r#"{"declaration":{"attributes":{"version":"1.0"}},"elements":[{"type":"doctype","doctype":"note SYSTEM \"Note.dtd\""},{"type":"element","name":"note"}]}"#
+    );
+    assert_eq!(output, xml);
+
+    let (json, output) = roundtrip(XmlJs::new().compact(true), xml);
+    assert_eq!(
+      json.to_string(),
+      r#"{"_declaration":{"_attributes":{"version":"1.0"}},"_doctype":"note SYSTEM \"Note.dtd\"","note":{}}"#
+    );
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn repeated_text() {
+    let (json, output) = roundtrip(XmlJs::new().compact(true), "<a>x<b/>y</a>");
+    assert_eq!(json.to_string(), r#"{"a":{"_text":["x","y"],"b":{}}}"#);
+    // The compact form doesn't keep the order of text and elements
+    assert_eq!(output, r#"<?xml version="1.0"?><a>xy<b/></a>"#);
+  }
+
+  #[test]
+  fn without_declaration() {
+    let xml = to_xml(XmlJs::new(), r#"{"elements":[{"type":"element","name":"a"}]}"#);
+    assert_eq!(xml, r#"<?xml version="1.0"?><a/>"#);
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut builder = XmlConfig::new().convention(XmlJs::new()).finalize();
+    let error = builder
+      .build_from_json_string(r#"{"elements":[{"type":"element","elements":[{"type":"node"}]}]}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Expected an element name at /elements/0.");
+
+    let error = builder
+      .build_from_json_string(r#"{"elements":[{"type":"element","name":"a","elements":[{"type":"node"}]}]}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Unknown node type at /elements/0/elements/0.");
+
+    let mut builder = XmlConfig::new().convention(XmlJs::new().compact(true)).finalize();
+    let error = builder.build_from_json_string(r#"{"a":{"_attributes":{"b":[1]}}}"#).unwrap_err();
+    assert_eq!(error.details(), "Expected attribute to be a string at /a/_attributes/b.");
+
+    let error = builder.build_from_json_string(r#"{"a":{"_comment":["x","y-"]}}"#).unwrap_err();
+    assert_eq!(error.details(), "Invalid comment at /a/_comment/1.");
+
+    let mut builder = XmlConfig::new().convention(XmlJs::new()).finalize();
+    let error = builder
+      .build_from_json_string(r#"{"elements":[{"type":"comment","comment":"a--b"}]}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid comment at /elements/0/comment.");
+  }
+
+  #[test]
+  fn invalid_instruction_and_doctype() {
+    let mut builder = XmlConfig::new().convention(XmlJs::new()).finalize();
+    let error = builder
+      .build_from_json_string(r#"{"elements":[{"type":"instruction","name":"a?>b","instruction":""},{"type":"element","name":"c"}]}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid processing instruction at /elements/0.");
+    assert!(builder
+      .build_from_json_string(r#"{"elements":[{"type":"instruction","name":"a","instruction":"x?>"}]}"#)
+      .is_err());
+    let error = builder
+      .build_from_json_string(r#"{"elements":[{"type":"doctype","doctype":"a [<!ENTITY x \"y\">]> <evil"},{"type":"element","name":"a"}]}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid doctype at /elements/0/doctype.");
+
+    let mut builder = XmlConfig::new().convention(XmlJs::new().compact(true)).finalize();
+    let error = builder
+      .build_from_json_string(r#"{"_instruction":{"a?>b":""},"c":{}}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid processing instruction at /_instruction/a?>b.");
+    assert!(builder
+      .build_from_json_string(r#"{"_instruction":{"xml":"version=\"1.0\""},"c":{}}"#)
+      .is_err());
+    let error = builder
+      .build_from_json_string(r#"{"_doctype":"a [<!ENTITY x \"y\">]> <evil","a":{}}"#)
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid doctype at /_doctype.");
+  }
+}
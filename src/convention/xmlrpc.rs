@@ -0,0 +1,311 @@
+use std::convert::TryFrom;
+
+use serde_json::{json, Map, Number, Value as JsonValue};
+
+use crate::{
+  convention::{document_element, tagged, Convention},
+  error::{Error, ErrorKind},
+  tree::{Content, Element},
+  utils
+};
+
+// First child element named `name`, which `element` is expected to have
+fn expect_child<'a>(element: &'a Element, name: &str) -> Result<&'a Element, Error> {
+  element
+    .child(name)
+    .ok_or_else(|| Error::new(ErrorKind::Syntax, format!("Expected <{}> in <{}>.", name, element.name)))
+}
+
+// Types of tagged values
+const TAGGED: [&str; 2] = ["dateTime.iso8601", "base64"];
+
+// Element `name` with `children`
+fn parent(name: &str, children: Vec<Element>) -> Element {
+  let mut element = Element::new(name);
+  element.children.extend(children.into_iter().map(Content::Element));
+  element
+}
+
+/// The [XML-RPC](http://xmlrpc.com/spec.md) payload format.
+///
+/// A `methodCall` is an object of its `methodName` and an array of its `params`, a
+/// `methodResponse` an object of its `params`, or of the value of its `fault`. Values are typed:
+/// `<int>`, `<i4>`, `<i8>` and `<double>` are numbers, `<boolean>` booleans, `<array>` arrays,
+/// `<struct>` objects and the `<nil/>` extension `null`. `<string>` and untyped values are
+/// strings. `<dateTime.iso8601>` and `<base64>` are tagged objects of their text, with the
+/// whitespace of `<base64>` removed: `{"$dateTime.iso8601": "19980717T14:08:55"}` and
+/// `{"$base64": "eW91"}`.
+///
+/// Building XML writes integers which don't fit in 32 bits as `<double>`, like other numbers, all
+/// strings as `<string>` and tagged objects back as their type. A struct with a single string
+/// member named `$dateTime.iso8601` or `$base64` is therefore written as that type.
+///
+/// ```rust
+/// use serde_json::json;
+/// use xml2json_rs::{JsonConfig, XmlConfig, XmlRpc};
+///
+/// let xml = r#"<?xml version="1.0"?><methodCall><methodName>examples.getStateName</methodName><params><param><value><int>41</int></value></param></params></methodCall>"#;
+/// let json = JsonConfig::new().convention(XmlRpc).finalize().build_from_xml(xml).unwrap();
+/// assert_eq!(json, json!({"methodName": "examples.getStateName", "params": [41]}));
+///
+/// let mut builder = XmlConfig::new().convention(XmlRpc).finalize();
+/// assert_eq!(builder.build_from_json(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XmlRpc;
+
+impl XmlRpc {
+  // JSON value of a `<value>` element
+  fn value(&self, value: &Element) -> Result<JsonValue, Error> {
+    let typed = match value.elements().next() {
+      Some(typed) => typed,
+      None => return Ok(value.text().into())
+    };
+    let invalid = |kind: &str| Error::new(ErrorKind::Syntax, format!("Invalid {} \"{}\".", kind, typed.text().trim()));
+
+    Ok(match typed.name.as_str() {
+      "int" | "i4" | "i8" => typed.text().trim().parse::<i64>().map_err(|_| invalid("int"))?.into(),
+      "boolean" => match typed.text().trim() {
+        "0" => JsonValue::Bool(false),
+        "1" => JsonValue::Bool(true),
+        _ => return Err(invalid("boolean"))
+      },
+      "double" => {
+        let double = typed.text().trim().parse::<f64>().map_err(|_| invalid("double"))?;
+        JsonValue::Number(Number::from_f64(double).ok_or_else(|| invalid("double"))?)
+      },
+      "string" => typed.text().into(),
+      "dateTime.iso8601" => json!({"$dateTime.iso8601": typed.text().trim()}),
+      "base64" => json!({"$base64": typed.text().split_whitespace().collect::<String>()}),
+      "nil" => JsonValue::Null,
+      "array" => {
+        let data = expect_child(typed, "data")?;
+        let values = data.elements().filter(|e| e.name == "value");
+        JsonValue::Array(values.map(|v| self.value(v)).collect::<Result<_, _>>()?)
+      },
+      "struct" => {
+        let mut object = Map::new();
+        for member in typed.elements().filter(|e| e.name == "member") {
+          let name = expect_child(member, "name")?.text();
+          object.insert(name, self.value(expect_child(member, "value")?)?);
+        }
+        JsonValue::Object(object)
+      },
+      name => {
+        return Err(Error::new(ErrorKind::Syntax, format!("Unknown XML-RPC value <{}>.", name)));
+      }
+    })
+  }
+
+  // Values of the `<params>` of a call or response
+  fn params(&self, element: &Element) -> Result<JsonValue, Error> {
+    let mut params = Vec::new();
+    if let Some(list) = element.child("params") {
+      for param in list.elements().filter(|e| e.name == "param") {
+        params.push(self.value(expect_child(param, "value")?)?);
+      }
+    }
+    Ok(JsonValue::Array(params))
+  }
+
+  // `<value>` element of a JSON value
+  fn element(&self, value: &JsonValue) -> Element {
+    let typed = match value {
+      JsonValue::Null => Element::new("nil"),
+      JsonValue::Bool(b) => Element::with_text("boolean", if *b { "1" } else { "0" }),
+      JsonValue::Number(n) => match n.as_i64().filter(|n| i32::try_from(*n).is_ok()) {
+        Some(int) => Element::with_text("int", int.to_string()),
+        // Doubles are written without exponent
+        None => Element::with_text("double", n.as_f64().unwrap_or_default().to_string())
+      },
+      JsonValue::String(s) => Element::with_text("string", s.clone()),
+      JsonValue::Array(array) => {
+        let data = parent("data", array.iter().map(|v| self.element(v)).collect());
+        parent("array", vec![data])
+      },
+      JsonValue::Object(object) => match tagged(object, &TAGGED) {
+        Some((name, text)) => Element::with_text(name, text),
+        None => {
+          let members = object
+            .iter()
+            .map(|(name, value)| parent("member", vec![Element::with_text("name", name.clone()), self.element(value)]))
+            .collect();
+          parent("struct", members)
+        }
+      }
+    };
+    parent("value", vec![typed])
+  }
+
+  // `<params>` element of the JSON params found at JSON pointer `path`
+  fn params_element(&self, path: &str, params: Option<&JsonValue>) -> Result<Element, Error> {
+    let params = match params {
+      Some(JsonValue::Array(array)) => array.as_slice(),
+      None | Some(JsonValue::Null) => &[],
+      Some(_) => return Err(Error::new(ErrorKind::Syntax, format!("Expected an array of params at {}.", path)))
+    };
+    let params = params.iter().map(|v| parent("param", vec![self.element(v)])).collect();
+    Ok(parent("params", params))
+  }
+}
+
+impl Convention for XmlRpc {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let root = document_element(nodes)?;
+    match root.name.as_str() {
+      "methodCall" => {
+        let name = expect_child(root, "methodName")?.text();
+        Ok(json!({"methodName": name.trim(), "params": self.params(root)?}))
+      },
+      "methodResponse" => match root.child("fault") {
+        Some(fault) => Ok(json!({"fault": self.value(expect_child(fault, "value")?)?})),
+        None => Ok(json!({"params": self.params(root)?}))
+      },
+      _ => Err(Error::new(
+        ErrorKind::Syntax,
+        "Expected a methodCall or methodResponse document element."
+      ))
+    }
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let expected = || Error::new(ErrorKind::Syntax, "Expected an object of a methodCall or methodResponse.");
+    let object = json.as_object().ok_or_else(expected)?;
+
+    let root = if let Some(name) = object.get("methodName") {
+      let path = utils::json_pointer("", "methodName");
+      let name = name
+        .as_str()
+        .ok_or_else(|| Error::new(ErrorKind::Syntax, format!("Expected a string at {}.", path)))?;
+      let params = self.params_element("/params", object.get("params"))?;
+      parent("methodCall", vec![Element::with_text("methodName", name), params])
+    } else if let Some(fault) = object.get("fault") {
+      parent("methodResponse", vec![parent("fault", vec![self.element(fault)])])
+    } else if object.contains_key("params") {
+      parent("methodResponse", vec![self.params_element("/params", object.get("params"))?])
+    } else {
+      return Err(expected());
+    };
+    Ok(vec![Content::Element(root)])
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+
+  fn to_json(xml: &str) -> Result<JsonValue, Error> {
+    JsonConfig::new().convention(XmlRpc).finalize().build_from_xml(xml)
+  }
+
+  fn to_xml(json: &JsonValue) -> Result<String, Error> {
+    XmlConfig::new().convention(XmlRpc).finalize().build_from_json(json)
+  }
+
+  // The examples of the XML-RPC specification
+
+  #[test]
+  fn method_call() {
+    let xml = r#"<?xml version="1.0"?>
+<methodCall>
+  <methodName>examples.getStateName</methodName>
+  <params>
+    <param>
+      <value><i4>41</i4></value>
+    </param>
+  </params>
+</methodCall>"#;
+    let json = to_json(xml).unwrap();
+    assert_eq!(json, json!({"methodName": "examples.getStateName", "params": [41]}));
+  }
+
+  #[test]
+  fn method_response() {
+    let xml = r#"<?xml version="1.0"?><methodResponse><params><param><value><string>South Dakota</string></value></param></params></methodResponse>"#;
+    let (json, output) = roundtrip(XmlRpc, xml);
+    assert_eq!(json, json!({"params": ["South Dakota"]}));
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn fault() {
+    let xml = r#"<?xml version="1.0"?><methodResponse><fault><value><struct><member><name>faultCode</name><value><int>4</int></value></member><member><name>faultString</name><value><string>Too many parameters.</string></value></member></struct></value></fault></methodResponse>"#;
+    let (json, output) = roundtrip(XmlRpc, xml);
+    assert_eq!(json, json!({"fault": {"faultCode": 4, "faultString": "Too many parameters."}}));
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn values() {
+    let xml = r#"<methodResponse><params>
+      <param><value><array><data><value><i4>12</i4></value><value><string>Egypt</string></value><value><boolean>0</boolean></value><value><i4>-31</i4></value></data></array></value></param>
+      <param><value><struct><member><name>lowerBound</name><value><i4>18</i4></value></member><member><name>upperBound</name><value><i4>139</i4></value></member></struct></value></param>
+      <param><value>plain</value></param>
+      <param><value><double>-12.214</double></value></param>
+      <param><value><dateTime.iso8601>19980717T14:08:55</dateTime.iso8601></value></param>
+      <param><value><base64>eW91IGNhbid0IHJlYWQgdGhpcyE=
+      </base64></value></param>
+      <param><value><nil/></value></param>
+    </params></methodResponse>"#;
+    let json = to_json(xml).unwrap();
+    assert_eq!(
+      json,
+      json!({"params": [
+        [12, "Egypt", false, -31],
+        {"lowerBound": 18, "upperBound": 139},
+        "plain",
+        -12.214,
+        {"$dateTime.iso8601": "19980717T14:08:55"},
+        {"$base64": "eW91IGNhbid0IHJlYWQgdGhpcyE="},
+        null
+      ]})
+    );
+  }
+
+  #[test]
+  fn tagged_values() {
+    let xml = r#"<?xml version="1.0"?><methodCall><methodName>m</methodName><params><param><value><dateTime.iso8601>19980717T14:08:55</dateTime.iso8601></value></param><param><value><base64>eW91IGNhbid0IHJlYWQgdGhpcyE=</base64></value></param><param><value><struct><member><name>$base64</name><value><int>1</int></value></member></struct></value></param></params></methodCall>"#;
+    let (json, output) = roundtrip(XmlRpc, xml);
+    assert_eq!(
+      json,
+      json!({"methodName": "m", "params": [
+        {"$dateTime.iso8601": "19980717T14:08:55"},
+        {"$base64": "eW91IGNhbid0IHJlYWQgdGhpcyE="},
+        {"$base64": 1}
+      ]})
+    );
+    assert_eq!(output, xml);
+  }
+
+  #[test]
+  fn build_values() {
+    let xml = to_xml(&json!({"methodName": "m", "params": [true, 5000000000i64, 1e21, [], {}, null]})).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><methodCall><methodName>m</methodName><params><param><value><boolean>1</boolean></value></param><param><value><double>5000000000</double></value></param><param><value><double>1000000000000000000000</double></value></param><param><value><array><data/></array></value></param><param><value><struct/></value></param><param><value><nil/></value></param></params></methodCall>"#
+    );
+  }
+
+  #[test]
+  fn invalid_documents() {
+    let error = to_json("<call/>").unwrap_err();
+    assert_eq!(error.details(), "Expected a methodCall or methodResponse document element.");
+
+    let error = to_json("<methodCall><params/></methodCall>").unwrap_err();
+    assert_eq!(error.details(), "Expected <methodName> in <methodCall>.");
+
+    let error =
+      to_json("<methodResponse><params><param><value><boolean>yes</boolean></value></param></params></methodResponse>").unwrap_err();
+    assert_eq!(error.details(), "Invalid boolean \"yes\".");
+
+    let error = to_xml(&json!({"methodName": 1})).unwrap_err();
+    assert_eq!(error.details(), "Expected a string at /methodName.");
+
+    let error = to_xml(&json!({"methodName": "m", "params": {}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an array of params at /params.");
+
+    let error = to_xml(&json!([])).unwrap_err();
+    assert_eq!(error.details(), "Expected an object of a methodCall or methodResponse.");
+  }
+}
@@ -0,0 +1,474 @@
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+  convention::{add, text, Convention},
+  error::{Error, ErrorKind},
+  namespace::{self, XML_NAMESPACE},
+  tree::{self, Content, Element},
+  utils
+};
+
+// Key of comments, as in xmltodict
+const COMMENT_KEY: &str = "#comment";
+
+/// The mapping of Python's [xmltodict](https://github.com/martinblech/xmltodict) `parse` and
+/// `unparse`, with the same options and defaults.
+///
+/// Elements are objects of their attributes, under their name prefixed with `attr_prefix`, and of
+/// their child elements; their text goes under `cdata_key`, after the child elements. Elements
+/// with text only are strings, empty elements are `null` and repeated elements are arrays. All
+/// text of an element is joined, and whitespace around it is stripped.
+///
+/// With `process_namespaces`, names in a namespace are written as `uri:local`, or with a short
+/// name configured for the URI, and the namespaces declared by an element go under the `xmlns`
+/// attribute. Building XML reads such names back, and prefixes are given to their namespaces.
+///
+/// ```rust
+/// use xml2json_rs::{JsonConfig, XmlConfig, XmlToDict};
+///
+/// let xml = r#"<?xml version="1.0"?><a prop="x"><b>1</b><b>2</b><c>3</c></a>"#;
+/// let builder = JsonConfig::new()
+///   .convention(XmlToDict::new().force_list("c"))
+///   .finalize();
+/// let json = builder.build_string_from_xml(xml).unwrap();
+/// assert_eq!(json, r#"{"a":{"@prop":"x","b":["1","2"],"c":["3"]}}"#);
+///
+/// let mut builder = XmlConfig::new().convention(XmlToDict::new()).finalize();
+/// assert_eq!(builder.build_from_json_string(&json).unwrap(), xml);
+/// ```
+#[derive(Debug, Clone)]
+pub struct XmlToDict {
+  attr_prefix:        String,
+  cdata_key:          String,
+  force_list:         Vec<String>,
+  process_namespaces: bool,
+  namespaces:         Vec<(String, String)>,
+  xml_attribs:        bool,
+  strip_whitespace:   bool,
+  process_comments:   bool
+}
+
+impl XmlToDict {
+  /// Initialize the xmltodict mapping with its default options
+  pub fn new() -> XmlToDict {
+    XmlToDict {
+      attr_prefix:        "@".to_owned(),
+      cdata_key:          "#text".to_owned(),
+      force_list:         Vec::new(),
+      process_namespaces: false,
+      namespaces:         Vec::new(),
+      xml_attribs:        true,
+      strip_whitespace:   true,
+      process_comments:   false
+    }
+  }
+
+  /// Prefix of attribute keys.
+  ///
+  /// (`"@"` by default)
+  pub fn attr_prefix<T: Into<String>>(mut self, prefix: T) -> XmlToDict {
+    self.attr_prefix = prefix.into();
+    self
+  }
+
+  /// Key of the text of elements with attributes or child elements.
+  ///
+  /// (`"#text"` by default)
+  pub fn cdata_key<T: Into<String>>(mut self, key: T) -> XmlToDict {
+    self.cdata_key = key.into();
+    self
+  }
+
+  /// Always store the values of `key` in an array, even when it's not repeated. Can be called
+  /// multiple times to add keys.
+  pub fn force_list<T: Into<String>>(mut self, key: T) -> XmlToDict {
+    self.force_list.push(key.into());
+    self
+  }
+
+  /// Resolve namespace prefixes into the namespace URI of names.
+  ///
+  /// (`false` by default)
+  pub fn process_namespaces(mut self, flag: bool) -> XmlToDict {
+    self.process_namespaces = flag;
+    self
+  }
+
+  /// Write names in namespace `uri` with the `short` name instead of the URI, or without it if
+  /// `short` is empty. Can be called multiple times to add namespaces.
+  pub fn namespace<U: Into<String>, S: Into<String>>(mut self, uri: U, short: S) -> XmlToDict {
+    self.namespaces.push((uri.into(), short.into()));
+    self
+  }
+
+  /// Include attributes.
+  ///
+  /// (`true` by default)
+  pub fn xml_attribs(mut self, flag: bool) -> XmlToDict {
+    self.xml_attribs = flag;
+    self
+  }
+
+  /// Strip whitespace around text and comments, and leave out whitespace-only text.
+  ///
+  /// (`true` by default)
+  pub fn strip_whitespace(mut self, flag: bool) -> XmlToDict {
+    self.strip_whitespace = flag;
+    self
+  }
+
+  /// Include comments under `#comment`. Building XML always writes them.
+  ///
+  /// (`false` by default)
+  pub fn process_comments(mut self, flag: bool) -> XmlToDict {
+    self.process_comments = flag;
+    self
+  }
+
+  // Key of a name given as `uri:local` or a local name, with the configured short name of its
+  // namespace
+  fn short_name(&self, name: String) -> String {
+    let (uri, local) = match name.rsplit_once(':') {
+      Some(parts) => parts,
+      None => return name
+    };
+    match self.namespaces.iter().find(|(u, _)| u == uri) {
+      Some((_, short)) if short.is_empty() => local.to_owned(),
+      Some((_, short)) => format!("{}:{}", short, local),
+      None => name
+    }
+  }
+
+  // Full name of an element or attribute, as `uri:local` when `process_namespaces` is set and it's
+  // in a namespace. Unprefixed attributes are in no namespace
+  fn full_name(&self, name: &str, scope: &[(String, String)], attr: bool) -> String {
+    if !self.process_namespaces {
+      return name.to_owned();
+    }
+    let (uri, local) = namespace::resolve(scope, name, attr);
+    // The `xml` prefix is bound without a declaration
+    let uri = match uri {
+      None if namespace::prefix_of(name) == "xml" => Some(XML_NAMESPACE),
+      uri => uri
+    };
+    uri.map_or_else(|| name.to_owned(), |uri| format!("{}:{}", uri, local))
+  }
+
+  // Key and value of an element, with the namespaces in scope of its parent
+  fn element(&self, element: &Element, scope: &[(String, String)]) -> (String, JsonValue) {
+    let scope = namespace::declare(scope, element);
+    let mut declarations = Map::new();
+    let mut attrs = Vec::new();
+    for (name, value) in &element.attrs {
+      let prefix = match name.strip_prefix("xmlns") {
+        Some("") => Some(""),
+        Some(rest) => rest.strip_prefix(':'),
+        None => None
+      };
+      match prefix {
+        Some(prefix) if self.process_namespaces => {
+          // An undeclared default namespace is `null`
+          let uri = if value.is_empty() { JsonValue::Null } else { value.clone().into() };
+          declarations.insert(prefix.to_owned(), uri);
+        },
+        _ => attrs.push((name, value))
+      }
+    }
+
+    let mut item = None;
+    if self.xml_attribs {
+      let mut map = Map::new();
+      for (name, value) in attrs {
+        let key = self.short_name(self.full_name(name, &scope, true));
+        map.insert(format!("{}{}", self.attr_prefix, key), value.clone().into());
+      }
+      if !declarations.is_empty() {
+        map.insert(format!("{}xmlns", self.attr_prefix), JsonValue::Object(declarations));
+      }
+      if !map.is_empty() {
+        item = Some(map);
+      }
+    }
+
+    let mut data = String::new();
+    for child in &element.children {
+      match child {
+        Content::Element(e) => {
+          let (key, value) = self.element(e, &scope);
+          add(item.get_or_insert_with(Map::new), key, value, &self.force_list);
+        },
+        Content::Text(t) | Content::CData(t) => data.push_str(t),
+        Content::Comment(c) if self.process_comments => {
+          let comment = if self.strip_whitespace { c.trim() } else { c };
+          add(
+            item.get_or_insert_with(Map::new),
+            COMMENT_KEY.to_owned(),
+            comment.into(),
+            &self.force_list
+          );
+        },
+        _ => ()
+      }
+    }
+    let data = if self.strip_whitespace { data.trim() } else { &data };
+
+    let value = match item {
+      Some(mut item) => {
+        if !data.is_empty() {
+          add(&mut item, self.cdata_key.clone(), data.into(), &self.force_list);
+        }
+        JsonValue::Object(item)
+      },
+      None if data.is_empty() => JsonValue::Null,
+      None => data.into()
+    };
+    (self.short_name(self.full_name(&element.name, &scope, false)), value)
+  }
+
+  // Name of an element or attribute built from its key. With `process_namespaces`, names in a
+  // namespace are written in Clark notation
+  fn xml_name(&self, key: &str) -> String {
+    match key.rsplit_once(':') {
+      Some((namespace, local)) if self.process_namespaces => {
+        let uri = self.namespaces.iter().find(|(_, s)| s == namespace).map_or(namespace, |(u, _)| u);
+        namespace::to_clark(uri, local)
+      },
+      _ => key.to_owned()
+    }
+  }
+
+  // Build the comments found at JSON pointer `path`
+  fn comments(&self, path: &str, value: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    let comments = match value {
+      JsonValue::Array(array) => array
+        .iter()
+        .enumerate()
+        .map(|(i, v)| (utils::json_pointer(path, &i.to_string()), v))
+        .collect(),
+      _ => vec![(path.to_owned(), value)]
+    };
+    for (path, value) in comments {
+      let comment = text(&path, value)?;
+      if !comment.is_empty() {
+        content.push(tree::comment(&path, comment)?);
+      }
+    }
+    Ok(())
+  }
+
+  // Build the element(s) named after `key` from a value at JSON pointer `path`. An array builds one
+  // element per item
+  fn elements(&self, path: &str, key: &str, value: &JsonValue, content: &mut Vec<Content>) -> Result<(), Error> {
+    match value.as_array() {
+      Some(array) => {
+        for (i, item) in array.iter().enumerate() {
+          let element = self.xml_element(&utils::json_pointer(path, &i.to_string()), key, item)?;
+          content.push(Content::Element(element));
+        }
+      },
+      None => content.push(Content::Element(self.xml_element(path, key, value)?))
+    }
+    Ok(())
+  }
+
+  // Build a single element named after `key` from its value at JSON pointer `path`. Text is written
+  // after the child elements
+  fn xml_element(&self, path: &str, key: &str, value: &JsonValue) -> Result<Element, Error> {
+    let mut element = Element::new(self.xml_name(key));
+    let mut data = String::new();
+    match value {
+      JsonValue::Object(object) => {
+        for (key, value) in object {
+          let path = utils::json_pointer(path, key);
+          if *key == self.cdata_key {
+            data = text(&path, value)?;
+          } else if let Some(attr) = key.strip_prefix(self.attr_prefix.as_str()) {
+            match value {
+              JsonValue::Object(declarations) if attr == "xmlns" => {
+                for (prefix, uri) in declarations {
+                  let name = if prefix.is_empty() {
+                    "xmlns".to_owned()
+                  } else {
+                    format!("xmlns:{}", prefix)
+                  };
+                  element.attrs.push((name, text(&utils::json_pointer(&path, prefix), uri)?));
+                }
+              },
+              JsonValue::Object(_) | JsonValue::Array(_) => {
+                return Err(Error::new(
+                  ErrorKind::Syntax,
+                  format!("Expected attribute to be a string at {}.", path)
+                ));
+              },
+              _ => element.attrs.push((self.xml_name(attr), text(&path, value)?))
+            }
+          } else if key == COMMENT_KEY {
+            self.comments(&path, value, &mut element.children)?;
+          } else {
+            self.elements(&path, key, value, &mut element.children)?;
+          }
+        }
+      },
+      JsonValue::Array(_) => {
+        return Err(Error::new(ErrorKind::Syntax, format!("Expected element content at {}.", path)));
+      },
+      _ => data = text(path, value)?
+    }
+    if !data.is_empty() {
+      element.children.push(Content::Text(data));
+    }
+    Ok(element)
+  }
+}
+
+impl Default for XmlToDict {
+  fn default() -> XmlToDict {
+    XmlToDict::new()
+  }
+}
+
+impl Convention for XmlToDict {
+  fn to_json(&self, nodes: &[Content]) -> Result<JsonValue, Error> {
+    let mut document = Map::new();
+    for node in nodes {
+      match node {
+        Content::Element(e) => {
+          let (key, value) = self.element(e, &[]);
+          add(&mut document, key, value, &self.force_list);
+        },
+        Content::Comment(c) if self.process_comments => {
+          let comment = if self.strip_whitespace { c.trim() } else { c };
+          add(&mut document, COMMENT_KEY.to_owned(), comment.into(), &self.force_list);
+        },
+        _ => ()
+      }
+    }
+    Ok(JsonValue::Object(document))
+  }
+
+  fn to_xml(&self, json: &JsonValue) -> Result<Vec<Content>, Error> {
+    let expected = || Error::new(ErrorKind::Syntax, "Expected an object with a single document element.");
+    let object = json.as_object().ok_or_else(expected)?;
+
+    let mut nodes = Vec::new();
+    for (key, value) in object {
+      let path = utils::json_pointer("", key);
+      if key == COMMENT_KEY {
+        self.comments(&path, value, &mut nodes)?;
+      } else {
+        self.elements(&path, key, value, &mut nodes)?;
+      }
+    }
+    if nodes.iter().filter(|n| matches!(n, Content::Element(_))).count() != 1 {
+      return Err(expected());
+    }
+    Ok(nodes)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{convention::roundtrip, json::JsonConfig, xml::XmlConfig};
+
+  fn to_json(convention: XmlToDict, xml: &str) -> String {
+    let builder = JsonConfig::new().convention(convention).finalize();
+    builder.build_string_from_xml(xml).unwrap()
+  }
+
+  fn to_xml(convention: XmlToDict, json: &str) -> String {
+    let mut builder = XmlConfig::new().convention(convention).finalize();
+    builder.build_from_json_string(json).unwrap()
+  }
+
+  #[test]
+  fn text_and_attributes() {
+    let (json, output) = roundtrip(XmlToDict::new(), r#"<a x="1">t<b/>u<c>  v </c></a>"#);
+    assert_eq!(json.to_string(), r##"{"a":{"@x":"1","b":null,"c":"v","#text":"tu"}}"##);
+    assert_eq!(output, r#"<?xml version="1.0"?><a x="1"><b/><c>v</c>tu</a>"#);
+  }
+
+  #[test]
+  fn options() {
+    let convention = XmlToDict::new()
+      .attr_prefix("_")
+      .cdata_key("text")
+      .force_list("b")
+      .force_list("text")
+      .strip_whitespace(false);
+    let (json, output) = roundtrip(convention.clone(), r#"<a x="1"><b> y </b></a>"#);
+    assert_eq!(json.to_string(), r#"{"a":{"_x":"1","b":[" y "]}}"#);
+    assert_eq!(output, r#"<?xml version="1.0"?><a x="1"><b> y </b></a>"#);
+
+    let json = to_json(convention, r#"<a x="1"> y </a>"#);
+    assert_eq!(json, r#"{"a":{"_x":"1","text":[" y "]}}"#);
+
+    let json = to_json(XmlToDict::new().xml_attribs(false), r#"<a x="1"><b y="2">c</b></a>"#);
+    assert_eq!(json, r#"{"a":{"b":"c"}}"#);
+  }
+
+  #[test]
+  fn comments() {
+    let xml = "<!-- top --><a><!-- b --><c>1<!--d--></c></a>";
+    assert_eq!(to_json(XmlToDict::new(), xml), r#"{"a":{"c":"1"}}"#);
+
+    let (json, output) = roundtrip(XmlToDict::new().process_comments(true), xml);
+    assert_eq!(
+      json.to_string(),
+      r##"{"#comment":"top","a":{"#comment":"b","c":{"#comment":"d","#text":"1"}}}"##
+    );
+    assert_eq!(output, r#"<?xml version="1.0"?><!--top--><a><!--b--><c><!--d-->1</c></a>"#);
+  }
+
+  #[test]
+  fn namespaces() {
+    let xml = r#"<a xmlns="http://a.com/" xmlns:b="http://b.com/"><b:c b:d="1" e="2"/><f xmlns=""/></a>"#;
+    let json = to_json(XmlToDict::new().process_namespaces(true), xml);
+    assert_eq!(
+      json,
+      r#"{"http://a.com/:a":{"@xmlns":{"":"http://a.com/","b":"http://b.com/"},"http://b.com/:c":{"@http://b.com/:d":"1","@e":"2"},"f":{"@xmlns":{"":null}}}}"#
+    );
+
+    let convention = XmlToDict::new()
+      .process_namespaces(true)
+      .namespace("http://a.com/", "")
+      .namespace("http://b.com/", "ns_b");
+    let (json, output) = roundtrip(convention.clone(), r#"<a xmlns="http://a.com/" xmlns:b="http://b.com/"><b:c/></a>"#);
+    assert_eq!(
+      json.to_string(),
+      r#"{"a":{"@xmlns":{"":"http://a.com/","b":"http://b.com/"},"ns_b:c":null}}"#
+    );
+
+    assert_eq!(
+      output,
+      r#"<?xml version="1.0"?><a xmlns="http://a.com/" xmlns:b="http://b.com/"><b:c/></a>"#
+    );
+    let xml = to_xml(convention, r#"{"a":{"ns_b:c":{"@ns_b:d":"1"}}}"#);
+    assert_eq!(xml, r#"<?xml version="1.0"?><a xmlns:ns0="http://b.com/"><ns0:c ns0:d="1"/></a>"#);
+  }
+
+  #[test]
+  fn unparse_values() {
+    let xml = to_xml(
+      XmlToDict::new(),
+      r##"{"a":{"@n":1,"@z":null,"b":[true,null,2.5],"c":[],"#text":"d"}}"##
+    );
+    assert_eq!(xml, r#"<?xml version="1.0"?><a n="1" z=""><b>true</b><b/><b>2.5</b>d</a>"#);
+  }
+
+  #[test]
+  fn invalid_json() {
+    let mut builder = XmlConfig::new().convention(XmlToDict::new()).finalize();
+    let error = builder.build_from_json_string(r#"{"a":{},"b":{}}"#).unwrap_err();
+    assert_eq!(error.details(), "Expected an object with a single document element.");
+
+    let error = builder.build_from_json_string(r#"{"a":["x","y"]}"#).unwrap_err();
+    assert_eq!(error.details(), "Expected an object with a single document element.");
+
+    let error = builder.build_from_json_string(r#"{"a":{"b":[[1]]}}"#).unwrap_err();
+    assert_eq!(error.details(), "Expected element content at /a/b/0.");
+
+    let error = builder.build_from_json_string(r##"{"a":{"#comment":"x--y"}}"##).unwrap_err();
+    assert_eq!(error.details(), "Invalid comment at /a/#comment.");
+  }
+}
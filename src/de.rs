@@ -0,0 +1,758 @@
+use std::vec;
+
+use quick_xml::{events::*, Reader};
+use serde::de::{
+  self,
+  value::BorrowedStrDeserializer,
+  DeserializeOwned,
+  DeserializeSeed,
+  EnumAccess,
+  MapAccess,
+  SeqAccess,
+  Unexpected,
+  VariantAccess,
+  Visitor
+};
+
+use crate::{
+  error::{Error, ErrorKind},
+  json::{JsonBuilder, JsonConfig},
+  tree
+};
+
+// An element read from the event stream
+struct Node {
+  // JSON key of the element name
+  key:      String,
+  // Attributes by JSON key, prefixed when they are merged
+  attrs:    Vec<(String, String)>,
+  text:     String,
+  // Whether the text has a CDATA section, which is kept even when it is whitespace
+  literal:  bool,
+  // Trimmed or normalized text, unless the element only holds whitespace
+  content:  Option<String>,
+  children: Vec<usize>
+}
+
+// Elements of a document in document order, read with the options of `builder`
+struct Document<'b> {
+  builder: &'b JsonBuilder,
+  nodes:   Vec<Node>,
+  root:    usize
+}
+
+impl<'b> Document<'b> {
+  fn read(builder: &'b JsonBuilder, xml: &str) -> Result<Document<'b>, Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut buffer = Vec::new();
+    let mut nodes: Vec<Node> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    let mut root = None;
+
+    loop {
+      match reader.read_event(&mut buffer) {
+        Ok(Event::Start(ref e)) => {
+          let index = Document::start(builder, e, &mut nodes, &stack, &reader)?;
+          stack.push(index);
+        },
+        Ok(Event::Empty(ref e)) => {
+          let index = Document::start(builder, e, &mut nodes, &stack, &reader)?;
+          Document::end(builder, &mut nodes[index]);
+          if stack.is_empty() {
+            root = Some(index);
+          }
+        },
+        Ok(Event::End(_)) => {
+          if let Some(index) = stack.pop() {
+            Document::end(builder, &mut nodes[index]);
+            if stack.is_empty() {
+              root = Some(index);
+            }
+          }
+        },
+        Ok(Event::Text(ref e)) => {
+          if let Some(&index) = stack.last() {
+            let text = if builder.xml_normalization() {
+              tree::text_value(e)?
+            } else {
+              e.unescape_and_decode(&reader)?
+            };
+            nodes[index].text.push_str(&text);
+          }
+        },
+        Ok(Event::CData(ref e)) => {
+          if let Some(&index) = stack.last() {
+            let text = if builder.xml_normalization() {
+              std::str::from_utf8(&tree::normalize_newlines(e))?.to_owned()
+            } else {
+              std::str::from_utf8(e)?.to_owned()
+            };
+            nodes[index].text.push_str(&text);
+            nodes[index].literal = true;
+          }
+        },
+        Ok(Event::Eof) => break,
+        Ok(_) => (),
+        Err(e) => {
+          return Err(Error::new(
+            ErrorKind::Syntax,
+            format!("Error at position {}: {:?}", reader.buffer_position(), e)
+          ))
+        },
+      }
+      buffer.clear();
+    }
+
+    let root = root.ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected a document element."))?;
+    Ok(Document { builder, nodes, root })
+  }
+
+  // Add the element of a start tag as a child of the innermost open element
+  fn start(
+    builder: &JsonBuilder,
+    event: &BytesStart,
+    nodes: &mut Vec<Node>,
+    stack: &[usize],
+    reader: &Reader<&[u8]>
+  ) -> Result<usize, Error> {
+    let mut attrs = Vec::new();
+    if !builder.ignore_attrs() {
+      for attr in event.attributes().flatten() {
+        let value = if builder.xml_normalization() {
+          tree::attribute_value(&attr.value)?
+        } else {
+          attr.unescape_and_decode_value(reader)?
+        };
+        let key = builder.options().decoded_name(std::str::from_utf8(attr.key)?.to_owned());
+        let key = if builder.merge_attrs() {
+          builder.options().json_key(format!("{}{}", builder.attr_prefix(), key))?
+        } else {
+          key
+        };
+        attrs.push((key, value));
+      }
+    }
+
+    let index = nodes.len();
+    nodes.push(Node {
+      key: builder.options().tag_key(std::str::from_utf8(event.name())?)?,
+      attrs,
+      text: String::new(),
+      literal: false,
+      content: None,
+      children: Vec::new()
+    });
+    if let Some(&parent) = stack.last() {
+      nodes[parent].children.push(index);
+    }
+    Ok(index)
+  }
+
+  // Process the text of a closed element
+  fn end(builder: &JsonBuilder, node: &mut Node) {
+    if node.literal || !builder.options().is_whitespace(&node.text) {
+      node.content = Some(builder.options().processed_text(&node.text));
+    }
+  }
+
+  // Check if the element is a string in the xml2js mapping, rather than an object
+  fn is_simple(&self, index: usize) -> bool {
+    let node = &self.nodes[index];
+    node.attrs.is_empty() && node.children.is_empty() && !(node.content.is_some() && self.builder.explicit_charkey())
+  }
+
+  // Text of an element: the string of a simple element, and otherwise its text content
+  fn text(&self, index: usize) -> &str {
+    let node = &self.nodes[index];
+    match node.content {
+      Some(ref content) => content,
+      None if !self.is_simple(index) => "",
+      None if !self.builder.empty_tag().is_empty() => self.builder.empty_tag(),
+      None => &node.text
+    }
+  }
+
+  // Check if an element has neither attributes, child elements nor text
+  fn is_empty(&self, index: usize) -> bool {
+    let node = &self.nodes[index];
+    node.attrs.is_empty() && node.children.is_empty() && node.content.is_none() && self.builder.empty_tag().is_empty()
+  }
+
+  // Members of the object of an element: attributes, child elements grouped by key and text
+  fn entries(&self, index: usize) -> Vec<(&str, Value<'_>)> {
+    let node = &self.nodes[index];
+    let mut entries = Vec::new();
+    if self.builder.merge_attrs() {
+      for (key, value) in &node.attrs {
+        push_group(&mut entries, key, Value::Scalar(value));
+      }
+    } else if !node.attrs.is_empty() {
+      let attrs = node.attrs.iter().map(|(k, v)| (k.as_str(), Value::Scalar(v))).collect();
+      entries.push((self.builder.attrkey(), Value::Map(attrs)));
+    }
+    for &child in &node.children {
+      push_group(&mut entries, &self.nodes[child].key, Value::Element(child));
+    }
+    if let Some(ref content) = node.content {
+      entries.push((self.builder.charkey(), Value::Scalar(content)));
+    }
+    entries
+  }
+}
+
+// Add `value` to the group of `key`
+fn push_group<'d>(entries: &mut Vec<(&'d str, Value<'d>)>, key: &'d str, value: Value<'d>) {
+  match entries.iter_mut().find(|(k, v)| *k == key && matches!(v, Value::Group(..))) {
+    Some((_, Value::Group(_, values))) => values.push(value),
+    _ => entries.push((key, Value::Group(key, vec![value])))
+  }
+}
+
+// Value of the xml2js mapping
+#[derive(Clone)]
+enum Value<'d> {
+  // An element, by index
+  Element(usize),
+  // Elements or merged attributes with the same key, one or many
+  Group(&'d str, Vec<Value<'d>>),
+  Map(Vec<(&'d str, Value<'d>)>),
+  Scalar(&'d str)
+}
+
+/// Deserialize an instance of `T` from XML, with the node-xml2js mapping configured by `config`.
+///
+/// The deserializer doesn't stream: the whole document is first read from the quick-xml events
+/// into an index tree of its elements, which takes memory in proportion to the document, though
+/// no JSON value is built. `T` is then built from that tree, with the values `build_from_xml`
+/// would give.
+///
+/// Fields accept a single element as well as an array of them, whatever `explicit_array`, and
+/// numbers and booleans are read from text, like the `"42"` of `<count>42</count>`. Elements with
+/// attributes or child elements give their text for scalars. Empty elements are `None` for options.
+///
+/// Conventions and `explicit_children` aren't supported.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use xml2json_rs::{from_str, JsonConfig};
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Book {
+///   #[serde(rename = "$")]
+///   attrs:   Attrs,
+///   title:   String,
+///   authors: Vec<String>,
+///   year:    u32
+/// }
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Attrs {
+///   category: String
+/// }
+///
+/// let xml = r#"<book category="fantasy"><title>The Name of the Wind</title><authors>Patrick Rothfuss</authors><year>2007</year></book>"#;
+/// let book: Book = from_str(xml, JsonConfig::new().explicit_root(false)).unwrap();
+/// assert_eq!(
+///   book,
+///   Book {
+///     attrs:   Attrs { category: "fantasy".to_owned() },
+///     title:   "The Name of the Wind".to_owned(),
+///     authors: vec!["Patrick Rothfuss".to_owned()],
+///     year:    2007
+///   }
+/// );
+/// ```
+pub fn from_str<T: DeserializeOwned>(xml: &str, config: &JsonConfig) -> Result<T, Error> {
+  let builder = config.try_finalize()?;
+  if !builder.is_xml2js() || builder.explicit_children() {
+    return Err(Error::new(
+      ErrorKind::Config,
+      "Deserializing supports the xml2js mapping without explicit_children."
+    ));
+  }
+  let document = Document::read(&builder, xml)?;
+  let value = if builder.explicit_root() {
+    Value::Map(vec![(document.nodes[document.root].key.as_str(), Value::Element(document.root))])
+  } else {
+    Value::Element(document.root)
+  };
+  T::deserialize(Deserializer {
+    document: &document,
+    value
+  })
+}
+
+struct Deserializer<'d> {
+  document: &'d Document<'d>,
+  value:    Value<'d>
+}
+
+impl<'d> Deserializer<'d> {
+  fn new(document: &'d Document<'d>, value: Value<'d>) -> Deserializer<'d> {
+    Deserializer { document, value }
+  }
+
+  // The value of a group holding a single one, and an error for a group of many
+  fn single(self) -> Result<Deserializer<'d>, Error> {
+    match self.value {
+      Value::Group(key, mut values) => {
+        if values.len() == 1 {
+          Ok(Deserializer::new(self.document, values.remove(0)))
+        } else {
+          Err(Error::new(
+            ErrorKind::Syntax,
+            format!("Expected a single \"{}\" value, found {}.", key, values.len())
+          ))
+        }
+      },
+      value => Ok(Deserializer::new(self.document, value))
+    }
+  }
+
+  // Text of a scalar value
+  fn scalar(self) -> Result<&'d str, Error> {
+    let document = self.document;
+    match self.single()?.value {
+      Value::Scalar(text) => Ok(text),
+      Value::Element(index) => Ok(document.text(index)),
+      _ => Err(Error::new(ErrorKind::Syntax, "Expected a scalar value, found a map."))
+    }
+  }
+}
+
+macro_rules! deserialize_number {
+  ($($method:ident => $visit:ident,)*) => {
+    $(
+      fn $method<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+        let text = self.scalar()?;
+        match text.trim().parse() {
+          Ok(n) => visitor.$visit(n),
+          Err(_) => Err(de::Error::invalid_value(Unexpected::Str(text), &visitor))
+        }
+      }
+    )*
+  };
+}
+
+impl<'d> de::Deserializer<'d> for Deserializer<'d> {
+  type Error = Error;
+
+  deserialize_number! {
+    deserialize_i8 => visit_i8,
+    deserialize_i16 => visit_i16,
+    deserialize_i32 => visit_i32,
+    deserialize_i64 => visit_i64,
+    deserialize_i128 => visit_i128,
+    deserialize_u8 => visit_u8,
+    deserialize_u16 => visit_u16,
+    deserialize_u32 => visit_u32,
+    deserialize_u64 => visit_u64,
+    deserialize_u128 => visit_u128,
+    deserialize_f32 => visit_f32,
+    deserialize_f64 => visit_f64,
+    deserialize_char => visit_char,
+  }
+
+  fn deserialize_any<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    let document = self.document;
+    match self.value {
+      Value::Element(index) if document.is_simple(index) => visitor.visit_borrowed_str(document.text(index)),
+      Value::Element(index) => visitor.visit_map(Entries::new(document, document.entries(index))),
+      Value::Group(_, mut values) if values.len() == 1 && !document.builder.explicit_array() => {
+        Deserializer::new(document, values.remove(0)).deserialize_any(visitor)
+      },
+      Value::Group(_, values) => visitor.visit_seq(Values::new(document, values)),
+      Value::Map(entries) => visitor.visit_map(Entries::new(document, entries)),
+      Value::Scalar(text) => visitor.visit_borrowed_str(text)
+    }
+  }
+
+  fn deserialize_bool<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    let text = self.scalar()?;
+    match text.trim() {
+      "true" | "1" => visitor.visit_bool(true),
+      "false" | "0" => visitor.visit_bool(false),
+      _ => Err(de::Error::invalid_value(Unexpected::Str(text), &visitor))
+    }
+  }
+
+  fn deserialize_str<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_borrowed_str(self.scalar()?)
+  }
+
+  fn deserialize_string<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_str(visitor)
+  }
+
+  fn deserialize_bytes<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_borrowed_bytes(self.scalar()?.as_bytes())
+  }
+
+  fn deserialize_byte_buf<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_bytes(visitor)
+  }
+
+  fn deserialize_option<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    match self.value {
+      Value::Element(index) if self.document.is_empty(index) => visitor.visit_none(),
+      Value::Group(_, ref values) if values.len() == 1 => self.single()?.deserialize_option(visitor),
+      _ => visitor.visit_some(self)
+    }
+  }
+
+  fn deserialize_unit<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_unit()
+  }
+
+  fn deserialize_unit_struct<V: Visitor<'d>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_unit()
+  }
+
+  fn deserialize_newtype_struct<V: Visitor<'d>>(self, _name: &'static str, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_newtype_struct(self)
+  }
+
+  fn deserialize_seq<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    match self.value {
+      Value::Group(_, values) => visitor.visit_seq(Values::new(self.document, values)),
+      value => visitor.visit_seq(Values::new(self.document, vec![value]))
+    }
+  }
+
+  fn deserialize_tuple<V: Visitor<'d>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_seq(visitor)
+  }
+
+  fn deserialize_tuple_struct<V: Visitor<'d>>(self, _name: &'static str, _len: usize, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_seq(visitor)
+  }
+
+  fn deserialize_map<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    let document = self.document;
+    match self.single()?.value {
+      Value::Element(index) => visitor.visit_map(Entries::new(document, document.entries(index))),
+      Value::Map(entries) => visitor.visit_map(Entries::new(document, entries)),
+      Value::Scalar(text) => Err(de::Error::invalid_type(Unexpected::Str(text), &visitor)),
+      Value::Group(..) => Err(de::Error::invalid_type(Unexpected::Seq, &visitor))
+    }
+  }
+
+  fn deserialize_struct<V: Visitor<'d>>(
+    self,
+    _name: &'static str,
+    _fields: &'static [&'static str],
+    visitor: V
+  ) -> Result<V::Value, Error> {
+    self.deserialize_map(visitor)
+  }
+
+  fn deserialize_enum<V: Visitor<'d>>(
+    self,
+    _name: &'static str,
+    _variants: &'static [&'static str],
+    visitor: V
+  ) -> Result<V::Value, Error> {
+    let document = self.document;
+    match self.single()?.value {
+      Value::Element(index) if !document.is_simple(index) => {
+        // An element holding the variant as its first child element
+        let variant = document.entries(index).into_iter().find(|(_, v)| matches!(v, Value::Group(..)));
+        let (key, value) = variant.ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected an element naming the variant."))?;
+        visitor.visit_enum(Variant {
+          document,
+          key,
+          value: Some(value)
+        })
+      },
+      Value::Element(index) => visitor.visit_enum(Variant {
+        document,
+        key: document.text(index),
+        value: None
+      }),
+      Value::Scalar(text) => visitor.visit_enum(Variant {
+        document,
+        key: text,
+        value: None
+      }),
+      _ => Err(Error::new(ErrorKind::Syntax, "Expected a variant name, found a map."))
+    }
+  }
+
+  fn deserialize_identifier<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    self.deserialize_str(visitor)
+  }
+
+  fn deserialize_ignored_any<V: Visitor<'d>>(self, visitor: V) -> Result<V::Value, Error> {
+    visitor.visit_unit()
+  }
+}
+
+// Members of an object
+struct Entries<'d> {
+  document: &'d Document<'d>,
+  entries:  vec::IntoIter<(&'d str, Value<'d>)>,
+  value:    Option<Value<'d>>
+}
+
+impl<'d> Entries<'d> {
+  fn new(document: &'d Document<'d>, entries: Vec<(&'d str, Value<'d>)>) -> Entries<'d> {
+    Entries {
+      document,
+      entries: entries.into_iter(),
+      value: None
+    }
+  }
+}
+
+impl<'d> MapAccess<'d> for Entries<'d> {
+  type Error = Error;
+
+  fn next_key_seed<K: DeserializeSeed<'d>>(&mut self, seed: K) -> Result<Option<K::Value>, Error> {
+    match self.entries.next() {
+      Some((key, value)) => {
+        self.value = Some(value);
+        seed.deserialize(BorrowedStrDeserializer::new(key)).map(Some)
+      },
+      None => Ok(None)
+    }
+  }
+
+  fn next_value_seed<V: DeserializeSeed<'d>>(&mut self, seed: V) -> Result<V::Value, Error> {
+    let value = self
+      .value
+      .take()
+      .ok_or_else(|| Error::new(ErrorKind::Unknown, "Expected a key before its value."))?;
+    seed.deserialize(Deserializer::new(self.document, value))
+  }
+}
+
+// Items of an array
+struct Values<'d> {
+  document: &'d Document<'d>,
+  values:   vec::IntoIter<Value<'d>>
+}
+
+impl<'d> Values<'d> {
+  fn new(document: &'d Document<'d>, values: Vec<Value<'d>>) -> Values<'d> {
+    Values {
+      document,
+      values: values.into_iter()
+    }
+  }
+}
+
+impl<'d> SeqAccess<'d> for Values<'d> {
+  type Error = Error;
+
+  fn next_element_seed<T: DeserializeSeed<'d>>(&mut self, seed: T) -> Result<Option<T::Value>, Error> {
+    match self.values.next() {
+      Some(value) => seed.deserialize(Deserializer::new(self.document, value)).map(Some),
+      None => Ok(None)
+    }
+  }
+
+  fn size_hint(&self) -> Option<usize> {
+    Some(self.values.len())
+  }
+}
+
+// Enum variant named `key`, with the value of the child element naming it
+struct Variant<'d> {
+  document: &'d Document<'d>,
+  key:      &'d str,
+  value:    Option<Value<'d>>
+}
+
+impl<'d> Variant<'d> {
+  fn value(self) -> Result<Deserializer<'d>, Error> {
+    match self.value {
+      Some(value) => Ok(Deserializer::new(self.document, value)),
+      None => Err(Error::new(
+        ErrorKind::Syntax,
+        format!("Expected a value for variant \"{}\".", self.key)
+      ))
+    }
+  }
+}
+
+impl<'d> EnumAccess<'d> for Variant<'d> {
+  type Error = Error;
+  type Variant = Variant<'d>;
+
+  fn variant_seed<V: DeserializeSeed<'d>>(self, seed: V) -> Result<(V::Value, Variant<'d>), Error> {
+    let key = BorrowedStrDeserializer::<Error>::new(self.key);
+    Ok((seed.deserialize(key)?, self))
+  }
+}
+
+impl<'d> VariantAccess<'d> for Variant<'d> {
+  type Error = Error;
+
+  fn unit_variant(self) -> Result<(), Error> {
+    Ok(())
+  }
+
+  fn newtype_variant_seed<T: DeserializeSeed<'d>>(self, seed: T) -> Result<T::Value, Error> {
+    seed.deserialize(self.value()?)
+  }
+
+  fn tuple_variant<V: Visitor<'d>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+    de::Deserializer::deserialize_seq(self.value()?, visitor)
+  }
+
+  fn struct_variant<V: Visitor<'d>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, Error> {
+    de::Deserializer::deserialize_map(self.value()?, visitor)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::json::CollisionPolicy;
+  use serde::Deserialize;
+  use serde_json::Value as JsonValue;
+  use std::collections::HashMap;
+
+  const COMPLEX: &str = include_str!("../tests/data/complex.xml");
+  const LISTS: &str = include_str!("../tests/data/lists.xml");
+  const CDS: &str = include_str!("../tests/data/cds.xml");
+
+  #[derive(Deserialize, Debug, PartialEq)]
+  struct Rdf {
+    #[serde(rename = "rdf:Description")]
+    descriptions: Vec<Description>
+  }
+
+  #[derive(Deserialize, Debug, PartialEq)]
+  struct Description {
+    #[serde(rename = "$")]
+    about:  About,
+    #[serde(rename = "cd:artist")]
+    artist: Vec<String>,
+    #[serde(rename = "cd:price")]
+    price:  f64,
+    #[serde(rename = "cd:year")]
+    year:   u16
+  }
+
+  #[derive(Deserialize, Debug, PartialEq)]
+  struct About {
+    #[serde(rename = "rdf:about")]
+    about: String
+  }
+
+  // Deserializing into JSON gives what `build_from_xml` builds
+  #[test]
+  fn xml2js_parity() {
+    let mut configs = vec![JsonConfig::new(); 8];
+    configs[1].explicit_array(false);
+    configs[2].merge_attrs(true).attr_prefix("@");
+    configs[3].trim(true).normalize_text(true).explicit_root(false);
+    configs[4].explicit_charkey(true).charkey("#text").attrkey("@");
+    configs[5].empty_tag("EMPTY").ignore_attrs(true);
+    configs[6].lowercase_tags(true).explicit_array(false).merge_attrs(true);
+    configs[7]
+      .xml_normalization(true)
+      .collisions(CollisionPolicy::Escape("x_".to_owned()));
+
+    for config in &configs {
+      for xml in &[COMPLEX, LISTS, CDS, "<a _=\"1\"><_>2</_><b/>  <b>  </b><b><![CDATA[ ]]></b></a>"] {
+        let expected = config.finalize().build_from_xml(xml).unwrap();
+        let json: JsonValue = from_str(xml, config).unwrap();
+        assert_eq!(json.to_string(), expected.to_string(), "{:?}", config);
+      }
+    }
+  }
+
+  #[test]
+  fn typed_fields() {
+    let rdf: HashMap<String, Rdf> = from_str(CDS, &JsonConfig::new()).unwrap();
+    assert_eq!(rdf["rdf:RDF"].descriptions, vec![Description {
+      about:  About {
+        about: "http://www.recshop.fake/cd/The Money Store".to_owned()
+      },
+      artist: vec!["Death Grips".to_owned()],
+      price:  0.0,
+      year:   2012
+    }]);
+
+    let rdf: Rdf = from_str(CDS, JsonConfig::new().explicit_root(false).explicit_array(false)).unwrap();
+    assert_eq!(rdf.descriptions[0].year, 2012);
+  }
+
+  #[test]
+  fn scalars_and_options() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Item {
+      #[serde(rename = "$")]
+      attrs:   Attrs,
+      #[serde(rename = "_")]
+      text:    Option<String>,
+      count:   Option<u32>,
+      note:    Option<String>,
+      missing: Option<String>,
+      price:   u32,
+      tags:    (String, String)
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Attrs {
+      id:      u64,
+      enabled: bool
+    }
+
+    let xml = r#"<item id="7" enabled="1">text<count>
+      </count><note></note><price currency="EUR"> 12 </price><tags>a</tags><tags>b</tags></item>"#;
+    let item: Item = from_str(xml, JsonConfig::new().explicit_root(false)).unwrap();
+    assert_eq!(item, Item {
+      attrs:   Attrs { id: 7, enabled: true },
+      text:    Some("text".to_owned()),
+      count:   None,
+      note:    None,
+      missing: None,
+      price:   12,
+      tags:    ("a".to_owned(), "b".to_owned())
+    });
+  }
+
+  #[test]
+  fn enums() {
+    #[derive(Deserialize, Debug, PartialEq)]
+    enum Shape {
+      Empty,
+      Circle { radius: f32 },
+      Square(u8)
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Drawing {
+      shape: Vec<Shape>
+    }
+
+    let xml =
+      "<drawing><shape>Empty</shape><shape><Circle><radius>1.5</radius></Circle></shape><shape><Square>2</Square></shape></drawing>";
+    let drawing: Drawing = from_str(xml, JsonConfig::new().explicit_root(false)).unwrap();
+    assert_eq!(drawing.shape, vec![Shape::Empty, Shape::Circle { radius: 1.5 }, Shape::Square(2)]);
+  }
+
+  #[test]
+  fn errors() {
+    #[derive(Deserialize, Debug)]
+    struct A {
+      #[allow(dead_code)]
+      b: u8
+    }
+
+    let error = from_str::<A>("<a><b>1</b><b>2</b></a>", JsonConfig::new().explicit_root(false)).unwrap_err();
+    assert_eq!(error.details(), "Expected a single \"b\" value, found 2.");
+
+    let error = from_str::<A>("<a><b>x</b></a>", JsonConfig::new().explicit_root(false)).unwrap_err();
+    assert_eq!(error.details(), "invalid value: string \"x\", expected u8");
+
+    let error = from_str::<A>("<a>", JsonConfig::new().explicit_root(false)).unwrap_err();
+    assert_eq!(error.details(), "Expected a document element.");
+
+    let error = from_str::<A>("<a/>", JsonConfig::new().explicit_children(true)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+  }
+}
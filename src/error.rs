@@ -1,13 +1,18 @@
-use std::{error::Error as StdError, fmt};
+use std::{error::Error as StdError, fmt, io};
 
 use quick_xml::Error as XmlError;
 use serde_json::error::Error as JsonError;
 use std::{str::Utf8Error, string::FromUtf8Error};
 
+use crate::convention::SoapFault;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum ErrorKind {
   Syntax,
   Encoding,
+  Config,
+  Fault,
+  Io,
   Unknown
 }
 
@@ -16,6 +21,9 @@ impl ErrorKind {
     match *self {
       ErrorKind::Syntax => "parse",
       ErrorKind::Encoding => "encoding",
+      ErrorKind::Config => "config",
+      ErrorKind::Fault => "fault",
+      ErrorKind::Io => "io",
       ErrorKind::Unknown => "unknown"
     }
   }
@@ -25,7 +33,9 @@ impl ErrorKind {
 pub struct Error {
   details: String,
   desc:    &'static str,
-  kind:    ErrorKind
+  kind:    ErrorKind,
+  fault:   Option<Box<SoapFault>>,
+  io:      Option<io::Error>
 }
 
 impl Error {
@@ -35,7 +45,9 @@ impl Error {
     Error {
       kind,
       desc,
-      details: detail.into()
+      details: detail.into(),
+      fault: None,
+      io: None
     }
   }
 
@@ -48,6 +60,16 @@ impl Error {
   pub fn details(&self) -> String {
     self.details.clone()
   }
+
+  /// SOAP fault of an error of kind `fault`
+  pub fn fault(&self) -> Option<&SoapFault> {
+    self.fault.as_deref()
+  }
+
+  /// I/O error of an error of kind `io`
+  pub fn io_error(&self) -> Option<&io::Error> {
+    self.io.as_ref()
+  }
 }
 
 impl fmt::Display for Error {
@@ -66,6 +88,22 @@ impl StdError for Error {
   fn description(&self) -> &str {
     self.desc
   }
+
+  fn source(&self) -> Option<&(dyn StdError + 'static)> {
+    self.io.as_ref().map(|e| e as &(dyn StdError + 'static))
+  }
+}
+
+impl serde::de::Error for Error {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    Error::new(ErrorKind::Syntax, msg.to_string())
+  }
+}
+
+impl serde::ser::Error for Error {
+  fn custom<T: fmt::Display>(msg: T) -> Self {
+    Error::new(ErrorKind::Syntax, msg.to_string())
+  }
 }
 
 impl From<JsonError> for Error {
@@ -80,6 +118,22 @@ impl From<XmlError> for Error {
   }
 }
 
+impl From<SoapFault> for Error {
+  fn from(fault: SoapFault) -> Self {
+    let mut error = Error::new(ErrorKind::Fault, fault.to_string());
+    error.fault = Some(Box::new(fault));
+    error
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(e: io::Error) -> Self {
+    let mut error = Error::new(ErrorKind::Io, e.to_string());
+    error.io = Some(e);
+    error
+  }
+}
+
 impl From<FromUtf8Error> for Error {
   fn from(e: FromUtf8Error) -> Self {
     Error::new(ErrorKind::Encoding, format!("{}", e))
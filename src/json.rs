@@ -1,32 +1,60 @@
 use crate::{
-  error::{Error, ErrorKind},
+  convention::{Convention, JsonOptions, Xml2Js, XmlOptions},
+  error::Error,
+  tree,
   utils
 };
 
-use serde_json::{json, Value as JsonValue};
-
-use quick_xml::{events::*, Reader};
-use regex::{NoExpand, Regex};
-
-lazy_static! {
-  static ref WHITESPACE_RE: Regex = Regex::new(r"^\s*$").unwrap();
-  static ref TWO_OR_MORE_WHITESPACE_RE: Regex = Regex::new(r"\s{2,}").unwrap();
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How names that collide with `attrkey` or `charkey` are handled, such as an element named `_`.
+/// Used by both [JsonConfig] and [XmlConfig].
+///
+/// [XmlConfig]: crate::XmlConfig
+pub enum CollisionPolicy {
+  /// Use names as they are. Colliding elements are merged into the attributes or text
+  Merge,
+  /// Prefix colliding names, and names which start with the prefix, with the given string. The
+  /// `XmlBuilder` removes the prefix again
+  Escape(String),
+  /// Fail with an error
+  Reject
 }
 
 /// Configuration options for JsonBuilder
-#[derive(Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct JsonConfig {
-  charkey:          Option<String>,
-  attrkey:          Option<String>,
-  empty_tag:        Option<String>,
-  explicit_root:    Option<bool>,
-  trim:             Option<bool>,
-  ignore_attrs:     Option<bool>,
-  merge_attrs:      Option<bool>,
-  normalize_text:   Option<bool>,
-  lowercase_tags:   Option<bool>,
-  explicit_array:   Option<bool>,
-  explicit_charkey: Option<bool>
+  charkey:                 Option<String>,
+  attrkey:                 Option<String>,
+  empty_tag:               Option<String>,
+  explicit_root:           Option<bool>,
+  trim:                    Option<bool>,
+  ignore_attrs:            Option<bool>,
+  merge_attrs:             Option<bool>,
+  attr_prefix:             Option<String>,
+  normalize_text:          Option<bool>,
+  lowercase_tags:          Option<bool>,
+  explicit_array:          Option<bool>,
+  explicit_charkey:        Option<bool>,
+  decode_names:            Option<bool>,
+  collisions:              Option<CollisionPolicy>,
+  childkey:                Option<String>,
+  explicit_children:       Option<bool>,
+  preserve_children_order: Option<bool>,
+  chars_as_children:       Option<bool>,
+  include_white_chars:     Option<bool>,
+  include_comments:        Option<bool>,
+  include_instructions:    Option<bool>,
+  include_prolog:          Option<bool>,
+  xml_normalization:       Option<bool>,
+  #[serde(skip)]
+  convention:              Option<Arc<dyn Convention>>
 }
 
 /// JsonBuilder configuration options
@@ -37,20 +65,91 @@ impl JsonConfig {
   /// `self`s methods. Any options not set will use their defaults upon call to `finalize`.
   pub fn new() -> JsonConfig {
     JsonConfig {
-      charkey:          None,
-      attrkey:          None,
-      empty_tag:        None,
-      explicit_root:    None,
-      trim:             None,
-      ignore_attrs:     None,
-      merge_attrs:      None,
-      normalize_text:   None,
-      lowercase_tags:   None,
-      explicit_array:   None,
-      explicit_charkey: None
+      charkey:                 None,
+      attrkey:                 None,
+      empty_tag:               None,
+      explicit_root:           None,
+      trim:                    None,
+      ignore_attrs:            None,
+      merge_attrs:             None,
+      attr_prefix:             None,
+      normalize_text:          None,
+      lowercase_tags:          None,
+      explicit_array:          None,
+      explicit_charkey:        None,
+      decode_names:            None,
+      collisions:              None,
+      childkey:                None,
+      explicit_children:       None,
+      preserve_children_order: None,
+      chars_as_children:       None,
+      include_white_chars:     None,
+      include_comments:        None,
+      include_instructions:    None,
+      include_prolog:          None,
+      xml_normalization:       None,
+      convention:              None
     }
   }
 
+  /// Load a configuration from JSON. Options are named after their setters and unknown options are
+  /// errors.
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let config = JsonConfig::from_json_str(r#"{"attrkey": "@", "explicit_array": false}"#).unwrap();
+  /// let json = config
+  ///   .finalize()
+  ///   .build_string_from_xml(r#"<a x="1"><b>c</b></a>"#);
+  /// assert_eq!(json.unwrap(), r#"{"a":{"@":{"x":"1"},"b":"c"}}"#);
+  ///
+  /// let error = JsonConfig::from_json_str(r#"{"atrkey": "@"}"#).unwrap_err();
+  /// assert!(error.details().starts_with("unknown field `atrkey`"));
+  /// ```
+  pub fn from_json_str(json: &str) -> Result<JsonConfig, Error> {
+    utils::config_from_json_str(json)
+  }
+
+  /// Load a configuration from a file, see [JsonConfig::from_json_str]. Files with a `.toml`
+  /// extension are read as TOML, which requires the `toml` feature, and other files as JSON
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Result<JsonConfig, Error> {
+    utils::config_from_path(path.as_ref())
+  }
+
+  /// Initialize a JsonConfig which keeps everything [XmlBuilder] needs to write the document back:
+  /// child order, whitespace, comments, processing instructions, CDATA sections, the declaration
+  /// and the doctype. The document is stored as an ordered array of its top level nodes under
+  /// `childkey`, and element content as ordered arrays of children. Use it with
+  /// [XmlConfig::lossless].
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let builder = JsonConfig::lossless().finalize();
+  /// let json = builder.build_string_from_xml(r#"<a x="1"><b/> text <!--c--></a><?d?>"#);
+  /// assert_eq!(
+  ///   json.unwrap(),
+  ///   r##"{"$$":[{"#name":"a","$":{"x":"1"},"$$":[{"#name":"b"},{"#name":"__text__","_":" text "},{"#name":"__comment__","_":"c"}]},{"#name":"__instruction__","_":"d"}]}"##
+  /// );
+  /// ```
+  ///
+  /// [XmlBuilder]: crate::XmlBuilder
+  /// [XmlConfig::lossless]: crate::XmlConfig::lossless
+  pub fn lossless() -> JsonConfig {
+    let mut config = JsonConfig::new();
+    config
+      .explicit_children(true)
+      .preserve_children_order(true)
+      .chars_as_children(true)
+      .include_white_chars(true)
+      .include_comments(true)
+      .include_instructions(true)
+      .include_prolog(true)
+      .xml_normalization(true);
+    config
+  }
+
   /// Key to store character content under.
   ///
   /// (`"_"` by default)
@@ -114,6 +213,32 @@ impl JsonConfig {
     self
   }
 
+  /// Prefix for merged attribute names.
+  ///
+  /// With `merge_attrs`, attributes are stored under their name with this prefix, such as `"@id"`,
+  /// which tells them apart from child elements. Use the same prefix with [XmlConfig::attr_prefix]
+  /// to write them back as attributes.
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let builder = JsonConfig::new()
+  ///   .merge_attrs(true)
+  ///   .attr_prefix("@")
+  ///   .explicit_array(false)
+  ///   .finalize();
+  /// let json = builder.build_string_from_xml(r#"<a id="1"><id>2</id></a>"#);
+  /// assert_eq!(json.unwrap(), r#"{"a":{"@id":"1","id":"2"}}"#);
+  /// ```
+  ///
+  /// (`""` by default)
+  ///
+  /// [XmlConfig::attr_prefix]: crate::XmlConfig::attr_prefix
+  pub fn attr_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut JsonConfig {
+    self.attr_prefix = Some(prefix.into());
+    self
+  }
+
   /// Removes whitespace character data in text nodes.
   ///
   /// This option will result in behavior that is a superset of [`trim`]. Whitespace at the
@@ -157,298 +282,384 @@ impl JsonConfig {
     self
   }
 
+  /// Decode `_xHHHH_` escape sequences in element and attribute names, as written by
+  /// [NamePolicy::Escape].
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let builder = JsonConfig::new().decode_names(true).finalize();
+  /// let json = builder.build_string_from_xml(r#"<a><first_x0020_name>b</first_x0020_name></a>"#);
+  /// assert_eq!(json.unwrap(), r#"{"a":{"first name":["b"]}}"#);
+  /// ```
+  ///
+  /// (`false` by default)
+  ///
+  /// [NamePolicy::Escape]: crate::NamePolicy::Escape
+  pub fn decode_names(&mut self, flag: bool) -> &mut JsonConfig {
+    self.decode_names = Some(flag);
+    self
+  }
+
+  /// Policy for element names, and attribute names when `merge_attrs` is set, which are the same as
+  /// `attrkey` or `charkey`.
+  ///
+  /// ```rust
+  /// use xml2json_rs::{CollisionPolicy, JsonConfig};
+  ///
+  /// let builder = JsonConfig::new()
+  ///   .collisions(CollisionPolicy::Escape("\\".to_owned()))
+  ///   .finalize();
+  /// let json = builder.build_string_from_xml(r#"<a><_>b</_>c</a>"#);
+  /// assert_eq!(json.unwrap(), r#"{"a":{"\\_":["b"],"_":"c"}}"#);
+  /// ```
+  ///
+  /// (`CollisionPolicy::Merge` by default)
+  pub fn collisions(&mut self, policy: CollisionPolicy) -> &mut JsonConfig {
+    self.collisions = Some(policy);
+    self
+  }
+
+  /// Key to store child elements under, when `explicit_children` is set.
+  ///
+  /// (`"$$"` by default)
+  pub fn childkey<T: Into<String>>(&mut self, key: T) -> &mut JsonConfig {
+    self.childkey = Some(key.into());
+    self
+  }
+
+  /// Store child elements under `childkey` rather than as properties of their parent.
+  ///
+  /// (`false` by default)
+  pub fn explicit_children(&mut self, flag: bool) -> &mut JsonConfig {
+    self.explicit_children = Some(flag);
+    self
+  }
+
+  /// Store the children under `childkey` as an array in document order rather than as an object.
+  /// Each child is an object with its element name under `#name`. Requires `explicit_children`.
+  ///
+  /// (`false` by default)
+  pub fn preserve_children_order(&mut self, flag: bool) -> &mut JsonConfig {
+    self.preserve_children_order = Some(flag);
+    self
+  }
+
+  /// Store text as children named `__text__`, or `__cdata__` for CDATA sections, with the text
+  /// under `charkey`, instead of under the parent's `charkey`. Requires `preserve_children_order`.
+  ///
+  /// (`false` by default)
+  pub fn chars_as_children(&mut self, flag: bool) -> &mut JsonConfig {
+    self.chars_as_children = Some(flag);
+    self
+  }
+
+  /// Keep text children which only contain whitespace. Requires `chars_as_children`.
+  ///
+  /// (`false` by default)
+  pub fn include_white_chars(&mut self, flag: bool) -> &mut JsonConfig {
+    self.include_white_chars = Some(flag);
+    self
+  }
+
+  /// Store comments as children named `__comment__`. Comments outside of the document element are
+  /// skipped, unless `include_prolog` is set. Requires `preserve_children_order`.
+  ///
+  /// (`false` by default)
+  pub fn include_comments(&mut self, flag: bool) -> &mut JsonConfig {
+    self.include_comments = Some(flag);
+    self
+  }
+
+  /// Store processing instructions as children named `__instruction__`, with their target and data
+  /// under `charkey`. Requires `preserve_children_order`.
+  ///
+  /// (`false` by default)
+  pub fn include_instructions(&mut self, flag: bool) -> &mut JsonConfig {
+    self.include_instructions = Some(flag);
+    self
+  }
+
+  /// Store the top level nodes of the document as an array under `childkey`, in document order:
+  /// the declaration as `__declaration__` with its pseudo-attributes under `attrkey`, the doctype
+  /// as `__doctype__`, the comments and processing instructions around the document element, and
+  /// the document element itself. Requires `preserve_children_order`. `explicit_root` doesn't
+  /// apply.
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let builder = JsonConfig::new()
+  ///   .explicit_children(true)
+  ///   .preserve_children_order(true)
+  ///   .include_prolog(true)
+  ///   .finalize();
+  /// let json = builder.build_string_from_xml(r#"<?xml version="1.0"?><!DOCTYPE a><a/><!--b-->"#);
+  /// assert_eq!(
+  ///   json.unwrap(),
+  ///   r##"{"$$":[{"#name":"__declaration__","$":{"version":"1.0"}},{"#name":"__doctype__","_":"a"},{"#name":"a"},{"#name":"__comment__","_":"b"}]}"##
+  /// );
+  /// ```
+  ///
+  /// (`false` by default)
+  pub fn include_prolog(&mut self, flag: bool) -> &mut JsonConfig {
+    self.include_prolog = Some(flag);
+    self
+  }
+
+  /// Normalize line breaks and attribute values the way a conforming XML parser does: `\r\n` and
+  /// `\r` become `\n`, and tabs and line breaks in attribute values become spaces. Characters
+  /// written as references, such as `&#xD;`, are kept.
+  ///
+  /// (`false` by default)
+  pub fn xml_normalization(&mut self, flag: bool) -> &mut JsonConfig {
+    self.xml_normalization = Some(flag);
+    self
+  }
+
+  /// Mapping convention
+  ///
+  /// Build JSON with another [Convention] than node-xml2js. The other options don't apply to it.
+  /// Conventions are not part of serialized configurations.
+  ///
+  /// (`None` by default, the [Xml2Js] convention configured by this `JsonConfig`)
+  ///
+  /// [Xml2Js]: crate::Xml2Js
+  pub fn convention<C: Convention + 'static>(&mut self, convention: C) -> &mut JsonConfig {
+    self.convention = Some(Arc::new(convention));
+    self
+  }
+
+  /// Finalize configuration options and build a JsonBuilder instance, failing with every conflict
+  /// between them: empty or identical keys, or `merge_attrs` together with `ignore_attrs`.
+  ///
+  /// ```rust
+  /// use xml2json_rs::JsonConfig;
+  ///
+  /// let error = JsonConfig::new()
+  ///   .attrkey("_")
+  ///   .merge_attrs(true)
+  ///   .ignore_attrs(true)
+  ///   .try_finalize();
+  /// assert_eq!(
+  ///   error.unwrap_err().details(),
+  ///   r#"charkey and attrkey are both "_"; merge_attrs and ignore_attrs are both set"#
+  /// );
+  /// ```
+  pub fn try_finalize(&self) -> Result<JsonBuilder, Error> {
+    let builder = self.finalize();
+    utils::check_conflicts(builder.conflicts())?;
+    Ok(builder)
+  }
+
   /// Finalize configuration options and build a JsonBuilder instance
   pub fn finalize(&self) -> JsonBuilder {
+    let json = JsonOptions {
+      charkey:                 self.charkey.clone().unwrap_or_else(|| "_".to_owned()),
+      attrkey:                 self.attrkey.clone().unwrap_or_else(|| "$".to_owned()),
+      empty_tag:               self.empty_tag.clone().unwrap_or_else(|| "".to_owned()),
+      explicit_root:           self.explicit_root.unwrap_or(true),
+      trim:                    self.trim.unwrap_or(false),
+      ignore_attrs:            self.ignore_attrs.unwrap_or(false),
+      merge_attrs:             self.merge_attrs.unwrap_or(false),
+      attr_prefix:             self.attr_prefix.clone().unwrap_or_default(),
+      normalize_text:          self.normalize_text.unwrap_or(false),
+      lowercase_tags:          self.lowercase_tags.unwrap_or(false),
+      explicit_array:          self.explicit_array.unwrap_or(true),
+      explicit_charkey:        self.explicit_charkey.unwrap_or(false),
+      decode_names:            self.decode_names.unwrap_or(false),
+      collisions:              self.collisions.clone().unwrap_or(CollisionPolicy::Merge),
+      childkey:                self.childkey.clone().unwrap_or_else(|| "$$".to_owned()),
+      explicit_children:       self.explicit_children.unwrap_or(false),
+      preserve_children_order: self.preserve_children_order.unwrap_or(false),
+      chars_as_children:       self.chars_as_children.unwrap_or(false),
+      include_white_chars:     self.include_white_chars.unwrap_or(false),
+      include_comments:        self.include_comments.unwrap_or(false),
+      include_instructions:    self.include_instructions.unwrap_or(false),
+      include_prolog:          self.include_prolog.unwrap_or(false),
+      xml_normalization:       self.xml_normalization.unwrap_or(false)
+    };
     JsonBuilder {
-      charkey:          self.charkey.clone().unwrap_or_else(|| "_".to_owned()),
-      attrkey:          self.attrkey.clone().unwrap_or_else(|| "$".to_owned()),
-      empty_tag:        self.empty_tag.clone().unwrap_or_else(|| "".to_owned()),
-      explicit_root:    self.explicit_root.clone().unwrap_or(true),
-      trim:             self.trim.clone().unwrap_or(false),
-      ignore_attrs:     self.ignore_attrs.clone().unwrap_or(false),
-      merge_attrs:      self.merge_attrs.clone().unwrap_or(false),
-      normalize_text:   self.normalize_text.clone().unwrap_or(false),
-      lowercase_tags:   self.lowercase_tags.clone().unwrap_or(false),
-      explicit_array:   self.explicit_array.clone().unwrap_or(true),
-      explicit_charkey: self.explicit_charkey.clone().unwrap_or(false)
+      xml2js:     Xml2Js {
+        json,
+        xml: XmlOptions::default()
+      },
+      convention: self.convention.clone()
     }
   }
 }
 
-// Text storage with state to distingiush between text in elements and text in CDATA sections
-// CDATA (literal) text will be added to JSON even when it is whitespace.
-struct Text {
-  data:    String,
-  literal: bool
+/// JSON builder struct for building JSON from XML
+#[derive(Debug, Default)]
+pub struct JsonBuilder {
+  xml2js:     Xml2Js,
+  convention: Option<Arc<dyn Convention>>
 }
 
-impl Default for Text {
-  fn default() -> Text {
-    Text {
-      data:    "".to_owned(),
-      literal: false
+impl JsonBuilder {
+  // Options of the Xml2Js convention
+  pub(crate) fn options(&self) -> &JsonOptions {
+    &self.xml2js.json
+  }
+
+  // Conflicts between the configuration options
+  fn conflicts(&self) -> Vec<String> {
+    let options = self.options();
+    let mut keys = vec![("charkey", options.charkey.as_str()), ("attrkey", options.attrkey.as_str())];
+    if options.explicit_children {
+      keys.push(("childkey", options.childkey.as_str()));
     }
+    let mut conflicts = utils::key_conflicts(&keys);
+    if options.merge_attrs && options.ignore_attrs {
+      conflicts.push("merge_attrs and ignore_attrs are both set".to_owned());
+    }
+    if options.collisions == CollisionPolicy::Escape(String::new()) {
+      conflicts.push("collisions escape prefix is empty".to_owned());
+    }
+    conflicts
   }
-}
 
-// Stores state for the current and previous levels in the XML tree.
-struct Node {
-  value: JsonValue,
-  text:  Text
-}
+  /// Key character content is stored under
+  pub fn charkey(&self) -> &str {
+    &self.options().charkey
+  }
 
-impl Node {
-  fn new() -> Node {
-    Node {
-      value: json!({}),
-      text:  Text::default()
-    }
+  /// Key attributes are stored under
+  pub fn attrkey(&self) -> &str {
+    &self.options().attrkey
   }
-}
 
-/// JSON builder struct for building JSON from XML
-pub struct JsonBuilder {
-  charkey:          String,
-  attrkey:          String,
-  empty_tag:        String,
-  explicit_root:    bool,
-  trim:             bool,
-  ignore_attrs:     bool,
-  merge_attrs:      bool,
-  normalize_text:   bool,
-  lowercase_tags:   bool,
-  explicit_array:   bool,
-  explicit_charkey: bool
-}
+  /// Value of empty elements
+  pub fn empty_tag(&self) -> &str {
+    &self.options().empty_tag
+  }
 
-impl Default for JsonBuilder {
-  fn default() -> JsonBuilder {
-    JsonBuilder {
-      charkey:          "_".to_owned(),
-      attrkey:          "$".to_owned(),
-      empty_tag:        "".to_owned(),
-      explicit_root:    true,
-      trim:             false,
-      ignore_attrs:     false,
-      merge_attrs:      false,
-      normalize_text:   false,
-      lowercase_tags:   false,
-      explicit_array:   true,
-      explicit_charkey: false
-    }
+  /// Whether the document element is part of the JSON
+  pub fn explicit_root(&self) -> bool {
+    self.options().explicit_root
   }
-}
 
-impl JsonBuilder {
-  // If text matches only newlines, spaces and tabs
-  fn is_whitespace(&self, value: &str) -> bool {
-    WHITESPACE_RE.is_match(value)
-  }
-
-  // This function is used to build out the JSON object.
-  // the behavior depends on the `explicit_array` setting. When this value is
-  // - true: an array will be created at `key` if it doesn't exist and new values will be pushed
-  // - false: `value` is assigned at `key` and converted into an array if there are multiple values
-  // at that key
-  fn assign_or_push(&self, object: &mut JsonValue, key: &str, value: JsonValue) {
-    if object.get(key).is_none() {
-      if self.explicit_array {
-        object[key] = json!([value]);
-      } else {
-        object[key] = value;
-      }
-    } else {
-      // Wrap object[key] in an array if it isn't one already
-      if !object[key].is_array() {
-        let current = object[key].take();
-        object[key] = json!([current]);
-      }
-      if let Some(array) = object[key].as_array_mut() {
-        array.push(value);
-      }
-    }
+  /// Whether whitespace is trimmed from text
+  pub fn trim(&self) -> bool {
+    self.options().trim
   }
 
-  // Process start tag
-  fn process_start(&self, event: &BytesStart, stack: &mut Vec<Node>, reader: &mut Reader<&[u8]>) -> Result<(), Error> {
-    let mut node = Node::new();
-
-    // Add any attributes
-    if !self.ignore_attrs {
-      // Initialize attribute object
-      if event.attributes().peekable().peek().is_some() && node.value.get(&self.attrkey).is_none() && !self.merge_attrs {
-        node.value[&self.attrkey] = json!({});
-      }
-
-      for attr in event.attributes() {
-        if let Ok(attr) = attr {
-          let value = attr.unescape_and_decode_value(&reader)?;
-          let key = std::str::from_utf8(attr.key)?;
-          if self.merge_attrs {
-            self.assign_or_push(&mut node.value, key, value.into());
-          } else {
-            node.value[&self.attrkey][key] = value.into();
-          }
-        }
-      }
-    }
+  /// Whether attributes are left out
+  pub fn ignore_attrs(&self) -> bool {
+    self.options().ignore_attrs
+  }
 
-    stack.push(node);
-    Ok(())
-  }
-
-  // Process text
-  fn process_text(&self, event: &BytesText, stack: &mut Vec<Node>, reader: &mut Reader<&[u8]>) -> Result<(), Error> {
-    let cdata = event.unescape_and_decode(&reader)?;
-
-    if let Some(last_node) = stack.last_mut() {
-      let text = &mut last_node.text.data;
-      // Setting reader.trim_text will remove all whitespaces in char data. To preserve
-      // compatibility with node-xml2js two or more consecutive whitespace characters will be
-      // replaced with a single space and then the resulting string will be trimmed
-      if self.normalize_text && !text.is_empty() {
-        let normalized = TWO_OR_MORE_WHITESPACE_RE.replace_all(text, NoExpand(" ")).into_owned();
-        text.clear();
-        text.push_str(&normalized);
-        let _ = text.trim();
-      }
-      text.push_str(&cdata);
-    }
+  /// Whether attributes are merged with child elements
+  pub fn merge_attrs(&self) -> bool {
+    self.options().merge_attrs
+  }
 
-    Ok(())
+  /// Prefix of merged attribute names
+  pub fn attr_prefix(&self) -> &str {
+    &self.options().attr_prefix
   }
 
-  // Process end, takes a `tag` rather than an `event` since an Event::Empty(e) uses this function as
-  // well
-  fn process_end(&self, tag: &[u8], stack: &mut Vec<Node>) -> Result<Option<JsonValue>, Error> {
-    let close_tag = if self.lowercase_tags {
-      std::str::from_utf8(tag)?.to_lowercase()
-    } else {
-      std::str::from_utf8(tag)?.to_owned()
-    };
-    // The JSON value that which will be nested inside of `outer` (unless we are at EOF)
-    let mut inner = match stack.pop() {
-      Some(j) => j,
-      None => return Err(Error::new(ErrorKind::Unknown, "Expected stack item at close tag."))
-    };
-    let stack_len = stack.len();
-    let outer = stack.last_mut();
+  /// Whether whitespace in text is normalized
+  pub fn normalize_text(&self) -> bool {
+    self.options().normalize_text
+  }
 
-    // This can grow to contain other whitespace characters ('\s')
-    let mut whitespace = "".to_owned();
-    let mut text = inner.text.data.as_ref();
+  /// Whether tag names are converted to lowercase
+  pub fn lowercase_tags(&self) -> bool {
+    self.options().lowercase_tags
+  }
 
-    if self.is_whitespace(text) && !inner.text.literal {
-      whitespace.push_str(text);
-    } else {
-      if self.trim {
-        text = text.trim();
-      }
-
-      // FIXME: warning for unused `normalized` can this be restructured in a better way?
-      let mut _normalized = String::new();
-      if self.normalize_text {
-        _normalized = TWO_OR_MORE_WHITESPACE_RE.replace_all(text, NoExpand(" ")).into_owned();
-        text = _normalized.trim().as_ref();
-      }
-
-      if utils::json_is_empty(&inner.value) && !self.explicit_charkey {
-        inner.value = JsonValue::String(text.to_owned());
-      } else {
-        inner.value[&self.charkey] = text.into();
-      }
-    }
+  /// Whether child elements are always stored in arrays
+  pub fn explicit_array(&self) -> bool {
+    self.options().explicit_array
+  }
 
-    if utils::json_is_empty(&inner.value) {
-      if !self.empty_tag.is_empty() {
-        inner.value = JsonValue::String(self.empty_tag.clone());
-      } else {
-        inner.value = JsonValue::String(whitespace);
-      }
-    }
+  /// Whether text is always stored under `charkey`
+  pub fn explicit_charkey(&self) -> bool {
+    self.options().explicit_charkey
+  }
 
-    // Check if we have closed all open tags
-    if stack_len > 0 {
-      if let Some(outer) = outer {
-        self.assign_or_push(&mut outer.value, &close_tag, inner.value);
-      }
-    } else {
-      // At EOF - either wrap result in an explicit root or return inner's value
-      let output = if self.explicit_root {
-        let output = json!({
-          close_tag: inner.value
-        });
-        output
-      } else {
-        inner.value
-      };
-      return Ok(Some(output));
-    }
-    Ok(None)
+  /// Whether `_xHHHH_` escapes in names are decoded
+  pub fn decode_names(&self) -> bool {
+    self.options().decode_names
   }
 
-  // Process empty
-  fn process_empty(&self, event: &BytesStart, stack: &mut Vec<Node>, reader: &mut Reader<&[u8]>) -> Result<Option<JsonValue>, Error> {
-    self.process_start(event, stack, reader)?;
-    self.process_end(event.name(), stack)
+  /// Policy for names that collide with `attrkey` or `charkey`
+  pub fn collisions(&self) -> &CollisionPolicy {
+    &self.options().collisions
   }
 
-  // Process XML CDATA
-  fn process_cdata(&self, event: &BytesCData, stack: &mut Vec<Node>, reader: &mut Reader<&[u8]>) -> Result<(), Error> {
-    self.process_text(&event.clone().escape(), stack, reader)?;
+  /// Key children are stored under
+  pub fn childkey(&self) -> &str {
+    &self.options().childkey
+  }
+
+  /// Whether children are stored under `childkey`
+  pub fn explicit_children(&self) -> bool {
+    self.options().explicit_children
+  }
 
-    if let Some(mut last_node) = stack.last_mut() {
-      last_node.text.literal = true;
+  /// Whether children are stored in document order
+  pub fn preserve_children_order(&self) -> bool {
+    self.options().preserve_children_order
+  }
+
+  /// Whether text is stored as children
+  pub fn chars_as_children(&self) -> bool {
+    self.options().chars_as_children
+  }
+
+  /// Whether whitespace-only text is kept as children
+  pub fn include_white_chars(&self) -> bool {
+    self.options().include_white_chars
+  }
+
+  /// Whether comments are kept as children
+  pub fn include_comments(&self) -> bool {
+    self.options().include_comments
+  }
+
+  /// Whether processing instructions are kept as children
+  pub fn include_instructions(&self) -> bool {
+    self.options().include_instructions
+  }
+
+  /// Whether the top level nodes of the document are kept in document order
+  pub fn include_prolog(&self) -> bool {
+    self.options().include_prolog
+  }
+
+  /// Whether line breaks and attribute values are normalized as by an XML parser
+  pub fn xml_normalization(&self) -> bool {
+    self.options().xml_normalization
+  }
+
+  /// Mapping convention, the [Xml2Js] convention configured by this builder unless another one
+  /// is set
+  ///
+  /// [Xml2Js]: crate::Xml2Js
+  pub fn convention(&self) -> &dyn Convention {
+    match self.convention {
+      Some(ref convention) => convention.as_ref(),
+      None => &self.xml2js
     }
-    Ok(())
+  }
+
+  // Check if JSON is built with the Xml2Js convention configured by this builder
+  pub(crate) fn is_xml2js(&self) -> bool {
+    self.convention.is_none()
   }
 
   /// Build JSON from xml
   pub fn build_from_xml(&self, xml: &str) -> Result<JsonValue, Error> {
-    let mut reader = Reader::from_str(xml);
-    let mut buffer = Vec::new();
-    let mut output = JsonValue::Null;
-    let mut stack = Vec::new();
-
-    loop {
-      match reader.read_event(&mut buffer) {
-        Ok(Event::Start(ref e)) => self.process_start(e, &mut stack, &mut reader)?,
-
-        Ok(Event::Text(ref e)) => self.process_text(e, &mut stack, &mut reader)?,
-
-        Ok(Event::End(ref e)) => {
-          if let Some(o) = self.process_end(e.name(), &mut stack)? {
-            output = o;
-          }
-        },
-
-        Ok(Event::CData(ref e)) => self.process_cdata(e, &mut stack, &mut reader)?,
-
-        Ok(Event::Empty(ref e)) => {
-          if let Some(o) = self.process_empty(e, &mut stack, &mut reader)? {
-            output = o;
-          }
-        },
-
-        Ok(Event::Eof) => {
-          break;
-        },
-
-        // Skip over everything else
-        Ok(_) => (),
-
-        Err(e) => {
-          return Err(Error::new(
-            ErrorKind::Syntax,
-            format!("Error at position {}: {:?}", reader.buffer_position(), e)
-          ))
-        },
-      }
-
-      buffer.clear();
-    }
-
-    Ok(output)
+    // node-xml2js reads line breaks and attribute values as written, unless `xml_normalization` is
+    // set
+    let nodes = if self.is_xml2js() && !self.xml_normalization() {
+      tree::parse_verbatim(xml)?
+    } else {
+      tree::parse_document(xml)?
+    };
+    self.convention().to_json(&nodes)
   }
 
   /// Build JSON string from xml
@@ -467,6 +678,7 @@ impl JsonBuilder {
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::error::ErrorKind;
 
   use pretty_assertions::assert_eq;
 
@@ -478,36 +690,159 @@ mod tests {
   }
 
   #[test]
-  fn is_whitespace1() {
-    let builder = JsonBuilder::default();
-    assert!(builder.is_whitespace(" \t \n "));
+  fn decode_names() {
+    let builder = JsonConfig::new().decode_names(true).finalize();
+    let json = builder
+      .build_string_from_xml(r#"<_x0032_021 a_x003C_b="c"><_x005F_x0020_/></_x0032_021>"#)
+      .unwrap();
+    assert_eq!(json, r#"{"2021":{"$":{"a<b":"c"},"_x0020_":[""]}}"#);
+
+    let json = JsonBuilder::default().build_string_from_xml(r#"<first_x0020_name/>"#).unwrap();
+    assert_eq!(json, r#"{"first_x0020_name":""}"#);
   }
 
   #[test]
-  fn is_whitespace2() {
-    let builder = JsonBuilder::default();
-    assert!(!builder.is_whitespace(" \t A \n "));
+  fn collisions_merge() {
+    let json = JsonBuilder::default().build_string_from_xml(r#"<a><_>b</_></a>"#).unwrap();
+    assert_eq!(json, r#"{"a":{"_":["b"]}}"#);
   }
 
   #[test]
-  fn assign_or_push1() {
-    let builder = JsonBuilder::default();
-    let mut actual = json!({});
-    let _ = builder.assign_or_push(&mut actual, "A", "B".into());
-    let _ = builder.assign_or_push(&mut actual, "C", "D".into());
-    let _ = builder.assign_or_push(&mut actual, "C", "E".into());
-    let expected: JsonValue = serde_json::from_str(r#"{"A":["B"],"C":["D","E"]}"#).unwrap();
-    assert_eq!(actual, expected);
+  fn collisions_escape() {
+    let builder = JsonConfig::new()
+      .attrkey("attrs")
+      .collisions(CollisionPolicy::Escape("~".to_owned()))
+      .finalize();
+    let json = builder
+      .build_string_from_xml(r#"<a x="y"><attrs>b</attrs><_/><_>c</_><_x/></a>"#)
+      .unwrap();
+    assert_eq!(json, r#"{"a":{"attrs":{"x":"y"},"~attrs":["b"],"~_":["","c"],"_x":[""]}}"#);
+
+    let builder = JsonConfig::new()
+      .merge_attrs(true)
+      .collisions(CollisionPolicy::Escape("~".to_owned()))
+      .finalize();
+    let json = builder.build_string_from_xml(r#"<a _="b" ~c="d"></a>"#).unwrap();
+    assert_eq!(json, r#"{"a":{"~_":["b"],"~~c":["d"]}}"#);
+  }
+
+  #[test]
+  fn try_finalize() {
+    assert!(JsonConfig::new().try_finalize().is_ok());
+
+    let error = JsonConfig::new()
+      .attrkey("")
+      .charkey("$$")
+      .explicit_children(true)
+      .collisions(CollisionPolicy::Escape("".to_owned()))
+      .try_finalize()
+      .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+    assert_eq!(
+      error.details(),
+      r#"attrkey is empty; charkey and childkey are both "$$"; collisions escape prefix is empty"#
+    );
+  }
+
+  #[test]
+  fn config_from_json() {
+    let mut config = JsonConfig::lossless();
+    config.collisions(CollisionPolicy::Escape("~".to_owned()));
+    let json = serde_json::to_string(&config).unwrap();
+    let builder = JsonConfig::from_json_str(&json).unwrap().finalize();
+    assert!(builder.explicit_children());
+    assert_eq!(builder.collisions(), &CollisionPolicy::Escape("~".to_owned()));
+
+    let error = JsonConfig::from_json_str(r#"{"trim": "yes"}"#).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+    assert_eq!(
+      error.details(),
+      "invalid type: string \"yes\", expected a boolean at line 1 column 14"
+    );
+  }
+
+  #[test]
+  fn config_from_path() {
+    let path = std::env::temp_dir().join("xml2json-rs-json-config.json");
+    std::fs::write(&path, r##"{"charkey": "#text"}"##).unwrap();
+    let builder = JsonConfig::from_path(&path).unwrap().finalize();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(builder.charkey(), "#text");
+
+    let error = JsonConfig::from_path(&path).unwrap_err();
+    assert!(error.details().starts_with(&format!("{}: ", path.display())));
+  }
+
+  #[test]
+  fn getters() {
+    let builder = JsonConfig::new().attrkey("@").explicit_array(false).finalize();
+    assert_eq!(builder.attrkey(), "@");
+    assert_eq!(builder.charkey(), "_");
+    assert!(!builder.explicit_array());
+    assert_eq!(builder.collisions(), &CollisionPolicy::Merge);
+  }
+
+  #[test]
+  fn attr_prefix() {
+    let builder = JsonConfig::new().merge_attrs(true).attr_prefix("@").finalize();
+    let json = builder.build_string_from_xml(r#"<a id="1"><id>2</id><b x="y">c</b></a>"#).unwrap();
+    assert_eq!(json, r#"{"a":{"@id":["1"],"id":["2"],"b":[{"@x":["y"],"_":"c"}]}}"#);
+
+    // Without merge_attrs the prefix isn't used
+    let builder = JsonConfig::new().attr_prefix("@").finalize();
+    let json = builder.build_string_from_xml(r#"<a id="1"/>"#).unwrap();
+    assert_eq!(json, r#"{"a":{"$":{"id":"1"}}}"#);
+  }
+
+  #[test]
+  fn collisions_reject() {
+    let builder = JsonConfig::new().collisions(CollisionPolicy::Reject).finalize();
+    let error = builder.build_string_from_xml(r#"<a><_>b</_></a>"#).unwrap_err();
+    assert_eq!(error.details(), r#"Name "_" collides with attrkey or charkey."#);
+    assert!(builder.build_string_from_xml(r#"<a><b>_</b></a>"#).is_ok());
+  }
+
+  #[test]
+  fn explicit_children() {
+    let builder = JsonConfig::new().explicit_children(true).finalize();
+    let json = builder.build_string_from_xml(r#"<a x="1"><b>c</b><d/><b>e</b></a>"#).unwrap();
+    assert_eq!(json, r#"{"a":{"$":{"x":"1"},"$$":{"b":["c","e"],"d":[""]}}}"#);
+  }
+
+  #[test]
+  fn preserve_children_order() {
+    let builder = JsonConfig::new()
+      .explicit_children(true)
+      .preserve_children_order(true)
+      .explicit_root(false)
+      .finalize();
+    let json = builder.build_string_from_xml("<a>text <b>c</b><!--d--> <d/></a>").unwrap();
+    assert_eq!(json, r##"{"#name":"a","$$":[{"#name":"b","_":"c"},{"#name":"d"}],"_":"text  "}"##);
+  }
+
+  #[test]
+  fn chars_as_children() {
+    let builder = JsonConfig::new()
+      .explicit_children(true)
+      .preserve_children_order(true)
+      .chars_as_children(true)
+      .trim(true)
+      .finalize();
+    let json = builder.build_string_from_xml("<a> text <b/> \n <![CDATA[ c ]]></a>").unwrap();
+    assert_eq!(
+      json,
+      r##"{"a":{"$$":[{"#name":"__text__","_":"text"},{"#name":"b"},{"#name":"__cdata__","_":" c "}]}}"##
+    );
   }
 
   #[test]
-  fn assign_or_push2() {
-    let builder = JsonConfig::new().explicit_array(false).finalize();
-    let mut actual = json!({});
-    let _ = builder.assign_or_push(&mut actual, "A", "B".into());
-    let _ = builder.assign_or_push(&mut actual, "C", "D".into());
-    let _ = builder.assign_or_push(&mut actual, "C", "E".into());
-    let expected: JsonValue = serde_json::from_str(r#"{"A":"B","C":["D","E"]}"#).unwrap();
-    assert_eq!(actual, expected);
+  fn xml_normalization() {
+    let xml = "<a b=\"c\r\nd&#xA;\">e\r\nf&#xD;\rg</a>";
+    let json = JsonBuilder::default().build_string_from_xml(xml).unwrap();
+    assert_eq!(json, r#"{"a":{"$":{"b":"c\r\nd\n"},"_":"e\r\nf\r\rg"}}"#);
+
+    let builder = JsonConfig::new().xml_normalization(true).finalize();
+    let json = builder.build_string_from_xml(xml).unwrap();
+    assert_eq!(json, r#"{"a":{"$":{"b":"c  d\n"},"_":"e\nf\r\ng"}}"#);
   }
 }
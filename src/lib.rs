@@ -62,7 +62,7 @@
 //! - [`build_from_xml`] build a [`serde_json::Value`] from an XML `String`.
 //! - [`build_string_from_xml`] build a JSON serialized `String` from an XML `String`.
 //! - [`build_pretty_string_from_xml`] build a pretty-printed JSON serialized `String` from an XML
-//! `String`
+//!   `String`
 //!
 //! ## Example
 //!
@@ -99,6 +99,63 @@
 //! }
 //! ```
 //!
+//! ## Serde
+//!
+//! [`from_str`] deserializes a type directly from XML, with the mapping of a [`JsonConfig`].
+//!
+//! ```rust
+//! use serde::Deserialize;
+//! use std::error::Error;
+//! use xml2json_rs::{from_str, JsonConfig};
+//!
+//! #[derive(Deserialize)]
+//! struct Book {
+//!   title: String,
+//!   year:  u32
+//! }
+//!
+//! fn main() -> Result<(), Box<dyn Error>> {
+//!   let xml =
+//!     r#"<?xml version="1.0"?><book><title>The Name of the Wind</title><year>2007</year></book>"#;
+//!   let book: Book = from_str(xml, JsonConfig::new().explicit_root(false))?;
+//!   assert_eq!(
+//!     (book.title.as_str(), book.year),
+//!     ("The Name of the Wind", 2007)
+//!   );
+//!   Ok(())
+//! }
+//! ```
+//!
+//! [`to_string`] and [`to_writer`] serialize a type as XML, with the mapping of an [`XmlConfig`].
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use std::error::Error;
+//! use xml2json_rs::{to_string, XmlConfig};
+//!
+//! #[derive(Serialize)]
+//! struct Book {
+//!   title: String,
+//!   year:  u32
+//! }
+//!
+//! fn main() -> Result<(), Box<dyn Error>> {
+//!   let book = Book {
+//!     title: "The Name of the Wind".to_owned(),
+//!     year:  2007
+//!   };
+//!   let xml = to_string(&book, XmlConfig::new().root_name("book"))?;
+//!   assert_eq!(
+//!     xml,
+//!     r#"<?xml version="1.0"?><book><title>The Name of the Wind</title><year>2007</year></book>"#
+//!   );
+//!   Ok(())
+//! }
+//! ```
+//!
+//! [`from_str`]: fn.from_str.html
+//! [`to_string`]: fn.to_string.html
+//! [`to_writer`]: fn.to_writer.html
 //! [`XmlBuilder`]: struct.XmlBuilder.html
 //! [`XmlConfig`]: struct.XmlConfig.html
 //! [`serde_json::Value`]: https://docs.serde.rs/serde_json/value/enum.Value.html
@@ -119,12 +176,52 @@ extern crate regex;
 #[macro_use]
 extern crate lazy_static;
 
+mod c14n;
+mod convention;
+mod de;
 mod json;
+mod mapping;
+mod name;
+mod namespace;
+mod render;
+mod roundtrip;
+mod ser;
+mod tree;
 mod xml;
 
-pub use json::{JsonBuilder, JsonConfig};
+pub use c14n::{canonicalize, canonicalize_exclusive, Canonicalization};
+
+pub use convention::{
+  BadgerFish,
+  Convention,
+  FeedFormat,
+  GData,
+  JsonFeed,
+  JsonML,
+  Parker,
+  Plist,
+  Soap,
+  SoapFault,
+  SoapVersion,
+  Xml2Js,
+  XmlJs,
+  XmlRpc,
+  XmlToDict
+};
+
+pub use de::from_str;
+
+pub use json::{CollisionPolicy, JsonBuilder, JsonConfig};
+
+pub use mapping::Mapping;
+
+pub use roundtrip::{verify_roundtrip, Diff};
+
+pub use ser::{to_string, to_writer};
+
+pub use tree::{Content, Element};
 
-pub use xml::{Declaration, Encoding, Indentation, Version, XmlBuilder, XmlConfig};
+pub use xml::{AttrWrap, Declaration, Encoding, Indentation, NamePolicy, Newline, NullPolicy, ValuePolicy, Version, XmlBuilder, XmlConfig};
 
 pub use error::Error as X2JError;
 
@@ -0,0 +1,452 @@
+use crate::{
+  error::Error,
+  json::{CollisionPolicy, JsonBuilder, JsonConfig},
+  utils,
+  xml::{NamePolicy, XmlBuilder, XmlConfig}
+};
+
+/// Mapping between XML and JSON, shared by both directions.
+///
+/// A `Mapping` configures a [JsonBuilder] and an [XmlBuilder] together, so that the XML written
+/// from the JSON of one document has the same elements, attributes and text. Options which lose
+/// information on the way to JSON, such as `trim`, `ignore_attrs` or `empty_tag`, are rejected when
+/// the builders are created.
+///
+/// The document order of the content is only kept with `explicit_children`,
+/// `preserve_children_order` and `chars_as_children`, which make the XML written the same
+/// document. Otherwise child elements are grouped by name, the text of an element is joined and
+/// written after its child elements, and whitespace between elements is dropped.
+///
+/// ```rust
+/// use xml2json_rs::Mapping;
+///
+/// let (json_builder, mut xml_builder) = Mapping::new()
+///   .attrkey("@")
+///   .explicit_root(false)
+///   .root_name("book")
+///   .explicit_array(false)
+///   .finalize()
+///   .unwrap();
+///
+/// let xml = r#"<?xml version="1.0"?><book lang="en"><title>The Name of the Wind</title></book>"#;
+/// let json = json_builder.build_from_xml(xml).unwrap();
+/// assert_eq!(
+///   json.to_string(),
+///   r#"{"@":{"lang":"en"},"title":"The Name of the Wind"}"#
+/// );
+/// assert_eq!(xml_builder.build_from_json(&json).unwrap(), xml);
+///
+/// assert!(Mapping::new().trim(true).finalize().is_err());
+/// ```
+#[derive(Debug, Default)]
+pub struct Mapping {
+  attrkey:                 Option<String>,
+  charkey:                 Option<String>,
+  childkey:                Option<String>,
+  root_name:               Option<String>,
+  empty_tag:               Option<String>,
+  explicit_root:           Option<bool>,
+  explicit_array:          Option<bool>,
+  explicit_charkey:        Option<bool>,
+  trim:                    Option<bool>,
+  normalize_text:          Option<bool>,
+  ignore_attrs:            Option<bool>,
+  merge_attrs:             Option<bool>,
+  attr_prefix:             Option<String>,
+  lowercase_tags:          Option<bool>,
+  escape_names:            Option<bool>,
+  collisions:              Option<CollisionPolicy>,
+  explicit_children:       Option<bool>,
+  preserve_children_order: Option<bool>,
+  chars_as_children:       Option<bool>,
+  include_white_chars:     Option<bool>,
+  include_comments:        Option<bool>
+}
+
+impl Mapping {
+  /// Initialize a new Mapping instance.
+  ///
+  /// This uses the builder pattern. All options are initialized to `None` and can be set using
+  /// `self`s methods. Any options not set will use the defaults of [JsonConfig].
+  pub fn new() -> Mapping {
+    Mapping::default()
+  }
+
+  /// Key to store attributes under. See [JsonConfig::attrkey].
+  pub fn attrkey<T: Into<String>>(&mut self, key: T) -> &mut Mapping {
+    self.attrkey = Some(key.into());
+    self
+  }
+
+  /// Key to store character data under. See [JsonConfig::charkey].
+  pub fn charkey<T: Into<String>>(&mut self, key: T) -> &mut Mapping {
+    self.charkey = Some(key.into());
+    self
+  }
+
+  /// Key to store children under. See [JsonConfig::childkey].
+  pub fn childkey<T: Into<String>>(&mut self, key: T) -> &mut Mapping {
+    self.childkey = Some(key.into());
+    self
+  }
+
+  /// Name of the document element when it isn't part of the JSON. Required with
+  /// `explicit_root(false)`.
+  pub fn root_name<T: Into<String>>(&mut self, name: T) -> &mut Mapping {
+    self.root_name = Some(name.into());
+    self
+  }
+
+  /// Value of empty elements. See [JsonConfig::empty_tag]. Can't be reversed unless empty.
+  pub fn empty_tag<T: Into<String>>(&mut self, value: T) -> &mut Mapping {
+    self.empty_tag = Some(value.into());
+    self
+  }
+
+  /// See [JsonConfig::explicit_root].
+  pub fn explicit_root(&mut self, flag: bool) -> &mut Mapping {
+    self.explicit_root = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::explicit_array].
+  pub fn explicit_array(&mut self, flag: bool) -> &mut Mapping {
+    self.explicit_array = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::explicit_charkey].
+  pub fn explicit_charkey(&mut self, flag: bool) -> &mut Mapping {
+    self.explicit_charkey = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::trim]. Can't be reversed.
+  pub fn trim(&mut self, flag: bool) -> &mut Mapping {
+    self.trim = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::normalize_text]. Can't be reversed.
+  pub fn normalize_text(&mut self, flag: bool) -> &mut Mapping {
+    self.normalize_text = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::ignore_attrs]. Can't be reversed.
+  pub fn ignore_attrs(&mut self, flag: bool) -> &mut Mapping {
+    self.ignore_attrs = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::merge_attrs]. Requires `attr_prefix`.
+  pub fn merge_attrs(&mut self, flag: bool) -> &mut Mapping {
+    self.merge_attrs = Some(flag);
+    self
+  }
+
+  /// Prefix for merged attribute names. See [JsonConfig::attr_prefix].
+  pub fn attr_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut Mapping {
+    self.attr_prefix = Some(prefix.into());
+    self
+  }
+
+  /// See [JsonConfig::lowercase_tags]. Can't be reversed.
+  pub fn lowercase_tags(&mut self, flag: bool) -> &mut Mapping {
+    self.lowercase_tags = Some(flag);
+    self
+  }
+
+  /// Escape JSON keys which aren't valid XML names with [NamePolicy::Escape], and decode them with
+  /// [JsonConfig::decode_names].
+  pub fn escape_names(&mut self, flag: bool) -> &mut Mapping {
+    self.escape_names = Some(flag);
+    self
+  }
+
+  /// Policy for names that collide with `attrkey` or `charkey`, in both directions.
+  pub fn collisions(&mut self, policy: CollisionPolicy) -> &mut Mapping {
+    self.collisions = Some(policy);
+    self
+  }
+
+  /// See [JsonConfig::explicit_children].
+  pub fn explicit_children(&mut self, flag: bool) -> &mut Mapping {
+    self.explicit_children = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::preserve_children_order].
+  pub fn preserve_children_order(&mut self, flag: bool) -> &mut Mapping {
+    self.preserve_children_order = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::chars_as_children]. Requires `preserve_children_order` and
+  /// `include_white_chars`.
+  pub fn chars_as_children(&mut self, flag: bool) -> &mut Mapping {
+    self.chars_as_children = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::include_white_chars].
+  pub fn include_white_chars(&mut self, flag: bool) -> &mut Mapping {
+    self.include_white_chars = Some(flag);
+    self
+  }
+
+  /// See [JsonConfig::include_comments].
+  pub fn include_comments(&mut self, flag: bool) -> &mut Mapping {
+    self.include_comments = Some(flag);
+    self
+  }
+
+  // Every option which can't be reversed, or which conflicts with another option
+  fn conflicts(&self) -> Vec<String> {
+    let mut conflicts = Vec::new();
+    let lossy = [
+      ("trim", self.trim),
+      ("normalize_text", self.normalize_text),
+      ("ignore_attrs", self.ignore_attrs),
+      ("lowercase_tags", self.lowercase_tags)
+    ];
+    for (option, flag) in lossy.iter() {
+      if flag.unwrap_or(false) {
+        conflicts.push(format!("{} can't be reversed", option));
+      }
+    }
+
+    // An element holding the text of `empty_tag` would be written as an empty element
+    if !self.empty_tag.as_deref().unwrap_or("").is_empty() {
+      conflicts.push("empty_tag can't be reversed".to_owned());
+    }
+
+    // Merged attributes are told apart from child elements by their prefix
+    if self.merge_attrs.unwrap_or(false) && self.attr_prefix.as_deref().unwrap_or("").is_empty() {
+      conflicts.push("merge_attrs requires attr_prefix".to_owned());
+    }
+
+    let explicit_root = self.explicit_root.unwrap_or(true);
+    match self.root_name.as_deref() {
+      None if !explicit_root => conflicts.push("explicit_root(false) requires root_name".to_owned()),
+      Some(_) if explicit_root => conflicts.push("root_name requires explicit_root(false)".to_owned()),
+      // XmlBuilder doesn't write a root element named "root", as node-xml2js
+      Some("root") => conflicts.push(r#"root_name "root" can't be reversed"#.to_owned()),
+      _ => ()
+    }
+
+    let explicit_children = self.explicit_children.unwrap_or(false);
+    let preserve_children_order = self.preserve_children_order.unwrap_or(false);
+    if preserve_children_order && !explicit_children {
+      conflicts.push("preserve_children_order requires explicit_children".to_owned());
+    }
+    let chars_as_children = self.chars_as_children.unwrap_or(false);
+    if chars_as_children && !preserve_children_order {
+      conflicts.push("chars_as_children requires preserve_children_order".to_owned());
+    }
+    // Whitespace between elements is only kept as children
+    if chars_as_children && !self.include_white_chars.unwrap_or(false) {
+      conflicts.push("chars_as_children requires include_white_chars".to_owned());
+    }
+    if self.include_comments.unwrap_or(false) && !preserve_children_order {
+      conflicts.push("include_comments requires preserve_children_order".to_owned());
+    }
+    conflicts
+  }
+
+  // Fail with every conflict
+  fn check(&self) -> Result<(), Error> {
+    utils::check_conflicts(self.conflicts())
+  }
+
+  /// Configuration for the XML to JSON direction, which may be refined with options that don't
+  /// affect the mapping
+  pub fn json_config(&self) -> Result<JsonConfig, Error> {
+    self.check()?;
+    let mut config = JsonConfig::new();
+    config
+      .explicit_root(self.explicit_root.unwrap_or(true))
+      .explicit_array(self.explicit_array.unwrap_or(true))
+      .explicit_charkey(self.explicit_charkey.unwrap_or(false))
+      .merge_attrs(self.merge_attrs.unwrap_or(false))
+      .decode_names(self.escape_names.unwrap_or(false))
+      .explicit_children(self.explicit_children.unwrap_or(false))
+      .preserve_children_order(self.preserve_children_order.unwrap_or(false))
+      .chars_as_children(self.chars_as_children.unwrap_or(false))
+      .include_white_chars(self.include_white_chars.unwrap_or(false))
+      .include_comments(self.include_comments.unwrap_or(false));
+    if let Some(ref key) = self.attrkey {
+      config.attrkey(key.as_str());
+    }
+    if let Some(ref key) = self.charkey {
+      config.charkey(key.as_str());
+    }
+    if let Some(ref key) = self.childkey {
+      config.childkey(key.as_str());
+    }
+    if let Some(ref value) = self.empty_tag {
+      config.empty_tag(value.as_str());
+    }
+    if let Some(ref prefix) = self.attr_prefix {
+      config.attr_prefix(prefix.as_str());
+    }
+    if let Some(ref policy) = self.collisions {
+      config.collisions(policy.clone());
+    }
+    Ok(config)
+  }
+
+  /// Configuration for the JSON to XML direction, which may be refined with options that don't
+  /// affect the mapping, such as `rendering` or `decl`
+  pub fn xml_config(&self) -> Result<XmlConfig, Error> {
+    self.check()?;
+    let mut config = XmlConfig::new();
+    if let Some(ref key) = self.attrkey {
+      config.attrkey(key.as_str());
+    }
+    if let Some(ref key) = self.charkey {
+      config.charkey(key.as_str());
+    }
+    if let Some(ref key) = self.childkey {
+      config.childkey(key.as_str());
+    }
+    if let Some(ref value) = self.empty_tag {
+      config.empty_tag(value.as_str());
+    }
+    if let Some(ref name) = self.root_name {
+      config.root_name(name.as_str());
+    }
+    if let Some(ref prefix) = self.attr_prefix {
+      config.attr_prefix(prefix.as_str());
+    }
+    if self.escape_names.unwrap_or(false) {
+      config.name_policy(NamePolicy::Escape);
+    }
+    if let Some(ref policy) = self.collisions {
+      config.collisions(policy.clone());
+    }
+    Ok(config)
+  }
+
+  /// Build a JsonBuilder and an XmlBuilder for this mapping
+  pub fn finalize(&self) -> Result<(JsonBuilder, XmlBuilder), Error> {
+    Ok((self.json_config()?.try_finalize()?, self.xml_config()?.try_finalize()?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::error::ErrorKind;
+
+  // Convert XML to JSON and back with `mapping`
+  fn roundtrip(mapping: &Mapping, xml: &str) -> String {
+    let (json_builder, mut xml_builder) = mapping.finalize().unwrap();
+    let json = json_builder.build_from_xml(xml).unwrap();
+    xml_builder.build_from_json(&json).unwrap()
+  }
+
+  #[test]
+  fn default_mapping() {
+    let xml = r#"<?xml version="1.0"?><a x="1"><b>c</b><b>d</b><e/></a>"#;
+    assert_eq!(roundtrip(&Mapping::new(), xml), xml);
+  }
+
+  #[test]
+  fn keys_and_empty_tag() {
+    let xml = r#"<?xml version="1.0"?><a x="1"><b y="2">c</b><e/></a>"#;
+    let mut mapping = Mapping::new();
+    mapping.attrkey("@").charkey("#").empty_tag("").explicit_charkey(true);
+    assert_eq!(roundtrip(&mapping, xml), xml);
+
+    let (json_builder, _) = mapping.finalize().unwrap();
+    let json = json_builder.build_string_from_xml(xml).unwrap();
+    assert_eq!(json, r##"{"a":{"@":{"x":"1"},"b":[{"@":{"y":"2"},"#":"c"}],"e":[""]}}"##);
+  }
+
+  #[test]
+  fn unordered_children() {
+    // Without ordered children, elements are grouped by name and text comes last
+    let xml = r#"<?xml version="1.0"?><a>x<b/><c/>y<b/></a>"#;
+    assert_eq!(roundtrip(&Mapping::new(), xml), r#"<?xml version="1.0"?><a><b/><b/><c/>xy</a>"#);
+
+    let mut mapping = Mapping::new();
+    mapping.explicit_children(true);
+    assert_eq!(roundtrip(&mapping, xml), r#"<?xml version="1.0"?><a><b/><b/><c/>xy</a>"#);
+    mapping.preserve_children_order(true);
+    assert_eq!(roundtrip(&mapping, xml), r#"<?xml version="1.0"?><a><b/><c/><b/>xy</a>"#);
+    mapping.chars_as_children(true).include_white_chars(true);
+    assert_eq!(roundtrip(&mapping, xml), xml);
+  }
+
+  #[test]
+  fn names_and_collisions() {
+    let xml = r#"<?xml version="1.0"?><a><_>b</_><first_x0020_name>c</first_x0020_name></a>"#;
+    let mut mapping = Mapping::new();
+    mapping.escape_names(true).collisions(CollisionPolicy::Escape("~".to_owned()));
+    assert_eq!(roundtrip(&mapping, xml), xml);
+  }
+
+  #[test]
+  fn merged_attributes() {
+    let xml = r#"<?xml version="1.0"?><a id="1"><id>2</id><b x="y">c</b></a>"#;
+    let mut mapping = Mapping::new();
+    mapping.merge_attrs(true).attr_prefix("@");
+    assert_eq!(roundtrip(&mapping, xml), xml);
+  }
+
+  #[test]
+  fn ordered_children() {
+    let xml = r#"<?xml version="1.0"?><a>text <b/> more<!--c--></a>"#;
+    let mut mapping = Mapping::new();
+    mapping
+      .explicit_children(true)
+      .preserve_children_order(true)
+      .chars_as_children(true)
+      .include_white_chars(true)
+      .include_comments(true);
+    assert_eq!(roundtrip(&mapping, xml), xml);
+  }
+
+  #[test]
+  fn conflicts() {
+    let error = Mapping::new()
+      .trim(true)
+      .merge_attrs(true)
+      .explicit_root(false)
+      .finalize()
+      .err()
+      .unwrap();
+    assert_eq!(error.kind(), ErrorKind::Config);
+    assert_eq!(
+      error.details(),
+      "trim can't be reversed; merge_attrs requires attr_prefix; explicit_root(false) requires root_name"
+    );
+
+    let error = Mapping::new().root_name("a").finalize().err().unwrap();
+    assert_eq!(error.details(), "root_name requires explicit_root(false)");
+
+    let error = Mapping::new().explicit_root(false).root_name("root").finalize().err().unwrap();
+    assert_eq!(error.details(), r#"root_name "root" can't be reversed"#);
+
+    let error = Mapping::new()
+      .chars_as_children(true)
+      .include_white_chars(true)
+      .finalize()
+      .err()
+      .unwrap();
+    assert_eq!(error.details(), "chars_as_children requires preserve_children_order");
+
+    let error = Mapping::new().empty_tag("EMPTY").finalize().err().unwrap();
+    assert_eq!(error.details(), "empty_tag can't be reversed");
+
+    let error = Mapping::new()
+      .explicit_children(true)
+      .preserve_children_order(true)
+      .chars_as_children(true)
+      .finalize()
+      .err()
+      .unwrap();
+    assert_eq!(error.details(), "chars_as_children requires include_white_chars");
+  }
+}
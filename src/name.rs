@@ -0,0 +1,111 @@
+use std::borrow::Cow;
+
+use regex::{Captures, Regex};
+
+lazy_static! {
+  static ref ESCAPE_RE: Regex = Regex::new(r"_x([0-9A-Fa-f]{8}|[0-9A-Fa-f]{4})_").unwrap();
+  static ref ESCAPE_START_RE: Regex = Regex::new(r"^_x([0-9A-Fa-f]{8}|[0-9A-Fa-f]{4})_").unwrap();
+}
+
+// NameStartChar (XML 1.0 fifth edition, section 2.3)
+fn is_name_start_char(c: char) -> bool {
+  matches!(c,
+    ':' | 'A'..='Z' | '_' | 'a'..='z' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}'
+    | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}'
+    | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}'
+    | '\u{10000}'..='\u{EFFFF}'
+  )
+}
+
+// NameChar (XML 1.0 fifth edition, section 2.3)
+fn is_name_char(c: char) -> bool {
+  is_name_start_char(c) || matches!(c, '-' | '.' | '0'..='9' | '\u{B7}' | '\u{300}'..='\u{36F}' | '\u{203F}'..='\u{2040}')
+}
+
+/// Check if `name` matches the XML `Name` production. Prefixed names such as `soap:Body` are valid
+/// names.
+pub(crate) fn is_valid(name: &str) -> bool {
+  let mut chars = name.chars();
+  chars.next().map_or(false, is_name_start_char) && chars.all(is_name_char)
+}
+
+/// Escape the characters of `name` which aren't allowed in an XML name as `_xHHHH_`, the encoding
+/// used by .NET's `XmlConvert.EncodeName` and SQL Server. Characters outside of the Basic
+/// Multilingual Plane are written as `_xHHHHHHHH_`. An underscore which would otherwise be read as
+/// the start of an escape sequence is escaped itself, so that [`decode`] always gives back `name`.
+pub(crate) fn encode(name: &str) -> Cow<'_, str> {
+  if is_valid(name) && !ESCAPE_RE.is_match(name) {
+    return Cow::Borrowed(name);
+  }
+
+  let mut encoded = String::with_capacity(name.len());
+  for (i, c) in name.char_indices() {
+    let valid = if i == 0 { is_name_start_char(c) } else { is_name_char(c) };
+    if !valid || (c == '_' && ESCAPE_START_RE.is_match(&name[i ..])) {
+      if (c as u32) > 0xFFFF {
+        encoded.push_str(&format!("_x{:08X}_", c as u32));
+      } else {
+        encoded.push_str(&format!("_x{:04X}_", c as u32));
+      }
+    } else {
+      encoded.push(c);
+    }
+  }
+  Cow::Owned(encoded)
+}
+
+/// Replace `_xHHHH_` and `_xHHHHHHHH_` escape sequences in `name` with the characters they encode.
+/// Sequences which don't encode a valid character are kept as they are.
+pub(crate) fn decode(name: &str) -> Cow<'_, str> {
+  ESCAPE_RE.replace_all(name, |captures: &Captures| {
+    u32::from_str_radix(&captures[1], 16)
+      .ok()
+      .and_then(std::char::from_u32)
+      .map_or_else(|| captures[0].to_owned(), |c| c.to_string())
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn valid_names() {
+    for name in &["a", "_a", "a-b.c", "soap:Body", "élément", "a\u{B7}b"] {
+      assert!(is_valid(name), "{}", name);
+    }
+    for name in &["", "first name", "2021", "a<b", "-a", ".a", "a&b"] {
+      assert!(!is_valid(name), "{}", name);
+    }
+  }
+
+  #[test]
+  fn encode_names() {
+    assert_eq!(encode("first name"), "first_x0020_name");
+    assert_eq!(encode("2021"), "_x0032_021");
+    assert_eq!(encode("a<b"), "a_x003C_b");
+    assert_eq!(encode("a\u{F0000}"), "a_x000F0000_");
+    assert_eq!(encode("_x0020_"), "_x005F_x0020_");
+    assert_eq!(encode("_xml"), "_xml");
+    assert_eq!(encode("valid"), "valid");
+  }
+
+  #[test]
+  fn decode_names() {
+    assert_eq!(decode("first_x0020_name"), "first name");
+    assert_eq!(decode("_x0032_021"), "2021");
+    assert_eq!(decode("a_x000F0000_"), "a\u{F0000}");
+    assert_eq!(decode("_x005F_x0020_"), "_x0020_");
+    assert_eq!(decode("_xD800_"), "_xD800_");
+    assert_eq!(decode("plain"), "plain");
+  }
+
+  #[test]
+  fn round_trip() {
+    for name in &["first name", "2021", "a<b", "_x0020_", "_x005F_", "x y\tz", "a\u{F0000}", "ok"] {
+      let encoded = encode(name);
+      assert!(is_valid(&encoded), "{}", encoded);
+      assert_eq!(decode(&encoded), *name);
+    }
+  }
+}
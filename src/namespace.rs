@@ -0,0 +1,234 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::tree::{Content, Element};
+
+/// Namespace bound to the reserved `xml` prefix. It is never declared.
+pub(crate) const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// XML Schema instance namespace, used for `xsi:nil`.
+pub(crate) const XSI_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema-instance";
+
+/// XML Schema namespace, of the built-in types named by `xsi:type`.
+pub(crate) const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// Split a name written in Clark notation (`{uri}local`) into its namespace URI and local part.
+pub(crate) fn parse_clark(name: &str) -> Option<(&str, &str)> {
+  let rest = name.strip_prefix('{')?;
+  let end = rest.find('}')?;
+  Some((&rest[.. end], &rest[end + 1 ..]))
+}
+
+/// Join a namespace URI and a local name in Clark notation.
+pub(crate) fn to_clark(uri: &str, local: &str) -> String {
+  format!("{{{}}}{}", uri, local)
+}
+
+/// Prefix of a qualified name, `""` when the name is unprefixed.
+pub(crate) fn prefix_of(name: &str) -> &str {
+  name.split_once(':').map_or("", |(prefix, _)| prefix)
+}
+
+/// In-scope namespace declarations as (prefix, uri), innermost last. The default namespace has
+/// the `""` prefix.
+pub(crate) type Scope = Vec<(String, String)>;
+
+/// Scope of `element`, with its own `xmlns` declarations added to the enclosing `scope`.
+pub(crate) fn declare(scope: &[(String, String)], element: &Element) -> Scope {
+  let mut scope = scope.to_vec();
+  for (name, value) in &element.attrs {
+    if name == "xmlns" {
+      scope.push((String::new(), value.clone()));
+    } else if let Some(prefix) = name.strip_prefix("xmlns:") {
+      scope.push((prefix.to_owned(), value.clone()));
+    }
+  }
+  scope
+}
+
+/// Namespace URI and local part of a qualified name in `scope`. Unprefixed attributes, and names
+/// bound to an undeclared or empty namespace, have no namespace.
+pub(crate) fn resolve<'a>(scope: &'a [(String, String)], name: &'a str, attribute: bool) -> (Option<&'a str>, &'a str) {
+  let (prefix, local) = match name.split_once(':') {
+    Some(parts) => parts,
+    None if attribute => return (None, name),
+    None => ("", name)
+  };
+  let uri = scope
+    .iter()
+    .rev()
+    .find(|(p, _)| p == prefix)
+    .map(|(_, u)| u.as_str())
+    .filter(|u| !u.is_empty());
+  (uri, local)
+}
+
+/// Rewrites element and attribute names written in Clark notation into prefixed names and adds
+/// the `xmlns` declarations they need.
+///
+/// Prefixed namespaces are declared once, on the outermost element of the document. Default
+/// namespaces (bound to the `""` prefix) are declared on each element where the default namespace
+/// changes. Names which are not in Clark notation are left untouched, and `xmlns` attributes which
+/// are already present are taken into account rather than repeated.
+pub(crate) struct NamespaceResolver<'a> {
+  // Configured (prefix, uri) bindings
+  bindings: &'a [(String, String)]
+}
+
+impl<'a> NamespaceResolver<'a> {
+  pub fn new(bindings: &'a [(String, String)]) -> NamespaceResolver<'a> {
+    NamespaceResolver { bindings }
+  }
+
+  // Configured prefix for a namespace URI
+  fn configured_prefix(&self, uri: &str) -> Option<&str> {
+    self.bindings.iter().find(|(_, u)| u == uri).map(|(p, _)| p.as_str())
+  }
+
+  // Configured namespace URI for a prefix
+  fn configured_uri(&self, prefix: &str) -> Option<&str> {
+    self.bindings.iter().find(|(p, _)| p == prefix).map(|(_, u)| u.as_str())
+  }
+
+  // Check if an element in `uri` is written without a prefix, in the default namespace
+  fn uses_default(&self, uri: &str) -> bool {
+    self.configured_prefix(uri) == Some("")
+  }
+
+  // Collect namespace URIs which need a prefix declared and every prefix which is already used in
+  // names or declarations. Prefixed names which aren't in Clark notation need a declaration when
+  // their prefix is a configured one
+  fn collect(&self, element: &Element, uris: &mut Vec<String>, prefixes: &mut BTreeSet<String>, declared: &mut Vec<(String, String)>) {
+    let mut add_uri = |uri: &str| {
+      if !uri.is_empty() && uri != XML_NAMESPACE && !uris.iter().any(|u| u == uri) {
+        uris.push(uri.to_owned());
+      }
+    };
+
+    match parse_clark(&element.name) {
+      Some((uri, _)) if !self.uses_default(uri) => add_uri(uri),
+      Some(_) => (),
+      None => {
+        let prefix = prefix_of(&element.name);
+        if let Some(uri) = self.configured_uri(prefix).filter(|_| !prefix.is_empty()) {
+          add_uri(uri);
+        }
+        prefixes.insert(prefix.to_owned());
+      }
+    }
+    for (name, value) in &element.attrs {
+      match parse_clark(name) {
+        Some((uri, _)) => add_uri(uri),
+        None => {
+          if let Some(prefix) = name.strip_prefix("xmlns:") {
+            prefixes.insert(prefix.to_owned());
+            declared.push((prefix.to_owned(), value.to_owned()));
+          } else if name != "xmlns" {
+            let prefix = prefix_of(name);
+            if let Some(uri) = self.configured_uri(prefix).filter(|_| !prefix.is_empty()) {
+              add_uri(uri);
+            }
+            prefixes.insert(prefix.to_owned());
+          }
+        },
+      }
+    }
+
+    for child in &element.children {
+      if let Content::Element(e) = child {
+        self.collect(e, uris, prefixes, declared);
+      }
+    }
+  }
+
+  // Check if `prefix` may be declared for `uri` on the outermost element: every declaration of the
+  // prefix in the document binds it to `uri`, and it isn't configured for another namespace
+  fn unambiguous(&self, prefix: &str, uri: &str, declared: &[(String, String)]) -> bool {
+    declared.iter().all(|(p, u)| p != prefix || u == uri) && self.configured_uri(prefix).map_or(true, |u| u == uri)
+  }
+
+  // Rename `element` and its descendants, given the prefixes assigned to each namespace and the
+  // default namespace in scope
+  fn rename(&self, element: &mut Element, assigned: &HashMap<String, String>, default: &str) {
+    let mut default = element.attr("xmlns").unwrap_or(default).to_owned();
+    let mut declarations = Vec::new();
+
+    if let Some((uri, local)) = parse_clark(&element.name).map(|(u, l)| (u.to_owned(), l.to_owned())) {
+      if uri == XML_NAMESPACE {
+        element.name = format!("xml:{}", local);
+      } else if uri.is_empty() || self.uses_default(&uri) {
+        // Unprefixed, redeclaring the default namespace if it changes here
+        if default != uri {
+          declarations.push(("xmlns".to_owned(), uri.clone()));
+          default = uri;
+        }
+        element.name = local;
+      } else {
+        element.name = format!("{}:{}", assigned[&uri], local);
+      }
+    }
+
+    for (name, _) in element.attrs.iter_mut() {
+      if let Some((uri, local)) = parse_clark(name).map(|(u, l)| (u.to_owned(), l.to_owned())) {
+        *name = if uri.is_empty() {
+          local
+        } else if uri == XML_NAMESPACE {
+          format!("xml:{}", local)
+        } else {
+          format!("{}:{}", assigned[&uri], local)
+        };
+      }
+    }
+    element.attrs.splice(0 .. 0, declarations);
+
+    for child in element.children.iter_mut() {
+      if let Content::Element(e) = child {
+        self.rename(e, assigned, &default);
+      }
+    }
+  }
+
+  /// Resolve the names of a top level element and its descendants
+  pub fn resolve(&self, element: &mut Element) {
+    let mut uris = Vec::new();
+    let mut prefixes = BTreeSet::new();
+    let mut declared = Vec::new();
+    self.collect(element, &mut uris, &mut prefixes, &mut declared);
+    for (prefix, _) in self.bindings {
+      prefixes.insert(prefix.to_owned());
+    }
+
+    // Assign a prefix to every namespace: the configured one, one that's declared in the document
+    // or else a generated `nsN` prefix. A prefix is only reused when no element of the document
+    // binds it to another namespace
+    let mut assigned = HashMap::new();
+    let mut declarations = Vec::new();
+    let mut generated = 0;
+    for uri in uris {
+      let configured = self.configured_prefix(&uri).filter(|p| !p.is_empty());
+      let prefix = match configured.filter(|p| self.unambiguous(p, &uri, &declared)) {
+        Some(prefix) => prefix.to_owned(),
+        None => match declared.iter().find(|(p, u)| *u == uri && self.unambiguous(p, &uri, &declared)) {
+          Some((prefix, _)) => prefix.to_owned(),
+          None => loop {
+            let prefix = format!("ns{}", generated);
+            generated += 1;
+            if !prefixes.contains(&prefix) {
+              break prefix;
+            }
+          }
+        }
+      };
+
+      let attr = format!("xmlns:{}", prefix);
+      if element.attr(&attr) != Some(uri.as_str()) {
+        declarations.push((attr, uri.clone()));
+      }
+      assigned.insert(uri, prefix);
+    }
+
+    self.rename(element, &assigned, "");
+    // Declarations go after the element's own default namespace declaration, if any
+    let position = element.attrs.iter().take_while(|(k, _)| k == "xmlns").count();
+    element.attrs.splice(position .. position, declarations);
+  }
+}
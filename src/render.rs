@@ -0,0 +1,244 @@
+use std::io::{self, Write};
+
+use quick_xml::escape::escape;
+
+use crate::{
+  tree::{Content, Element},
+  xml::{AttrWrap, Declaration, Indentation}
+};
+
+// Escape character data. Carriage returns are written as references since a parser would turn
+// them into line feeds
+fn escape_text(text: &str) -> Vec<u8> {
+  let escaped = escape(text.as_bytes());
+  if escaped.contains(&b'\r') {
+    String::from_utf8_lossy(&escaped).replace('\r', "&#xD;").into_bytes()
+  } else {
+    escaped.into_owned()
+  }
+}
+
+// Escape an attribute value. Tabs and line breaks are written as references since a parser would
+// turn them into spaces
+fn escape_attr(value: &str) -> Vec<u8> {
+  let escaped = escape(value.as_bytes());
+  if escaped.iter().any(|b| matches!(b, b'\t' | b'\n' | b'\r')) {
+    String::from_utf8_lossy(&escaped)
+      .replace('\t', "&#x9;")
+      .replace('\n', "&#xA;")
+      .replace('\r', "&#xD;")
+      .into_bytes()
+  } else {
+    escaped.into_owned()
+  }
+}
+
+/// Pretty-printer for a tree of [`Element`]s.
+///
+/// Without `Indentation` the document is written compactly. With it, element-only content is
+/// broken into indented lines, while the content of mixed content elements, and of elements with
+/// `xml:space="preserve"`, is written exactly as it is so that no whitespace is added to
+/// significant text.
+#[derive(Debug)]
+pub(crate) struct Renderer {
+  indentation: Option<Indentation>
+}
+
+impl Renderer {
+  pub fn new(indentation: Option<Indentation>) -> Renderer {
+    Renderer { indentation }
+  }
+
+  pub fn indentation(&self) -> Option<&Indentation> {
+    self.indentation.as_ref()
+  }
+
+  // Write a newline and indentation for `depth`, if rendering is enabled
+  fn write_line<W: Write>(&self, writer: &mut W, depth: usize) -> io::Result<()> {
+    if let Some(ref i) = self.indentation {
+      writer.write_all(i.newline.to_string().as_bytes())?;
+      writer.write_all(&vec![i.indent_char; i.indent_size * depth])?;
+    }
+    Ok(())
+  }
+
+  // Check if the attributes of a start tag should be written on separate lines
+  fn wrap_attrs(&self, element: &Element, depth: usize) -> bool {
+    let indentation = match self.indentation {
+      Some(ref i) => i,
+      None => return false
+    };
+    match indentation.attr_wrap {
+      AttrWrap::Never => false,
+      AttrWrap::Count(count) => element.attrs.len() > count,
+      AttrWrap::Width(width) => {
+        // <name a="b" c="d">
+        let attrs_width: usize = element.attrs.iter().map(|(k, v)| k.len() + escape_attr(v).len() + 4).sum();
+        indentation.indent_size * depth + element.name.len() + attrs_width + 2 > width
+      }
+    }
+  }
+
+  // Check if the children of `element` may be re-indented. `format` is the inherited setting
+  fn format_children(&self, element: &Element, format: bool) -> bool {
+    let format = match element.attr("xml:space") {
+      Some("preserve") => false,
+      Some("default") => self.indentation.is_some(),
+      _ => format
+    };
+    format && !element.is_mixed()
+  }
+
+  fn write_text<W: Write>(&self, writer: &mut W, text: &str) -> io::Result<()> {
+    writer.write_all(&escape_text(text))
+  }
+
+  // A CDATA section can't contain `]]>`, so it is split across two sections
+  fn write_cdata<W: Write>(&self, writer: &mut W, text: &str) -> io::Result<()> {
+    writer.write_all(b"<![CDATA[")?;
+    writer.write_all(text.replace("]]>", "]]]]><![CDATA[>").as_bytes())?;
+    writer.write_all(b"]]>")
+  }
+
+  fn write_comment<W: Write>(&self, writer: &mut W, comment: &str) -> io::Result<()> {
+    writer.write_all(b"<!--")?;
+    writer.write_all(comment.as_bytes())?;
+    writer.write_all(b"-->")
+  }
+
+  fn write_instruction<W: Write>(&self, writer: &mut W, instruction: &str) -> io::Result<()> {
+    writer.write_all(b"<?")?;
+    writer.write_all(instruction.as_bytes())?;
+    writer.write_all(b"?>")
+  }
+
+  fn write_doctype<W: Write>(&self, writer: &mut W, doctype: &str) -> io::Result<()> {
+    writer.write_all(b"<!DOCTYPE ")?;
+    writer.write_all(doctype.as_bytes())?;
+    writer.write_all(b">")
+  }
+
+  // Write a single node of content
+  fn write_content<W: Write>(&self, writer: &mut W, content: &Content, depth: usize, format: bool) -> io::Result<()> {
+    match content {
+      Content::Element(e) => self.write_element(writer, e, depth, format),
+      Content::Text(t) => self.write_text(writer, t),
+      Content::CData(t) => self.write_cdata(writer, t),
+      Content::Comment(c) => self.write_comment(writer, c),
+      Content::Instruction(i) => self.write_instruction(writer, i),
+      Content::DocType(d) => self.write_doctype(writer, d),
+      Content::Declaration(_) => Ok(())
+    }
+  }
+
+  // Write an element and its content at `depth`. When `format` is unset, nothing but the element's
+  // own markup and content is written
+  fn write_element<W: Write>(&self, writer: &mut W, element: &Element, depth: usize, format: bool) -> io::Result<()> {
+    let wrap = format && self.wrap_attrs(element, depth);
+
+    writer.write_all(b"<")?;
+    writer.write_all(element.name.as_bytes())?;
+    for (key, value) in &element.attrs {
+      if wrap {
+        self.write_line(writer, depth + 1)?;
+      } else {
+        writer.write_all(b" ")?;
+      }
+      writer.write_all(key.as_bytes())?;
+      writer.write_all(b"=\"")?;
+      writer.write_all(&escape_attr(value))?;
+      writer.write_all(b"\"")?;
+    }
+
+    if element.is_empty() {
+      if self.indentation.as_ref().map_or(false, |i| i.space_before_slash) {
+        writer.write_all(b" ")?;
+      }
+      writer.write_all(b"/>")?;
+      return Ok(());
+    }
+    writer.write_all(b">")?;
+
+    let format_children = self.format_children(element, format);
+    let inline_text = self.indentation.as_ref().map_or(true, |i| i.inline_text);
+    if !format_children || (!element.has_elements() && inline_text) {
+      // Mixed or preserved content, or text which stays on the same line as its tags
+      for child in &element.children {
+        self.write_content(writer, child, depth + 1, false)?;
+      }
+    } else if element.has_elements() {
+      // Element-only content, one child per line
+      for child in &element.children {
+        self.write_line(writer, depth + 1)?;
+        self.write_content(writer, child, depth + 1, true)?;
+      }
+      self.write_line(writer, depth)?;
+    } else {
+      // Text-only content on its own line
+      self.write_line(writer, depth + 1)?;
+      for child in &element.children {
+        self.write_content(writer, child, depth + 1, false)?;
+      }
+      self.write_line(writer, depth)?;
+    }
+
+    writer.write_all(b"</")?;
+    writer.write_all(element.name.as_bytes())?;
+    writer.write_all(b">")
+  }
+
+  /// Write a document made of an XML declaration followed by `nodes` into `writer`. A first
+  /// declaration node is written instead of `decl`, and without either no declaration is written
+  pub fn write<W: Write>(&self, writer: &mut W, decl: Option<&Declaration>, nodes: &[Content]) -> io::Result<()> {
+    let mut written = true;
+    let nodes = match nodes.split_first() {
+      Some((Content::Declaration(attrs), rest)) => {
+        writer.write_all(b"<?xml")?;
+        for (key, value) in attrs {
+          writer.write_all(b" ")?;
+          writer.write_all(key.as_bytes())?;
+          writer.write_all(b"=\"")?;
+          writer.write_all(&escape_attr(value))?;
+          writer.write_all(b"\"")?;
+        }
+        writer.write_all(b"?>")?;
+        rest
+      },
+      _ => {
+        match decl {
+          Some(decl) => {
+            writer.write_all(b"<?")?;
+            writer.write_all(&decl.as_bytes_decl())?;
+            writer.write_all(b"?>")?;
+          },
+          None => written = false
+        }
+        nodes
+      }
+    };
+
+    for node in nodes {
+      if node.is_char_data() {
+        self.write_content(writer, node, 0, false)?;
+      } else {
+        if written {
+          self.write_line(writer, 0)?;
+        }
+        self.write_content(writer, node, 0, self.indentation.is_some())?;
+      }
+      // An empty text node or a misplaced declaration writes nothing
+      written |= match node {
+        Content::Text(t) => !t.is_empty(),
+        Content::Declaration(_) => false,
+        _ => true
+      };
+    }
+
+    if let Some(ref i) = self.indentation {
+      if i.final_newline {
+        writer.write_all(i.newline.to_string().as_bytes())?;
+      }
+    }
+    Ok(())
+  }
+}
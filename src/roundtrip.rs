@@ -0,0 +1,302 @@
+use std::{error::Error as StdError, fmt};
+
+use crate::{
+  json::JsonConfig,
+  tree::{self, Content, Element},
+  xml::XmlConfig
+};
+
+/// First semantic difference between an XML document and the document written back from its JSON.
+///
+/// The path locates the difference with XPath steps, such as `/a[1]/b[2]/@id` or
+/// `/a[1]/text()[1]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diff {
+  path:        String,
+  description: String
+}
+
+impl Diff {
+  fn new<P: Into<String>, D: Into<String>>(path: P, description: D) -> Diff {
+    Diff {
+      path:        path.into(),
+      description: description.into()
+    }
+  }
+
+  /// Path to the node or attribute which differs
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+
+  /// Description of the difference
+  pub fn description(&self) -> &str {
+    &self.description
+  }
+}
+
+impl fmt::Display for Diff {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}: {}", self.path, self.description)
+  }
+}
+
+impl StdError for Diff {}
+
+// XPath node test and the kind of node it names, used in descriptions
+fn node_test(content: &Content) -> (String, &'static str) {
+  match content {
+    Content::Element(e) => (e.name.clone(), "element"),
+    Content::Text(_) | Content::CData(_) => ("text()".to_owned(), "text"),
+    Content::Comment(_) => ("comment()".to_owned(), "comment"),
+    Content::Instruction(_) => ("processing-instruction()".to_owned(), "processing instruction"),
+    Content::Declaration(_) => ("/".to_owned(), "declaration"),
+    Content::DocType(_) => ("/".to_owned(), "doctype")
+  }
+}
+
+// Path steps for each of `children`, numbered among the siblings with the same node test. The
+// declaration and doctype are located at the document
+fn steps(path: &str, children: &[Content]) -> Vec<String> {
+  let mut tests: Vec<String> = Vec::new();
+  children
+    .iter()
+    .map(|child| {
+      let (test, _) = node_test(child);
+      if test == "/" {
+        return test;
+      }
+      let position = tests.iter().filter(|t| **t == test).count() + 1;
+      tests.push(test.clone());
+      format!("{}/{}[{}]", path, test, position)
+    })
+    .collect()
+}
+
+fn compare_elements(path: &str, expected: &Element, actual: &Element) -> Result<(), Diff> {
+  if expected.name != actual.name {
+    return Err(Diff::new(
+      path,
+      format!("expected element {:?}, found {:?}", expected.name, actual.name)
+    ));
+  }
+
+  for (name, value) in &expected.attrs {
+    let attr_path = format!("{}/@{}", path, name);
+    match actual.attr(name) {
+      None => return Err(Diff::new(attr_path, "missing attribute")),
+      Some(v) if v != value => {
+        return Err(Diff::new(attr_path, format!("expected value {:?}, found {:?}", value, v)));
+      },
+      _ => ()
+    }
+  }
+  if let Some((name, _)) = actual.attrs.iter().find(|(name, _)| expected.attr(name).is_none()) {
+    return Err(Diff::new(format!("{}/@{}", path, name), "unexpected attribute"));
+  }
+
+  compare_children(path, &expected.children, &actual.children)
+}
+
+// Compare the children of an element, or the top level nodes of a document with an empty `path`
+fn compare_children(path: &str, expected: &[Content], actual: &[Content]) -> Result<(), Diff> {
+  let expected_steps = steps(path, expected);
+  let actual_steps = steps(path, actual);
+  for (i, (e, a)) in expected.iter().zip(actual).enumerate() {
+    compare_content(&expected_steps[i], e, a)?;
+  }
+  if let Some(step) = expected_steps.get(actual.len()) {
+    let (_, kind) = node_test(&expected[actual.len()]);
+    return Err(Diff::new(step.as_str(), format!("missing {}", kind)));
+  }
+  if let Some(step) = actual_steps.get(expected.len()) {
+    let (_, kind) = node_test(&actual[expected.len()]);
+    return Err(Diff::new(step.as_str(), format!("unexpected {}", kind)));
+  }
+  Ok(())
+}
+
+fn compare_content(path: &str, expected: &Content, actual: &Content) -> Result<(), Diff> {
+  match (expected, actual) {
+    (Content::Element(e), Content::Element(a)) => compare_elements(path, e, a),
+    (Content::Text(e), Content::Text(a))
+    | (Content::Comment(e), Content::Comment(a))
+    | (Content::Instruction(e), Content::Instruction(a))
+    | (Content::DocType(e), Content::DocType(a)) => {
+      if e == a {
+        Ok(())
+      } else {
+        Err(Diff::new(path, format!("expected {:?}, found {:?}", e, a)))
+      }
+    },
+    (Content::Declaration(e), Content::Declaration(a)) => {
+      if e == a {
+        Ok(())
+      } else {
+        Err(Diff::new(path, format!("expected declaration {:?}, found {:?}", e, a)))
+      }
+    },
+    _ => Err(Diff::new(
+      path,
+      format!("expected {}, found {}", node_test(expected).1, node_test(actual).1)
+    ))
+  }
+}
+
+// Parse a document, keeping the prolog. CDATA sections are merged into the surrounding text
+fn parse(xml: &str) -> Result<Vec<Content>, crate::error::Error> {
+  Ok(merge_cdata(tree::parse_document(xml)?))
+}
+
+fn merge_cdata(nodes: Vec<Content>) -> Vec<Content> {
+  let mut merged = Vec::with_capacity(nodes.len());
+  for node in nodes {
+    match node {
+      Content::Text(t) | Content::CData(t) => tree::push_text(&mut merged, &t),
+      Content::Element(mut e) => {
+        e.children = merge_cdata(e.children);
+        merged.push(Content::Element(e));
+      },
+      node => merged.push(node)
+    }
+  }
+  merged
+}
+
+/// Convert `xml` to JSON with [JsonConfig::lossless] and back with [XmlConfig::lossless], and check
+/// that the document is unchanged: the declaration, the doctype, the document element, and the
+/// comments and processing instructions around it.
+///
+/// Documents are compared after parsing: attribute order, the quotes around attribute values,
+/// character references, CDATA sections and whitespace outside of the document element are not
+/// significant.
+///
+/// ```rust
+/// use xml2json_rs::verify_roundtrip;
+///
+/// assert!(verify_roundtrip(r#"<a x="1"> <b>text</b><!--c--><![CDATA[d]]></a>"#).is_ok());
+/// assert!(
+///   verify_roundtrip(r#"<?xml version="1.0"?><!DOCTYPE a><a><?pi data?></a><!--e-->"#).is_ok()
+/// );
+/// ```
+///
+/// [JsonConfig::lossless]: crate::JsonConfig::lossless
+/// [XmlConfig::lossless]: crate::XmlConfig::lossless
+pub fn verify_roundtrip(xml: &str) -> Result<(), Diff> {
+  let expected = parse(xml).map_err(|e| Diff::new("/", format!("invalid input: {}", e)))?;
+  let json = JsonConfig::lossless()
+    .finalize()
+    .build_from_xml(xml)
+    .map_err(|e| Diff::new("/", format!("cannot build JSON: {}", e)))?;
+  let output = XmlConfig::lossless()
+    .finalize()
+    .build_from_json(&json)
+    .map_err(|e| Diff::new("/", format!("cannot build XML: {}", e)))?;
+  let actual = parse(&output).map_err(|e| Diff::new("/", format!("invalid output: {}", e)))?;
+  compare_children("", &expected, &actual)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn lossless_documents() {
+    let documents = [
+      "<a/>",
+      "<?xml version=\"1.0\"?>\n<!-- prolog -->\n<a>\n  <b x=\"1\" y='2'>text</b>\n  <c/>\n</a>\n",
+      "<a>mixed <b>content</b> with <c>order</c> kept<b>again</b></a>",
+      "<a><![CDATA[<raw> & ]]]]><![CDATA[>]]> and &lt;escaped&gt;</a>",
+      "<a>  <!-- comment -->  </a>",
+      "<a x=\"line&#xA;break\" y=\"tab&#x9;\" z=\"literal\nnewline\">cr&#xD;\r\nlf</a>",
+      "<a><_>b</_><$$>c</$$></a>",
+      "<p:a xmlns:p=\"urn:p\" xmlns=\"urn:d\"><b p:x=\"1\"/></p:a>",
+      "<?xml version=\"1.1\" encoding=\"UTF-8\" standalone=\"yes\"?><!DOCTYPE a SYSTEM \"a.dtd\"><a/>",
+      "<?xml-stylesheet href=\"a.xsl\"?><a><?pi data?></a><!-- epilog --><?end?>"
+    ];
+    for xml in documents.iter() {
+      assert_eq!(verify_roundtrip(xml), Ok(()), "{}", xml);
+    }
+  }
+
+  #[test]
+  fn invalid_input() {
+    let diff = verify_roundtrip("<a><b></a>").unwrap_err();
+    assert_eq!(diff.path(), "/");
+    assert!(diff.description().starts_with("invalid input"));
+  }
+
+  #[test]
+  fn element_differences() {
+    let document_element = |xml| match parse(xml).unwrap().remove(0) {
+      Content::Element(e) => e,
+      _ => unreachable!()
+    };
+    let expected = document_element("<a><b/><c x=\"1\">t</c><c/></a>");
+
+    let cases = [
+      ("<z/>", "/a[1]", "expected element \"a\", found \"z\""),
+      (
+        "<a><b/><c x=\"2\">t</c><c/></a>",
+        "/a[1]/c[1]/@x",
+        "expected value \"1\", found \"2\""
+      ),
+      ("<a><b/><c>t</c><c/></a>", "/a[1]/c[1]/@x", "missing attribute"),
+      ("<a><b y=\"\"/><c x=\"1\">t</c><c/></a>", "/a[1]/b[1]/@y", "unexpected attribute"),
+      (
+        "<a><b/><c x=\"1\">u</c><c/></a>",
+        "/a[1]/c[1]/text()[1]",
+        "expected \"t\", found \"u\""
+      ),
+      ("<a><b/><c x=\"1\">t</c></a>", "/a[1]/c[2]", "missing element"),
+      (
+        "<a><b/><c x=\"1\">t</c><c/><!--d--></a>",
+        "/a[1]/comment()[1]",
+        "unexpected comment"
+      ),
+      (
+        "<a><b/><c x=\"1\"><t/></c><c/></a>",
+        "/a[1]/c[1]/text()[1]",
+        "expected text, found element"
+      )
+    ];
+    for (xml, path, description) in cases.iter() {
+      let actual = document_element(xml);
+      let diff = compare_elements("/a[1]", &expected, &actual).unwrap_err();
+      assert_eq!((diff.path(), diff.description()), (*path, *description), "{}", xml);
+    }
+  }
+
+  #[test]
+  fn prolog_differences() {
+    let expected = parse("<?xml version=\"1.0\"?><!DOCTYPE a><!--b--><a/><?c?>").unwrap();
+
+    let cases = [
+      ("<!DOCTYPE a><!--b--><a/><?c?>", "/", "expected declaration, found doctype"),
+      (
+        "<?xml version=\"1.1\"?><!DOCTYPE a><!--b--><a/><?c?>",
+        "/",
+        "expected declaration [(\"version\", \"1.0\")], found [(\"version\", \"1.1\")]"
+      ),
+      (
+        "<?xml version=\"1.0\"?><!DOCTYPE b><!--b--><a/><?c?>",
+        "/",
+        "expected \"a\", found \"b\""
+      ),
+      (
+        "<?xml version=\"1.0\"?><!DOCTYPE a><!--b--><a/>",
+        "/processing-instruction()[1]",
+        "missing processing instruction"
+      ),
+      (
+        "<?xml version=\"1.0\"?><!DOCTYPE a><!--b--><a/><?c?><!--d-->",
+        "/comment()[2]",
+        "unexpected comment"
+      )
+    ];
+    for (xml, path, description) in cases.iter() {
+      let diff = compare_children("", &expected, &parse(xml).unwrap()).unwrap_err();
+      assert_eq!((diff.path(), diff.description()), (*path, *description), "{}", xml);
+    }
+  }
+}
@@ -0,0 +1,899 @@
+use std::io;
+
+use serde::ser::{self, Serialize, SerializeMap as _, Serializer as _};
+use serde_json::{value::Serializer as ValueSerializer, Map, Value as JsonValue};
+
+use crate::{
+  convention::{TagAttrs, XmlOptions},
+  error::{Error, ErrorKind},
+  tree::{Content, Element},
+  utils,
+  xml::{XmlBuilder, XmlConfig}
+};
+
+// What a serialized value builds, after the xml2js functions of `XmlOptions`
+#[derive(Clone, Copy)]
+enum Role<'k> {
+  // The top level nodes of the document (`XmlOptions::document`)
+  Document,
+  // Elements named by the key, one per item of a sequence (`XmlOptions::elements`)
+  Elements(&'k str),
+  // A single element named by the key (`XmlOptions::push_element`)
+  Element(&'k str),
+  // Character data held under `charkey` (`XmlOptions::char_data`)
+  Text
+}
+
+// Serializer of the node at JSON pointer `path`, adding what it builds to `out`
+struct NodeSerializer<'a> {
+  builder: &'a XmlOptions,
+  path:    String,
+  role:    Role<'a>,
+  out:     &'a mut Vec<Content>
+}
+
+impl<'a> NodeSerializer<'a> {
+  // Build a JSON value as `XmlOptions` does. Used for scalars and for the few values whose output
+  // depends on more than the value at hand, which are collected into JSON first
+  fn node(self, value: &JsonValue) -> Result<(), Error> {
+    let NodeSerializer { builder, path, role, out } = self;
+    match role {
+      Role::Document => out.extend(builder.document(value)?),
+      Role::Elements(key) => builder.elements(&path, key, value, out)?,
+      Role::Element(key) => builder.push_element(&path, key, value, out)?,
+      Role::Text => builder.char_data(&path, value, out)?
+    }
+    Ok(())
+  }
+
+  // Collect the value into JSON with `state`, then build it
+  fn buffer<S>(self, state: S) -> Buffer<'a, S> {
+    Buffer { node: self, state }
+  }
+
+  // Build an object, as an element named `key` or as content when `key` is `None`
+  fn object(self, key: Option<&'a str>) -> MapSerializer<'a> {
+    MapSerializer::Object(ObjectSerializer {
+      builder: self.builder,
+      path:    self.path,
+      out:     self.out,
+      element: key.map(|key| PendingElement {
+        key,
+        ns: None,
+        attrs: Vec::new(),
+        merged: Vec::new(),
+        children: Vec::new()
+      }),
+      key:     None
+    })
+  }
+}
+
+impl<'a> ser::Serializer for NodeSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+  type SerializeMap = MapSerializer<'a>;
+  type SerializeSeq = SeqSerializer<'a>;
+  type SerializeStruct = MapSerializer<'a>;
+  type SerializeStructVariant = Buffer<'a, <ValueSerializer as ser::Serializer>::SerializeStructVariant>;
+  type SerializeTuple = SeqSerializer<'a>;
+  type SerializeTupleStruct = SeqSerializer<'a>;
+  type SerializeTupleVariant = Buffer<'a, <ValueSerializer as ser::Serializer>::SerializeTupleVariant>;
+
+  fn serialize_bool(self, v: bool) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_i8(self, v: i8) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_i16(self, v: i16) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_i32(self, v: i32) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_i64(self, v: i64) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_i128(self, v: i128) -> Result<(), Error> {
+    self.node(&serde_json::to_value(v)?)
+  }
+
+  fn serialize_u8(self, v: u8) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_u16(self, v: u16) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_u32(self, v: u32) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_u64(self, v: u64) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_u128(self, v: u128) -> Result<(), Error> {
+    self.node(&serde_json::to_value(v)?)
+  }
+
+  fn serialize_f32(self, v: f32) -> Result<(), Error> {
+    self.node(&serde_json::to_value(v)?)
+  }
+
+  fn serialize_f64(self, v: f64) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_char(self, v: char) -> Result<(), Error> {
+    self.node(&v.to_string().into())
+  }
+
+  fn serialize_str(self, v: &str) -> Result<(), Error> {
+    self.node(&v.into())
+  }
+
+  fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+    self.collect_seq(v)
+  }
+
+  fn serialize_none(self) -> Result<(), Error> {
+    self.node(&JsonValue::Null)
+  }
+
+  fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<(), Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_unit(self) -> Result<(), Error> {
+    self.node(&JsonValue::Null)
+  }
+
+  fn serialize_unit_struct(self, _name: &'static str) -> Result<(), Error> {
+    self.node(&JsonValue::Null)
+  }
+
+  fn serialize_unit_variant(self, _name: &'static str, _index: u32, variant: &'static str) -> Result<(), Error> {
+    self.node(&variant.into())
+  }
+
+  fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _name: &'static str, value: &T) -> Result<(), Error> {
+    value.serialize(self)
+  }
+
+  fn serialize_newtype_variant<T: ?Sized + Serialize>(
+    self,
+    _name: &'static str,
+    _index: u32,
+    variant: &'static str,
+    value: &T
+  ) -> Result<(), Error> {
+    let mut map = self.serialize_map(Some(1))?;
+    map.serialize_entry(variant, value)?;
+    map.end()
+  }
+
+  fn serialize_seq(self, len: Option<usize>) -> Result<SeqSerializer<'a>, Error> {
+    let builder = self.builder;
+    let (item, element) = match self.role {
+      Role::Document if builder.root_name == "root" => (Role::Element(&builder.root_name), None),
+      Role::Document => match builder.values.array_item {
+        Some(ref item) => (Role::Element(item), Some(builder.xml_name(builder.root_name.to_owned(), "")?)),
+        None => {
+          return Ok(SeqSerializer::Buffer(self.buffer(ValueSerializer.serialize_seq(len)?)));
+        }
+      },
+      Role::Elements(key) => (Role::Element(key), None),
+      // A nested array, which has one item element per entry or else takes the attributes of a
+      // single item
+      Role::Element(key) => match builder.values.array_item {
+        Some(ref item) => (Role::Element(item), Some(builder.xml_name(key.to_owned(), &self.path)?)),
+        None => {
+          return Ok(SeqSerializer::Buffer(self.buffer(ValueSerializer.serialize_seq(len)?)));
+        }
+      },
+      Role::Text => (Role::Text, None)
+    };
+    Ok(SeqSerializer::Items {
+      builder,
+      path: self.path,
+      item,
+      index: 0,
+      element: element.map(Element::new),
+      out: self.out
+    })
+  }
+
+  fn serialize_tuple(self, len: usize) -> Result<SeqSerializer<'a>, Error> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer<'a>, Error> {
+    self.serialize_seq(Some(len))
+  }
+
+  fn serialize_tuple_variant(
+    self,
+    name: &'static str,
+    index: u32,
+    variant: &'static str,
+    len: usize
+  ) -> Result<Self::SerializeTupleVariant, Error> {
+    let state = ValueSerializer.serialize_tuple_variant(name, index, variant, len)?;
+    Ok(self.buffer(state))
+  }
+
+  fn serialize_map(self, len: Option<usize>) -> Result<MapSerializer<'a>, Error> {
+    let builder = self.builder;
+    match self.role {
+      // As with `XmlOptions::document`, an object with a single key under the root name "root" is
+      // the document element itself
+      Role::Document => match len {
+        Some(len) if builder.root_name != "root" || len > 1 => Ok(self.object(Some(&builder.root_name))),
+        Some(_) => Ok(self.object(None)),
+        None => Ok(MapSerializer::Buffer(self.buffer(ValueSerializer.serialize_map(None)?)))
+      },
+      Role::Elements(key) | Role::Element(key) => Ok(self.object(Some(key))),
+      // Objects are not character data
+      Role::Text => {
+        builder.char_data(&self.path, &JsonValue::Object(Map::new()), self.out)?;
+        Ok(MapSerializer::Ignore)
+      }
+    }
+  }
+
+  fn serialize_struct(self, _name: &'static str, len: usize) -> Result<MapSerializer<'a>, Error> {
+    self.serialize_map(Some(len))
+  }
+
+  fn serialize_struct_variant(
+    self,
+    name: &'static str,
+    index: u32,
+    variant: &'static str,
+    len: usize
+  ) -> Result<Self::SerializeStructVariant, Error> {
+    let state = ValueSerializer.serialize_struct_variant(name, index, variant, len)?;
+    Ok(self.buffer(state))
+  }
+}
+
+// A value collected into JSON before it is built
+struct Buffer<'a, S> {
+  node:  NodeSerializer<'a>,
+  state: S
+}
+
+impl<'a, S> ser::SerializeSeq for Buffer<'a, S>
+where
+  S: ser::SerializeSeq<Ok = JsonValue, Error = serde_json::Error>
+{
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    Ok(self.state.serialize_element(value)?)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    self.node.node(&self.state.end()?)
+  }
+}
+
+impl<'a, S> ser::SerializeMap for Buffer<'a, S>
+where
+  S: ser::SerializeMap<Ok = JsonValue, Error = serde_json::Error>
+{
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+    Ok(self.state.serialize_key(key)?)
+  }
+
+  fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    Ok(self.state.serialize_value(value)?)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    self.node.node(&self.state.end()?)
+  }
+}
+
+impl<'a, S> ser::SerializeTupleVariant for Buffer<'a, S>
+where
+  S: ser::SerializeTupleVariant<Ok = JsonValue, Error = serde_json::Error>
+{
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    Ok(self.state.serialize_field(value)?)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    self.node.node(&self.state.end()?)
+  }
+}
+
+impl<'a, S> ser::SerializeStructVariant for Buffer<'a, S>
+where
+  S: ser::SerializeStructVariant<Ok = JsonValue, Error = serde_json::Error>
+{
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+    Ok(self.state.serialize_field(key, value)?)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    self.node.node(&self.state.end()?)
+  }
+}
+
+// Serializer of sequences
+enum SeqSerializer<'a> {
+  Items {
+    builder: &'a XmlOptions,
+    path:    String,
+    // What each item builds
+    item:    Role<'a>,
+    index:   usize,
+    // Element of a nested array, holding the item elements
+    element: Option<Element>,
+    out:     &'a mut Vec<Content>
+  },
+  Buffer(Buffer<'a, <ValueSerializer as ser::Serializer>::SerializeSeq>)
+}
+
+impl<'a> ser::SerializeSeq for SeqSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    match self {
+      SeqSerializer::Items {
+        builder,
+        path,
+        item,
+        index,
+        element,
+        out
+      } => {
+        // Items of character data report errors at `charkey`
+        let path = match item {
+          Role::Text => path.clone(),
+          _ => utils::json_pointer(path, &index.to_string())
+        };
+        *index += 1;
+        let out = match element {
+          Some(element) => &mut element.children,
+          None => &mut **out
+        };
+        value.serialize(NodeSerializer {
+          builder,
+          path,
+          role: *item,
+          out
+        })
+      },
+      SeqSerializer::Buffer(buffer) => buffer.serialize_element(value)
+    }
+  }
+
+  fn end(self) -> Result<(), Error> {
+    match self {
+      SeqSerializer::Items { element, out, .. } => {
+        out.extend(element.map(Content::Element));
+        Ok(())
+      },
+      SeqSerializer::Buffer(buffer) => buffer.end()
+    }
+  }
+}
+
+impl<'a> ser::SerializeTuple for SeqSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    ser::SerializeSeq::serialize_element(self, value)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    ser::SerializeSeq::end(self)
+  }
+}
+
+impl<'a> ser::SerializeTupleStruct for SeqSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    ser::SerializeSeq::serialize_element(self, value)
+  }
+
+  fn end(self) -> Result<(), Error> {
+    ser::SerializeSeq::end(self)
+  }
+}
+
+// An element whose start tag is known once all of its keys are serialized
+struct PendingElement<'a> {
+  key:      &'a str,
+  ns:       Option<JsonValue>,
+  attrs:    TagAttrs,
+  merged:   TagAttrs,
+  children: Vec<Content>
+}
+
+// Serializer of the keys of an object
+struct ObjectSerializer<'a> {
+  builder: &'a XmlOptions,
+  path:    String,
+  out:     &'a mut Vec<Content>,
+  element: Option<PendingElement<'a>>,
+  // Key of the value serialized next
+  key:     Option<String>
+}
+
+impl<'a> ObjectSerializer<'a> {
+  // Serialize the value of `key`, as `XmlOptions::content` and `XmlOptions::tag_attributes` read it
+  fn entry<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+    let builder = self.builder;
+    let path = utils::json_pointer(&self.path, key);
+    let out = match self.element {
+      Some(ref mut element) => &mut element.children,
+      None => &mut *self.out
+    };
+    if builder.is_charkey(key) {
+      value.serialize(NodeSerializer {
+        builder,
+        path,
+        role: Role::Text,
+        out
+      })
+    } else if key == builder.childkey {
+      builder.children(&path, &serde_json::to_value(value)?, out)
+    } else if builder.is_element_key(key) {
+      let role = Role::Elements(builder.element_key(key));
+      value.serialize(NodeSerializer { builder, path, role, out })
+    } else if let Some(ref mut element) = self.element {
+      // Attributes and namespaces are small, and read as JSON
+      let value = serde_json::to_value(value)?;
+      if builder.is_attrkey(key) {
+        builder.attrkey_attributes(&self.path, &value, &mut element.attrs)?;
+      } else if key == builder.nskey {
+        element.ns = Some(value.clone());
+      }
+      builder.merged_attribute(&self.path, key, &value, &mut element.merged)
+    } else {
+      Ok(())
+    }
+  }
+
+  fn end(self) -> Result<(), Error> {
+    if let Some(pending) = self.element {
+      let name = self.builder.namespaced_name(pending.key, pending.ns.as_ref());
+      let mut element = Element::new(self.builder.xml_name(name, &self.path)?);
+      element.attrs = pending.attrs;
+      element.attrs.extend(pending.merged);
+      element.children = pending.children;
+      self.out.push(Content::Element(element));
+    }
+    Ok(())
+  }
+}
+
+// Serializer of maps and structs
+enum MapSerializer<'a> {
+  Object(ObjectSerializer<'a>),
+  Buffer(Buffer<'a, <ValueSerializer as ser::Serializer>::SerializeMap>),
+  // An object in character data, which adds nothing
+  Ignore
+}
+
+// Key of a map entry, turned into a string the way serde_json does
+fn map_key<T: ?Sized + Serialize>(key: &T) -> Result<String, Error> {
+  let mut map = ValueSerializer.serialize_map(Some(1))?;
+  map.serialize_entry(key, &())?;
+  match map.end()? {
+    JsonValue::Object(object) => Ok(object.into_iter().next().map(|(key, _)| key).unwrap_or_default()),
+    _ => Err(Error::new(ErrorKind::Unknown, "Expected a map."))
+  }
+}
+
+impl<'a> ser::SerializeMap for MapSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), Error> {
+    match self {
+      MapSerializer::Object(object) => object.key = Some(map_key(key)?),
+      MapSerializer::Buffer(buffer) => buffer.serialize_key(key)?,
+      MapSerializer::Ignore => ()
+    }
+    Ok(())
+  }
+
+  fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), Error> {
+    match self {
+      MapSerializer::Object(object) => {
+        let key = object.key.take().unwrap_or_default();
+        object.entry(&key, value)
+      },
+      MapSerializer::Buffer(buffer) => buffer.serialize_value(value),
+      MapSerializer::Ignore => Ok(())
+    }
+  }
+
+  fn end(self) -> Result<(), Error> {
+    match self {
+      MapSerializer::Object(object) => object.end(),
+      MapSerializer::Buffer(buffer) => buffer.end(),
+      MapSerializer::Ignore => Ok(())
+    }
+  }
+}
+
+impl<'a> ser::SerializeStruct for MapSerializer<'a> {
+  type Error = Error;
+  type Ok = ();
+
+  fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), Error> {
+    match self {
+      MapSerializer::Object(object) => object.entry(key, value),
+      _ => self.serialize_entry(key, value)
+    }
+  }
+
+  fn end(self) -> Result<(), Error> {
+    ser::SerializeMap::end(self)
+  }
+}
+
+/// Serialize a value as XML, with the node-xml2js mapping of an [`XmlConfig`].
+///
+/// The XML is the one [`XmlBuilder::build_from_json`] builds from the value's
+/// `serde_json::to_value`, without building that JSON value first. Some values are still converted
+/// to JSON on the way:
+///
+/// - attribute objects under `attrkey`, the `xmlns` value under `nskey` and the members read as
+///   merged attributes with `attr_prefix` or `attr_hint`
+/// - child lists under `childkey` (`$$`)
+/// - tuple and struct enum variants
+/// - `i128`, `u128` and `f32` numbers
+/// - maps of unknown length and, without `array_item`, sequences at the document level unless the
+///   root name is `root`, and nested sequences
+/// - any value of a mapping convention, such as XML-RPC or SOAP
+///
+/// ```rust
+/// use serde::Serialize;
+/// use xml2json_rs::{to_string, XmlConfig};
+///
+/// #[derive(Serialize)]
+/// struct Book {
+///   #[serde(rename = "$")]
+///   attrs:   Attrs,
+///   title:   String,
+///   authors: Vec<String>,
+///   year:    u32
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Attrs {
+///   category: String
+/// }
+///
+/// let book = Book {
+///   attrs:   Attrs { category: "fantasy".to_owned() },
+///   title:   "The Name of the Wind".to_owned(),
+///   authors: vec!["Patrick Rothfuss".to_owned()],
+///   year:    2007
+/// };
+/// let xml = to_string(&book, XmlConfig::new().root_name("book")).unwrap();
+/// assert_eq!(
+///   xml,
+///   r#"<?xml version="1.0"?><book category="fantasy"><title>The Name of the Wind</title><authors>Patrick Rothfuss</authors><year>2007</year></book>"#
+/// );
+/// ```
+///
+/// [`XmlConfig`]: struct.XmlConfig.html
+/// [`XmlBuilder::build_from_json`]: struct.XmlBuilder.html#method.build_from_json
+pub fn to_string<T: ?Sized + Serialize>(value: &T, config: &XmlConfig) -> Result<String, Error> {
+  let builder = config.try_finalize()?;
+  builder.render(to_nodes(value, &builder)?)
+}
+
+/// Serialize a value as XML into `writer`, as [`to_string`] does. The XML is written as it is
+/// rendered, and a failed write is returned as an error of kind `io` which keeps the
+/// [`io::Error`].
+///
+/// [`to_string`]: fn.to_string.html
+pub fn to_writer<W: io::Write, T: ?Sized + Serialize>(mut writer: W, value: &T, config: &XmlConfig) -> Result<(), Error> {
+  let builder = config.try_finalize()?;
+  builder.write(&mut writer, to_nodes(value, &builder)?)?;
+  Ok(())
+}
+
+// Top level nodes of the document for `value`
+fn to_nodes<T: ?Sized + Serialize>(value: &T, builder: &XmlBuilder) -> Result<Vec<Content>, Error> {
+  if builder.is_xml2js() {
+    let mut nodes = Vec::new();
+    value.serialize(NodeSerializer {
+      builder: builder.options(),
+      path:    String::new(),
+      role:    Role::Document,
+      out:     &mut nodes
+    })?;
+    Ok(nodes)
+  } else {
+    builder.convention().to_xml(&serde_json::to_value(value)?)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{
+    convention::XmlRpc,
+    json::{CollisionPolicy, JsonConfig},
+    xml::{Indentation, NamePolicy, NullPolicy, ValuePolicy}
+  };
+  use serde::Serialize;
+  use serde_json::json;
+  use std::collections::BTreeMap;
+
+  const DOCUMENTS: [&str; 7] = [
+    include_str!("../tests/data/cds.json"),
+    include_str!("../tests/data/childobj.json"),
+    include_str!("../tests/data/complex.json"),
+    include_str!("../tests/data/lists.json"),
+    include_str!("../tests/data/multiroot.json"),
+    include_str!("../tests/data/singleroot.json"),
+    include_str!("../tests/data/soap.json")
+  ];
+
+  // What `build_from_json` builds from the JSON of a value
+  fn build_from_json<T: Serialize>(value: &T, config: &XmlConfig) -> Result<String, Error> {
+    config.finalize().build_from_json(&serde_json::to_value(value)?)
+  }
+
+  fn configs() -> Vec<XmlConfig> {
+    let mut configs = vec![XmlConfig::new(); 9];
+    configs[1].root_name("doc").rendering(Indentation::new(b' ', 2));
+    configs[2].attr_prefix("@").empty_tag("EMPTY");
+    configs[3].values(ValuePolicy::new().array_item("item").null(NullPolicy::Nil).scalar_attrs(true));
+    configs[4].values(ValuePolicy::new().null(NullPolicy::Omit)).root_name("doc");
+    configs[5]
+      .values(ValuePolicy::new().null(NullPolicy::Empty).array_item("item"))
+      .root_name("doc");
+    configs[6].collisions(CollisionPolicy::Reject);
+    configs[7]
+      .name_policy(NamePolicy::Escape)
+      .collisions(CollisionPolicy::Escape("x_".to_owned()))
+      .namespace("x", "urn:x");
+    configs[8].charkey("#text").attrkey("@").nskey("@ns").childkey("#children");
+    configs
+  }
+
+  // Serializing JSON gives what `build_from_json` builds, errors included
+  #[test]
+  fn xml2js_parity() {
+    let mut documents: Vec<JsonValue> = DOCUMENTS.iter().map(|json| serde_json::from_str(json).unwrap()).collect();
+    documents.extend(vec![
+      json!({
+        "a": [[{"$": {"x": "1"}, "b": 1}], [1, [2, 3]], []],
+        "c": null,
+        "d": {"_": ["x", 1, true, null], "$$": [{"#name": "e", "_": "t"}, {"#name": "__text__", "_": "u"}]},
+        "f": {"$ns": {"uri": "urn:x", "local": "g"}, "$": {"y": {"value": "2", "uri": "urn:y", "local": "z"}}},
+        "@h": ["merged"],
+        "i": "EMPTY",
+        "j": [1.5, -0.0, 1e300],
+        "k": {"$": {"n": "", "o": "1"}, "p": false},
+        "x_$": "escaped",
+        "first name": ""
+      }),
+      json!({"a": {"_": {"b": 1}}}),
+      json!({"k": {"$": {"n": null, "o": 1}}}),
+      json!({"a": {"$": "attrs"}}),
+      json!({"single": {"x": 1}}),
+      json!({"#text": "text", "@": {"a": "1"}, "@ns": {"uri": "urn:x"}}),
+      json!([1, {"a": 2}, [3]]),
+      json!([[{"$": {"a": "1"}}]]),
+      json!("text"),
+      json!(1),
+      json!(null),
+      json!({}),
+      json!([]),
+    ]);
+
+    for config in &configs() {
+      for document in &documents {
+        let expected = build_from_json(document, config).map_err(|e| e.to_string());
+        assert_eq!(to_string(document, config).map_err(|e| e.to_string()), expected, "{}", document);
+      }
+    }
+  }
+
+  #[derive(Serialize)]
+  struct Book {
+    #[serde(rename = "$")]
+    attrs:    Attrs,
+    title:    Title,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    subtitle: Option<String>,
+    authors:  Vec<String>,
+    year:     u32,
+    price:    f32,
+    isbn:     (u64, char),
+    format:   Format,
+    editions: Vec<Edition>,
+    grid:     Vec<Vec<u8>>,
+    notes:    BTreeMap<u32, Option<String>>,
+    cover:    Vec<u8>
+  }
+
+  #[derive(Serialize)]
+  struct Attrs {
+    category: &'static str,
+    #[serde(rename = "xml:lang")]
+    lang:     Option<&'static str>
+  }
+
+  #[derive(Serialize)]
+  struct Title {
+    #[serde(rename = "_")]
+    text:  &'static str,
+    #[serde(rename = "$")]
+    attrs: BTreeMap<&'static str, &'static str>
+  }
+
+  #[derive(Serialize)]
+  #[serde(rename_all = "lowercase")]
+  enum Format {
+    Hardcover,
+    #[allow(dead_code)]
+    Paperback
+  }
+
+  #[derive(Serialize)]
+  enum Edition {
+    First { year: u32 },
+    Reprint(u32, &'static str),
+    Translated(&'static str),
+    Unknown
+  }
+
+  fn book(subtitle: Option<&str>) -> Book {
+    Book {
+      attrs:    Attrs {
+        category: "fantasy",
+        lang:     None
+      },
+      title:    Title {
+        text:  "The Name of the Wind",
+        attrs: vec![("lang", "en")].into_iter().collect()
+      },
+      subtitle: subtitle.map(str::to_owned),
+      authors:  vec!["Patrick Rothfuss".to_owned()],
+      year:     2007,
+      price:    9.99,
+      isbn:     (978_075_640_407, '9'),
+      format:   Format::Hardcover,
+      editions: vec![
+        Edition::First { year: 2007 },
+        Edition::Reprint(2008, "DAW"),
+        Edition::Translated("de"),
+        Edition::Unknown,
+      ],
+      grid:     vec![vec![1, 2], vec![3]],
+      notes:    vec![(1, Some("signed".to_owned())), (2, None)].into_iter().collect(),
+      cover:    vec![0, 255]
+    }
+  }
+
+  #[test]
+  fn typed_values() {
+    for config in &configs() {
+      for value in &[book(None), book(Some("The Kingkiller Chronicle"))] {
+        let expected = build_from_json(value, config).map_err(|e| e.to_string());
+        assert_eq!(to_string(value, config).map_err(|e| e.to_string()), expected);
+      }
+    }
+
+    let config = XmlConfig::new()
+      .root_name("book")
+      .values(ValuePolicy::new().null(NullPolicy::Omit))
+      .clone();
+    assert_eq!(
+      to_string(&book(None), &config).unwrap(),
+      concat!(
+        r#"<?xml version="1.0"?><book category="fantasy"><title lang="en">The Name of the Wind</title>"#,
+        "<authors>Patrick Rothfuss</authors><year>2007</year><price>9.989999771118164</price>",
+        "<isbn>978075640407</isbn><isbn>9</isbn><format>hardcover</format>",
+        "<editions><First><year>2007</year></First></editions><editions><Reprint>2008</Reprint><Reprint>DAW</Reprint></editions>",
+        "<editions><Translated>de</Translated></editions><editions>Unknown</editions>",
+        "<grid>12</grid><grid>3</grid><notes><1>signed</1></notes><cover>0</cover><cover>255</cover></book>"
+      )
+    );
+  }
+
+  #[test]
+  fn documents() {
+    #[derive(Serialize)]
+    struct Item {
+      name: &'static str
+    }
+
+    // A single key under the root name "root" is the document element
+    let xml = to_string(&json!({"item": {"name": "a"}}), &XmlConfig::new()).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><item><name>a</name></item>"#);
+    let xml = to_string(&Item { name: "a" }, &XmlConfig::new()).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><name>a</name>"#);
+    let xml = to_string(&Item { name: "a" }, XmlConfig::new().root_name("item")).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><item><name>a</name></item>"#);
+
+    // Maps of unknown length are collected first
+    let mut map = BTreeMap::new();
+    map.insert("a", 1);
+    map.insert("b", 2);
+    let xml = to_string(&map, &XmlConfig::new()).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><root><a>1</a><b>2</b></root>"#);
+
+    let mut buffer = Vec::new();
+    to_writer(&mut buffer, &vec![Item { name: "a" }, Item { name: "b" }], &XmlConfig::new()).unwrap();
+    assert_eq!(
+      String::from_utf8(buffer).unwrap(),
+      r#"<?xml version="1.0"?><root><name>a</name></root><root><name>b</name></root>"#
+    );
+  }
+
+  #[test]
+  fn conventions() {
+    let value = json!({"methodName": "add", "params": [1, 2]});
+    let config = XmlConfig::new().convention(XmlRpc).clone();
+    assert_eq!(to_string(&value, &config).unwrap(), build_from_json(&value, &config).unwrap());
+  }
+
+  #[test]
+  fn errors() {
+    let error = to_string(&json!({"a": {"$": {"b": 1}}}), &XmlConfig::new()).unwrap_err();
+    assert_eq!(error.details(), "Expected attribute to be a string.");
+
+    let error = to_string(&json!({"a b": 1}), XmlConfig::new().name_policy(NamePolicy::Reject)).unwrap_err();
+    assert_eq!(error.details(), "Invalid XML name \"a b\" at /a b");
+
+    let error = to_string(&BTreeMap::<u32, u32>::new(), XmlConfig::new().attrkey("_")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+
+    let round_trip = JsonConfig::new()
+      .finalize()
+      .build_from_xml(&to_string(&json!({"a": 1}), &XmlConfig::new()).unwrap());
+    assert_eq!(round_trip.unwrap(), json!({"a": "1"}));
+
+    // Write errors are returned as they are
+    struct Full;
+    impl io::Write for Full {
+      fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::new(io::ErrorKind::WriteZero, "full"))
+      }
+
+      fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+      }
+    }
+    let error = to_writer(Full, &json!({"a": 1}), &XmlConfig::new()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Io);
+    assert_eq!(error.io_error().unwrap().kind(), io::ErrorKind::WriteZero);
+    assert!(std::error::Error::source(&error).is_some());
+  }
+}
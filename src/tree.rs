@@ -0,0 +1,363 @@
+use quick_xml::{escape::unescape, events::*, Reader};
+
+use crate::{
+  error::{Error, ErrorKind},
+  name
+};
+
+/// Content of an XML element, or a top level node of a document
+#[derive(Clone, Debug, PartialEq)]
+pub enum Content {
+  /// A child element
+  Element(Element),
+  /// Character data, stored unescaped
+  Text(String),
+  /// Character data written as a CDATA section
+  CData(String),
+  /// Comment
+  Comment(String),
+  /// Processing instruction, with its target and data
+  Instruction(String),
+  /// XML declaration, with its pseudo-attributes such as `version`. Only the first top level node
+  /// of a document is a declaration
+  Declaration(Vec<(String, String)>),
+  /// Document type declaration, with the text between `<!DOCTYPE` and `>` such as
+  /// `html PUBLIC "-//W3C//DTD XHTML 1.0 Strict//EN" "DTD/xhtml1-strict.dtd"`
+  DocType(String)
+}
+
+impl Content {
+  /// Check if the content is character data, as text or a CDATA section
+  pub fn is_char_data(&self) -> bool {
+    matches!(self, Content::Text(_) | Content::CData(_))
+  }
+}
+
+/// An XML element, as parsed from a document or built from JSON before it is rendered.
+///
+/// Building the whole element before writing it lets the renderer know what kind of content the
+/// element holds (element-only, text-only or mixed) at the time its start tag is written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Element {
+  /// Qualified name, or a Clark notation name (`{uri}local`) to be given a prefix when written
+  pub name:     String,
+  /// Attributes in document order, with unescaped values. Namespace declarations are attributes
+  pub attrs:    Vec<(String, String)>,
+  /// Content in document order
+  pub children: Vec<Content>
+}
+
+impl Element {
+  /// Initialize an element without attributes or content
+  pub fn new<T: Into<String>>(name: T) -> Element {
+    Element {
+      name:     name.into(),
+      attrs:    Vec::new(),
+      children: Vec::new()
+    }
+  }
+
+  /// Initialize an element holding `text`, or nothing when it is empty
+  pub fn with_text<N: Into<String>, T: Into<String>>(name: N, text: T) -> Element {
+    let mut element = Element::new(name);
+    let text = text.into();
+    if !text.is_empty() {
+      element.children.push(Content::Text(text));
+    }
+    element
+  }
+
+  /// Local part of the element's qualified name
+  pub fn local_name(&self) -> &str {
+    self.name.rsplit(':').next().unwrap_or(&self.name)
+  }
+
+  /// Value of the attribute named `name`
+  pub fn attr(&self, name: &str) -> Option<&str> {
+    self.attrs.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+  }
+
+  /// Character data of the element, without the text of child elements
+  pub fn text(&self) -> String {
+    self
+      .children
+      .iter()
+      .filter_map(|c| match c {
+        Content::Text(t) | Content::CData(t) => Some(t.as_str()),
+        _ => None
+      })
+      .collect()
+  }
+
+  /// Child elements of the element
+  pub fn elements(&self) -> impl Iterator<Item = &Element> {
+    self.children.iter().filter_map(|c| match c {
+      Content::Element(e) => Some(e),
+      _ => None
+    })
+  }
+
+  /// First child element named `name`
+  pub fn child(&self, name: &str) -> Option<&Element> {
+    self.elements().find(|e| e.name == name)
+  }
+
+  /// Elements without content are written as self-closing tags
+  pub fn is_empty(&self) -> bool {
+    self.children.is_empty()
+  }
+
+  /// Check if any of the element's content is markup: a child element, comment or processing
+  /// instruction
+  pub fn has_elements(&self) -> bool {
+    self.children.iter().any(|c| !c.is_char_data())
+  }
+
+  /// Check if any of the element's content is character data
+  pub fn has_text(&self) -> bool {
+    self.children.iter().any(Content::is_char_data)
+  }
+
+  /// Mixed content elements contain both character data and child elements. Whitespace inside
+  /// them is significant, so they are never re-indented.
+  pub fn is_mixed(&self) -> bool {
+    self.has_elements() && self.has_text()
+  }
+}
+
+// Attribute value normalization (XML 1.0 section 3.3.3): literal whitespace characters become
+// spaces before references are replaced
+pub(crate) fn attribute_value(raw: &[u8]) -> Result<String, Error> {
+  let normalized: Vec<u8> = raw
+    .iter()
+    .map(|&b| if b == b'\t' || b == b'\n' || b == b'\r' { b' ' } else { b })
+    .collect();
+  let value = unescape(&normalized).map_err(|e| Error::new(ErrorKind::Syntax, format!("{:?}", e)))?;
+  Ok(std::str::from_utf8(&value)?.to_owned())
+}
+
+// Line ending normalization (XML 1.0 section 2.11), applied to literal text before references are
+// replaced so that `&#xD;` is kept
+pub(crate) fn normalize_newlines(raw: &[u8]) -> Vec<u8> {
+  let mut normalized = Vec::with_capacity(raw.len());
+  let mut bytes = raw.iter().peekable();
+  while let Some(&b) = bytes.next() {
+    if b == b'\r' {
+      bytes.next_if_eq(&&b'\n');
+      normalized.push(b'\n');
+    } else {
+      normalized.push(b);
+    }
+  }
+  normalized
+}
+
+// Character data with line endings normalized and references replaced
+pub(crate) fn text_value(raw: &[u8]) -> Result<String, Error> {
+  unescaped(&normalize_newlines(raw))
+}
+
+// A comment built from the JSON value at JSON pointer `path`. Comments can't contain `--` or end
+// with `-`, which would end them early or make them malformed when written
+pub(crate) fn comment(path: &str, comment: String) -> Result<Content, Error> {
+  if comment.contains("--") || comment.ends_with('-') {
+    return Err(Error::new(ErrorKind::Syntax, format!("Invalid comment at {}.", path)));
+  }
+  Ok(Content::Comment(comment))
+}
+
+// A processing instruction built from the JSON value at JSON pointer `path`, with its target and
+// data. The target must be a name other than `xml`, and the instruction can't contain `?>`, which
+// would end it early when written
+pub(crate) fn instruction(path: &str, instruction: String) -> Result<Content, Error> {
+  let target = instruction.split(is_xml_space).next().unwrap_or_default();
+  if !name::is_valid(target) || target.eq_ignore_ascii_case("xml") || instruction.contains("?>") {
+    return Err(Error::new(
+      ErrorKind::Syntax,
+      format!("Invalid processing instruction at {}.", path)
+    ));
+  }
+  Ok(Content::Instruction(instruction))
+}
+
+// A doctype built from the JSON value at JSON pointer `path`. It must start with the name of the
+// document element and can't end before the `>` written after it
+pub(crate) fn doctype(path: &str, doctype: String) -> Result<Content, Error> {
+  let name_end = doctype.find(|c| is_xml_space(c) || c == '[').unwrap_or(doctype.len());
+  if !name::is_valid(&doctype[.. name_end]) || !doctype_closed(&doctype[name_end ..]) {
+    return Err(Error::new(ErrorKind::Syntax, format!("Invalid doctype at {}.", path)));
+  }
+  Ok(Content::DocType(doctype))
+}
+
+// Check that the rest of a doctype leaves its literals, internal subset, and the comments and
+// processing instructions in it closed, with no `>` outside of them
+fn doctype_closed(mut rest: &str) -> bool {
+  let mut subset = false;
+  while let Some(c) = rest.chars().next() {
+    let skip = match c {
+      '"' | '\'' => rest[1 ..].find(c).map(|i| i + 2),
+      '<' if subset && rest.starts_with("<!--") => rest[4 ..].find("-->").map(|i| i + 7),
+      '<' if subset && rest.starts_with("<?") => rest[2 ..].find("?>").map(|i| i + 4),
+      '>' if !subset => None,
+      '[' if !subset => {
+        subset = true;
+        Some(1)
+      },
+      ']' if subset => {
+        subset = false;
+        Some(1)
+      },
+      _ => Some(c.len_utf8())
+    };
+    match skip {
+      Some(n) => rest = &rest[n ..],
+      None => return false
+    }
+  }
+  !subset
+}
+
+fn is_xml_space(c: char) -> bool {
+  matches!(c, ' ' | '\t' | '\r' | '\n')
+}
+
+// Append character data to `content`, merging it with preceding text
+pub(crate) fn push_text(content: &mut Vec<Content>, text: &str) {
+  if text.is_empty() {
+    return;
+  }
+  if let Some(Content::Text(last)) = content.last_mut() {
+    last.push_str(text);
+  } else {
+    content.push(Content::Text(text.to_owned()));
+  }
+}
+
+/// Parse an XML document into its top level nodes: the document element and any comments and
+/// processing instructions around it. The declaration and doctype are skipped, CDATA sections are
+/// merged into the surrounding text and whitespace outside the document element is dropped.
+pub(crate) fn parse(xml: &str) -> Result<Vec<Content>, Error> {
+  parse_nodes(xml, false, true)
+}
+
+/// Parse an XML document like [`parse`], keeping the declaration, the doctype and CDATA sections
+pub(crate) fn parse_document(xml: &str) -> Result<Vec<Content>, Error> {
+  parse_nodes(xml, true, true)
+}
+
+/// Parse an XML document like [`parse_document`], with line breaks and attribute values kept as
+/// they are written
+pub(crate) fn parse_verbatim(xml: &str) -> Result<Vec<Content>, Error> {
+  parse_nodes(xml, true, false)
+}
+
+// Character data with references replaced, without normalization
+fn unescaped(raw: &[u8]) -> Result<String, Error> {
+  let value = unescape(raw).map_err(|e| Error::new(ErrorKind::Syntax, format!("{:?}", e)))?;
+  Ok(std::str::from_utf8(&value)?.to_owned())
+}
+
+// Parse the top level nodes of a document. Unless `document` is set, the declaration and doctype
+// are skipped and CDATA sections are merged into text. Unless `normalize` is set, line breaks and
+// attribute values are not normalized
+fn parse_nodes(xml: &str, document: bool, normalize: bool) -> Result<Vec<Content>, Error> {
+  let newlines = |raw: &[u8]| if normalize { normalize_newlines(raw) } else { raw.to_vec() };
+  let mut reader = Reader::from_str(xml);
+  let mut buffer = Vec::new();
+  let mut nodes = Vec::new();
+  let mut stack: Vec<Element> = Vec::new();
+
+  loop {
+    let event = reader.read_event(&mut buffer).map_err(|e| {
+      Error::new(
+        ErrorKind::Syntax,
+        format!("Error at position {}: {:?}", reader.buffer_position(), e)
+      )
+    })?;
+
+    match event {
+      Event::Start(ref e) | Event::Empty(ref e) => {
+        let mut element = Element::new(std::str::from_utf8(e.name())?);
+        for attr in e.attributes() {
+          let attr = attr.map_err(|e| Error::new(ErrorKind::Syntax, format!("{:?}", e)))?;
+          element.attrs.push((
+            std::str::from_utf8(attr.key)?.to_owned(),
+            if normalize {
+              attribute_value(&attr.value)?
+            } else {
+              unescaped(&attr.value)?
+            }
+          ));
+        }
+        if let Event::Start(_) = event {
+          stack.push(element);
+        } else {
+          match stack.last_mut() {
+            Some(parent) => parent.children.push(Content::Element(element)),
+            None => nodes.push(Content::Element(element))
+          }
+        }
+      },
+      Event::End(_) => {
+        let element = stack
+          .pop()
+          .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected stack item at close tag."))?;
+        match stack.last_mut() {
+          Some(parent) => parent.children.push(Content::Element(element)),
+          None => nodes.push(Content::Element(element))
+        }
+      },
+      Event::Text(ref e) => {
+        if let Some(parent) = stack.last_mut() {
+          let text = if normalize { text_value(e)? } else { unescaped(e)? };
+          push_text(&mut parent.children, &text);
+        }
+      },
+      Event::CData(ref e) => {
+        if let Some(parent) = stack.last_mut() {
+          let text = String::from_utf8(newlines(e))?;
+          if document {
+            parent.children.push(Content::CData(text));
+          } else {
+            push_text(&mut parent.children, &text);
+          }
+        }
+      },
+      Event::Decl(ref e) if document => {
+        let mut attrs = vec![("version".to_owned(), String::from_utf8(e.version()?.into_owned())?)];
+        if let Some(encoding) = e.encoding() {
+          attrs.push(("encoding".to_owned(), String::from_utf8(encoding?.into_owned())?));
+        }
+        if let Some(standalone) = e.standalone() {
+          attrs.push(("standalone".to_owned(), String::from_utf8(standalone?.into_owned())?));
+        }
+        nodes.push(Content::Declaration(attrs));
+      },
+      Event::DocType(ref e) if document => {
+        let doctype = std::str::from_utf8(&newlines(e))?.trim().to_owned();
+        nodes.push(Content::DocType(doctype));
+      },
+      Event::PI(ref e) | Event::Comment(ref e) => {
+        let data = std::str::from_utf8(&newlines(e))?.to_owned();
+        let node = if let Event::PI(_) = event {
+          Content::Instruction(data)
+        } else {
+          Content::Comment(data)
+        };
+        match stack.last_mut() {
+          Some(parent) => parent.children.push(node),
+          None => nodes.push(node)
+        }
+      },
+      Event::Eof => break,
+      _ => ()
+    }
+    buffer.clear();
+  }
+
+  if !stack.is_empty() {
+    return Err(Error::new(ErrorKind::Syntax, "Unexpected end of document."));
+  }
+  Ok(nodes)
+}
@@ -1,5 +1,10 @@
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
 use serde_json::Value as JsonValue;
 
+use crate::error::{Error, ErrorKind};
+
 // Check if a JSON value is empty
 pub fn json_is_empty(node: &JsonValue) -> bool {
   match node {
@@ -29,3 +34,50 @@ pub fn to_string_raw(node: &JsonValue) -> String {
     node.to_string()
   }
 }
+
+// Append `token` to a JSON pointer (RFC 6901), escaping `~` and `/`
+pub fn json_pointer(path: &str, token: &str) -> String {
+  format!("{}/{}", path, token.replace('~', "~0").replace('/', "~1"))
+}
+
+// Conflicts between configuration keys, given as (option, key) pairs: empty keys, and keys which
+// are the same
+pub fn key_conflicts(keys: &[(&str, &str)]) -> Vec<String> {
+  let mut conflicts = Vec::new();
+  for (i, (option, key)) in keys.iter().enumerate() {
+    if key.is_empty() {
+      conflicts.push(format!("{} is empty", option));
+    } else if let Some((other, _)) = keys[.. i].iter().find(|(_, k)| k == key) {
+      conflicts.push(format!("{} and {} are both {:?}", other, option, key));
+    }
+  }
+  conflicts
+}
+
+// Fail with a config error listing every conflict, if there are any
+pub fn check_conflicts(conflicts: Vec<String>) -> Result<(), Error> {
+  if conflicts.is_empty() {
+    Ok(())
+  } else {
+    Err(Error::new(ErrorKind::Config, conflicts.join("; ")))
+  }
+}
+
+// Deserialize a configuration from JSON
+pub fn config_from_json_str<T: DeserializeOwned>(json: &str) -> Result<T, Error> {
+  serde_json::from_str(json).map_err(|e| Error::new(ErrorKind::Config, e.to_string()))
+}
+
+// Deserialize a configuration from a file: TOML for `.toml` files, JSON otherwise. Errors start
+// with the file's path
+pub fn config_from_path<T: DeserializeOwned>(path: &Path) -> Result<T, Error> {
+  let in_file = |e: &dyn std::fmt::Display| Error::new(ErrorKind::Config, format!("{}: {}", path.display(), e));
+  let text = std::fs::read_to_string(path).map_err(|e| in_file(&e))?;
+  if path.extension().map_or(false, |e| e == "toml") {
+    #[cfg(feature = "toml")]
+    return toml::from_str(&text).map_err(|e| in_file(&e));
+    #[cfg(not(feature = "toml"))]
+    return Err(in_file(&"TOML configurations require the `toml` feature"));
+  }
+  serde_json::from_str(&text).map_err(|e| in_file(&e))
+}
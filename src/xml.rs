@@ -1,15 +1,22 @@
-use quick_xml::{events::*, Writer};
+use quick_xml::events::BytesDecl;
 
-use std::{cell::RefCell, convert::TryFrom, io::Cursor, ops::DerefMut, rc::Rc};
+use std::{convert::TryFrom, io::Write, mem, path::Path, sync::Arc};
 
 use crate::{
+  c14n::Canonicalization,
+  convention::{check_name, Convention, JsonOptions, Xml2Js, XmlOptions},
   error::{Error, ErrorKind},
+  json::CollisionPolicy,
+  namespace::{self, NamespaceResolver},
+  render::Renderer,
+  tree::{Content, Element},
   utils
 };
 
-use serde_json::{Map as JsonMap, Value as JsonValue};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// XML [Declaration] encoding.
 ///
 /// For now only UTF-8 is supported
@@ -17,6 +24,7 @@ use serde_json::{Map as JsonMap, Value as JsonValue};
 /// [Declaration]: struct.Declaration.html
 pub enum Encoding {
   /// UTF-8
+  #[serde(rename = "UTF-8")]
   UTF8 // see https://www.w3resource.com/xml/declarations.php
 }
 
@@ -41,7 +49,7 @@ impl TryFrom<&str> for Encoding {
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 /// XML [Declaration] version.
 ///
 /// Setting this in a [Declaration] will not alter the output of the XML apart from writing the
@@ -50,8 +58,10 @@ impl TryFrom<&str> for Encoding {
 /// [Declaration]: struct.Declaration.html
 pub enum Version {
   /// 1.0
+  #[serde(rename = "1.0")]
   XML10,
   /// 1.1
+  #[serde(rename = "1.1")]
   XML11
 }
 
@@ -78,7 +88,8 @@ impl TryFrom<&str> for Version {
   }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 /// XML Declaration
 pub struct Declaration {
   version:    Version,
@@ -106,7 +117,7 @@ impl Declaration {
     }
   }
 
-  fn as_bytes_decl(&self) -> BytesDecl {
+  pub(crate) fn as_bytes_decl(&self) -> BytesDecl<'_> {
     let version = self.version.to_string().as_bytes();
     let encoding = self.encoding.as_ref().map(|v| v.to_string().as_bytes());
     let standalone = self
@@ -118,11 +129,83 @@ impl Declaration {
   }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+/// Line break written between rendered lines.
+pub enum Newline {
+  /// `\n`
+  LF,
+  /// `\r\n`
+  CRLF
+}
+
+impl Newline {
+  /// Serialize `Newline` as a `&'static str`
+  pub fn to_string(&self) -> &'static str {
+    match *self {
+      Newline::LF => "\n",
+      Newline::CRLF => "\r\n"
+    }
+  }
+}
+
+impl TryFrom<&str> for Newline {
+  type Error = Error;
+
+  /// Try to initialize a `Newline` from `&str`
+  fn try_from(s: &str) -> Result<Self, Self::Error> {
+    match s {
+      "\n" | "LF" => Ok(Newline::LF),
+      "\r\n" | "CRLF" => Ok(Newline::CRLF),
+      _ => Err(Error::new(ErrorKind::Unknown, format!("Cannot convert from {:?} to Newline.", s)))
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// When the attributes of a start tag are written on separate lines.
+pub enum AttrWrap {
+  /// Always keep attributes on the same line as the tag name
+  Never,
+  /// Wrap when the indented start tag would be wider than this many characters
+  Width(usize),
+  /// Wrap when the tag has more than this many attributes
+  Count(usize)
+}
+
+// `Indentation::indent_char` as a string of one ASCII character
+mod indent_char {
+  use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S: Serializer>(indent_char: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_char(char::from(*indent_char))
+  }
+
+  pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u8, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+      (Some(c), None) if c.is_ascii() => Ok(c as u8),
+      _ => Err(D::Error::custom(format!(
+        "invalid indent_char {:?}, expected one ASCII character",
+        s
+      )))
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 /// XML Indentation rendering options
 pub struct Indentation {
-  indent_char: u8,
-  indent_size: usize
+  #[serde(with = "indent_char")]
+  pub(crate) indent_char:        u8,
+  pub(crate) indent_size:        usize,
+  pub(crate) newline:            Newline,
+  pub(crate) inline_text:        bool,
+  pub(crate) attr_wrap:          AttrWrap,
+  pub(crate) space_before_slash: bool,
+  pub(crate) final_newline:      bool
 }
 
 /// Optional indentation rendering
@@ -132,27 +215,177 @@ impl Indentation {
   /// This can be passed as a `XmlConfig.rendering` option to output XML with line-breaks and
   /// indentations.
   pub fn new(indent_char: u8, indent_size: usize) -> Indentation {
-    Indentation { indent_char, indent_size }
+    Indentation {
+      indent_char,
+      indent_size,
+      newline: Newline::LF,
+      inline_text: true,
+      attr_wrap: AttrWrap::Never,
+      space_before_slash: false,
+      final_newline: false
+    }
+  }
+
+  /// Line break written between lines.
+  ///
+  /// (`Newline::LF` by default)
+  pub fn newline(mut self, newline: Newline) -> Indentation {
+    self.newline = newline;
+    self
+  }
+
+  /// Keep elements which only contain text on a single line (`<a>text</a>`). When unset the text
+  /// is written on its own, indented line between the start and end tags.
+  ///
+  /// (`true` by default)
+  pub fn inline_text(mut self, flag: bool) -> Indentation {
+    self.inline_text = flag;
+    self
+  }
+
+  /// Write each attribute on its own line, indented one level deeper than its element, once the
+  /// start tag exceeds the given [AttrWrap] limit.
+  ///
+  /// (`AttrWrap::Never` by default)
+  ///
+  /// [AttrWrap]: enum.AttrWrap.html
+  pub fn attr_wrap(mut self, attr_wrap: AttrWrap) -> Indentation {
+    self.attr_wrap = attr_wrap;
+    self
+  }
+
+  /// Write a space before the `/>` of self-closing tags (`<a />`).
+  ///
+  /// (`false` by default)
+  pub fn space_before_slash(mut self, flag: bool) -> Indentation {
+    self.space_before_slash = flag;
+    self
+  }
+
+  /// End the document with a line break.
+  ///
+  /// (`false` by default)
+  pub fn final_newline(mut self, flag: bool) -> Indentation {
+    self.final_newline = flag;
+    self
   }
 }
 
 impl Default for Indentation {
   fn default() -> Indentation {
-    let indent_char = b' ';
-    Indentation {
-      indent_char,
-      indent_size: 2
+    Indentation::new(b' ', 2)
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How JSON keys which aren't valid XML names are handled.
+pub enum NamePolicy {
+  /// Write names as they are, even if the resulting XML is malformed
+  Verbatim,
+  /// Fail with an error giving the name and the JSON pointer to it
+  Reject,
+  /// Escape invalid characters as `_xHHHH_`, e.g. `"first name"` becomes `first_x0020_name`. The
+  /// names can be decoded with [JsonConfig::decode_names].
+  ///
+  /// [JsonConfig::decode_names]: crate::JsonConfig::decode_names
+  Escape
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+/// How JSON `null` values are written.
+pub enum NullPolicy {
+  /// Write the text `null`
+  Text,
+  /// Leave out the element or attribute
+  Omit,
+  /// Write an empty element or attribute
+  Empty,
+  /// Write an empty element with `xsi:nil="true"`. Attributes are left out
+  Nil
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+/// Typed JSON value options: numbers, booleans, `null` and arrays nested in arrays
+pub struct ValuePolicy {
+  pub(crate) scalar_attrs: bool,
+  pub(crate) null:         NullPolicy,
+  pub(crate) array_item:   Option<String>
+}
+
+impl ValuePolicy {
+  /// Initialize a ValuePolicy instance, matching the behavior of node-xml2js
+  pub fn new() -> ValuePolicy {
+    ValuePolicy {
+      scalar_attrs: false,
+      null:         NullPolicy::Text,
+      array_item:   None
     }
   }
+
+  /// Write number and boolean attribute values as their JSON text, instead of failing with
+  /// "Expected attribute to be a string.".
+  ///
+  /// (`false` by default)
+  pub fn scalar_attrs(mut self, flag: bool) -> ValuePolicy {
+    self.scalar_attrs = flag;
+    self
+  }
+
+  /// How `null` element and attribute values are written. A `null` attribute value is only written
+  /// as `null` when `scalar_attrs` is set.
+  ///
+  /// (`NullPolicy::Text` by default)
+  pub fn null(mut self, null: NullPolicy) -> ValuePolicy {
+    self.null = null;
+    self
+  }
+
+  /// Element name used for the items of an array nested in an element's array. With `"item"`,
+  /// `{"a":[[1,2]]}` is written as `<a><item>1</item><item>2</item></a>`. Without it, the content
+  /// of every item is written directly in the element: `<a>12</a>`.
+  ///
+  /// (`None` by default)
+  pub fn array_item<T: Into<String>>(mut self, name: T) -> ValuePolicy {
+    self.array_item = Some(name.into());
+    self
+  }
+}
+
+impl Default for ValuePolicy {
+  fn default() -> ValuePolicy {
+    ValuePolicy::new()
+  }
 }
 
 /// XmlBuilder configuration options
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct XmlConfig {
-  attrkey:   Option<String>,
-  charkey:   Option<String>,
-  root_name: Option<String>,
-  decl:      Option<Declaration>,
-  rendering: Option<Indentation>
+  attrkey:     Option<String>,
+  charkey:     Option<String>,
+  nskey:       Option<String>,
+  childkey:    Option<String>,
+  empty_tag:   Option<String>,
+  root_name:   Option<String>,
+  decl:        Option<Declaration>,
+  json_decl:   Option<bool>,
+  rendering:   Option<Indentation>,
+  #[serde(rename = "canonicalization")]
+  canonical:   Option<Canonicalization>,
+  #[serde(rename = "name_policy")]
+  names:       Option<NamePolicy>,
+  values:      Option<ValuePolicy>,
+  collisions:  Option<CollisionPolicy>,
+  attr_prefix: Option<String>,
+  #[serde(default)]
+  attr_hints:  Vec<String>,
+  #[serde(default)]
+  namespaces:  Vec<(String, String)>,
+  #[serde(skip)]
+  convention:  Option<Arc<dyn Convention>>
 }
 
 impl Default for XmlConfig {
@@ -168,14 +401,78 @@ impl XmlConfig {
   /// `self`s methods. Any options not set will use their defaults upon call to `finalize`.
   pub fn new() -> XmlConfig {
     XmlConfig {
-      root_name: None,
-      attrkey:   None,
-      charkey:   None,
-      decl:      None,
-      rendering: None
+      root_name:   None,
+      attrkey:     None,
+      charkey:     None,
+      nskey:       None,
+      childkey:    None,
+      empty_tag:   None,
+      decl:        None,
+      json_decl:   None,
+      rendering:   None,
+      canonical:   None,
+      names:       None,
+      values:      None,
+      collisions:  None,
+      attr_prefix: None,
+      attr_hints:  Vec::new(),
+      namespaces:  Vec::new(),
+      convention:  None
     }
   }
 
+  /// Load a configuration from JSON. Options are named after their setters and unknown options are
+  /// errors.
+  ///
+  /// ```rust
+  /// use xml2json_rs::XmlConfig;
+  ///
+  /// let config = XmlConfig::from_json_str(
+  ///   r#"{
+  ///   "decl": {"version": "1.0", "encoding": "UTF-8"},
+  ///   "rendering": {"indent_char": " ", "indent_size": 2, "attr_wrap": {"count": 2}},
+  ///   "values": {"null": "omit"},
+  ///   "namespaces": [["soap", "http://schemas.xmlsoap.org/soap/envelope/"]]
+  /// }"#
+  /// );
+  /// assert!(config.is_ok());
+  /// ```
+  pub fn from_json_str(json: &str) -> Result<XmlConfig, Error> {
+    utils::config_from_json_str(json)
+  }
+
+  /// Load a configuration from a file, see [XmlConfig::from_json_str]. Files with a `.toml`
+  /// extension are read as TOML, which requires the `toml` feature, and other files as JSON
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Result<XmlConfig, Error> {
+    utils::config_from_path(path.as_ref())
+  }
+
+  /// Initialize an XmlConfig which writes JSON built with [JsonConfig::lossless] back to equivalent
+  /// XML: the children under `childkey` are written in order, the declaration is the one of the
+  /// input document, if any, and the document is written compactly so that no whitespace is added.
+  ///
+  /// ```rust
+  /// use xml2json_rs::{JsonConfig, XmlConfig};
+  ///
+  /// let xml = r#"<!DOCTYPE a><a x="1">text <![CDATA[<b>]]> <!--c--><d/><?e f?></a>"#;
+  /// let json = JsonConfig::lossless()
+  ///   .finalize()
+  ///   .build_from_xml(xml)
+  ///   .unwrap();
+  /// let output = XmlConfig::lossless()
+  ///   .finalize()
+  ///   .build_from_json(&json)
+  ///   .unwrap();
+  /// assert_eq!(output, xml);
+  /// ```
+  ///
+  /// [JsonConfig::lossless]: crate::JsonConfig::lossless
+  pub fn lossless() -> XmlConfig {
+    let mut config = XmlConfig::new();
+    config.childkey("$$").json_decl(true);
+    config
+  }
+
   /// Root key name to contain produced JSON object.
   ///
   /// When this is set to its default value "root", the output will not be wrapped in `root_name`'s
@@ -207,6 +504,72 @@ impl XmlConfig {
     self
   }
 
+  /// Namespace key
+  ///
+  /// The value of the JSON key holding an element's namespace as a `{"uri": ..., "local": ...}`
+  /// object, as produced by node-xml2js with its `xmlns` option. An element with a namespace object
+  /// is written as if its key was `{uri}local`.
+  ///
+  /// (`"$ns"` by default)
+  pub fn nskey<T: Into<String>>(&mut self, key: T) -> &mut XmlConfig {
+    self.nskey = Some(key.into());
+    self
+  }
+
+  /// Children key
+  ///
+  /// The value of the JSON key holding an element's children, as produced by a [JsonBuilder] with
+  /// `explicit_children`. It holds either an object keyed by element names or an array of children
+  /// in document order. Children in an array are objects with their element name under `#name`,
+  /// or with `#name` set to `__text__`, `__cdata__` or `__comment__` and their text under
+  /// `charkey`.
+  ///
+  /// (`"$$"` by default)
+  ///
+  /// [JsonBuilder]: crate::JsonBuilder
+  pub fn childkey<T: Into<String>>(&mut self, key: T) -> &mut XmlConfig {
+    self.childkey = Some(key.into());
+    self
+  }
+
+  /// The value of empty nodes.
+  ///
+  /// An element whose value is this string is written as an empty element, reversing the
+  /// `empty_tag` option of [JsonConfig].
+  ///
+  /// (`""` by default)
+  ///
+  /// [JsonConfig]: crate::JsonConfig
+  pub fn empty_tag<T: Into<String>>(&mut self, key: T) -> &mut XmlConfig {
+    self.empty_tag = Some(key.into());
+    self
+  }
+
+  /// Bind a namespace URI to a prefix.
+  ///
+  /// Element and attribute keys may be written in Clark notation (`{uri}local`). They are written
+  /// with the prefix bound to `uri`, or with a generated `nsN` prefix when `uri` isn't bound, and
+  /// the `xmlns` declarations they require are added automatically. Binding a URI to the empty
+  /// prefix `""` makes it the default namespace for elements. Keys which use a bound prefix
+  /// verbatim, such as `soap:Envelope`, get their declaration added too.
+  ///
+  /// ```rust
+  /// use xml2json_rs::XmlConfig;
+  ///
+  /// let mut builder = XmlConfig::new().namespace("soap", "http://schemas.xmlsoap.org/soap/envelope/").finalize();
+  /// let xml = builder.build_from_json_string(r#"{"{http://schemas.xmlsoap.org/soap/envelope/}Envelope":{"soap:Body":""}}"#);
+  /// assert_eq!(
+  ///   xml.unwrap(),
+  ///   r#"<?xml version="1.0"?><soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/"><soap:Body/></soap:Envelope>"#
+  /// );
+  /// ```
+  ///
+  /// (No bindings by default)
+  pub fn namespace<P: Into<String>, U: Into<String>>(&mut self, prefix: P, uri: U) -> &mut XmlConfig {
+    self.namespaces.push((prefix.into(), uri.into()));
+    self
+  }
+
   /// XML Declaration
   ///
   /// ([Declaration::default()] by default)
@@ -216,6 +579,28 @@ impl XmlConfig {
     self
   }
 
+  /// Write only the declaration held in the JSON, as a `__declaration__` child under `childkey`
+  /// such as [JsonConfig::include_prolog] builds, and no declaration when there is none, instead of
+  /// `decl`.
+  ///
+  /// ```rust
+  /// use serde_json::json;
+  /// use xml2json_rs::XmlConfig;
+  ///
+  /// let mut builder = XmlConfig::new().json_decl(true).finalize();
+  /// assert_eq!(builder.build_from_json(&json!({"a": "b"})).unwrap(), "<a>b</a>");
+  /// let json = json!({"$$": [{"#name": "__declaration__", "$": {"version": "1.1"}}, {"#name": "a"}]});
+  /// assert_eq!(builder.build_from_json(&json).unwrap(), r#"<?xml version="1.1"?><a/>"#);
+  /// ```
+  ///
+  /// (`false` by default)
+  ///
+  /// [JsonConfig::include_prolog]: crate::JsonConfig::include_prolog
+  pub fn json_decl(&mut self, flag: bool) -> &mut XmlConfig {
+    self.json_decl = Some(flag);
+    self
+  }
+
   /// Rendering indentation options
   ///
   /// (`None` by default)
@@ -224,276 +609,420 @@ impl XmlConfig {
     self
   }
 
+  /// Canonical XML output
+  ///
+  /// Write canonical XML instead of rendering a document: no XML declaration, start/end tag pairs
+  /// for empty elements, sorted namespace declarations and attributes and escaped whitespace in
+  /// attribute values. `decl` and `rendering` are ignored. Documents can be brought to the same
+  /// form with [canonicalize].
+  ///
+  /// ```rust
+  /// use xml2json_rs::{Canonicalization, XmlConfig};
+  ///
+  /// let mut builder = XmlConfig::new()
+  ///   .canonicalization(Canonicalization::Inclusive)
+  ///   .finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"$":{"z":"1","b":"2"},"c":""}}"#);
+  /// assert_eq!(xml.unwrap(), r#"<a b="2" z="1"><c></c></a>"#);
+  /// ```
+  ///
+  /// (`None` by default)
+  /// [canonicalize]: crate::canonicalize
+  pub fn canonicalization(&mut self, canonicalization: Canonicalization) -> &mut XmlConfig {
+    self.canonical = Some(canonicalization);
+    self
+  }
+
+  /// Policy for JSON keys which aren't valid XML names, such as `"first name"`, `"2021"` or
+  /// `"a<b"`. Element names, attribute names and the local part of Clark notation names are
+  /// checked. The names of XML built by other conventions are checked as well, and must also be
+  /// qualified names: they are never written verbatim, so `NamePolicy::Verbatim` rejects them.
+  ///
+  /// ```rust
+  /// use xml2json_rs::{NamePolicy, XmlConfig};
+  ///
+  /// let mut builder = XmlConfig::new().name_policy(NamePolicy::Escape).finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"first name":"b"}}"#);
+  /// assert_eq!(
+  ///   xml.unwrap(),
+  ///   r#"<?xml version="1.0"?><a><first_x0020_name>b</first_x0020_name></a>"#
+  /// );
+  ///
+  /// let mut builder = XmlConfig::new().name_policy(NamePolicy::Reject).finalize();
+  /// let error = builder
+  ///   .build_from_json_string(r#"{"a":{"first name":"b"}}"#)
+  ///   .unwrap_err();
+  /// assert_eq!(
+  ///   error.details(),
+  ///   r#"Invalid XML name "first name" at /a/first name"#
+  /// );
+  /// ```
+  ///
+  /// (`NamePolicy::Verbatim` by default)
+  pub fn name_policy(&mut self, policy: NamePolicy) -> &mut XmlConfig {
+    self.names = Some(policy);
+    self
+  }
+
+  /// Typed JSON value options
+  ///
+  /// ```rust
+  /// use xml2json_rs::{NullPolicy, ValuePolicy, XmlConfig};
+  ///
+  /// let values = ValuePolicy::new().scalar_attrs(true).null(NullPolicy::Nil).array_item("item");
+  /// let mut builder = XmlConfig::new().values(values).finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"$":{"n":1,"b":true},"c":null,"d":[["x","y"]]}}"#);
+  /// assert_eq!(
+  ///   xml.unwrap(),
+  ///   r#"<?xml version="1.0"?><a xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" n="1" b="true"><c xsi:nil="true"/><d><item>x</item><item>y</item></d></a>"#
+  /// );
+  /// ```
+  ///
+  /// ([ValuePolicy::default()] by default)
+  /// [ValuePolicy::default()]: ValuePolicy::default
+  pub fn values(&mut self, values: ValuePolicy) -> &mut XmlConfig {
+    self.values = Some(values);
+    self
+  }
+
+  /// Policy for JSON keys that collide with `attrkey` or `charkey`.
+  ///
+  /// With `CollisionPolicy::Escape`, keys which start with the prefix are elements, written without
+  /// it, which reverses the escaping done by a `JsonBuilder` with the same policy. With
+  /// `CollisionPolicy::Reject`, an `attrkey` value which isn't an object of attributes, or a
+  /// `charkey` value which holds objects, is an error rather than being skipped.
+  ///
+  /// ```rust
+  /// use xml2json_rs::{CollisionPolicy, XmlConfig};
+  ///
+  /// let mut builder = XmlConfig::new()
+  ///   .collisions(CollisionPolicy::Escape("\\".to_owned()))
+  ///   .finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"\\_":["b"],"_":"c"}}"#);
+  /// assert_eq!(xml.unwrap(), r#"<?xml version="1.0"?><a><_>b</_>c</a>"#);
+  /// ```
+  ///
+  /// (`CollisionPolicy::Merge` by default)
+  pub fn collisions(&mut self, policy: CollisionPolicy) -> &mut XmlConfig {
+    self.collisions = Some(policy);
+    self
+  }
+
+  /// Prefix of merged attribute names
+  ///
+  /// Keys of an element object which start with this prefix are written as attributes of the
+  /// element, named without the prefix. This reads JSON built with [JsonConfig::merge_attrs] and
+  /// [JsonConfig::attr_prefix].
+  ///
+  /// ```rust
+  /// use xml2json_rs::XmlConfig;
+  ///
+  /// let mut builder = XmlConfig::new().attr_prefix("@").finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"@id":["1"],"id":["2"]}}"#);
+  /// assert_eq!(
+  ///   xml.unwrap(),
+  ///   r#"<?xml version="1.0"?><a id="1"><id>2</id></a>"#
+  /// );
+  /// ```
+  ///
+  /// (`""` by default, keys are never attributes by their prefix)
+  ///
+  /// [JsonConfig::merge_attrs]: crate::JsonConfig::merge_attrs
+  /// [JsonConfig::attr_prefix]: crate::JsonConfig::attr_prefix
+  pub fn attr_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut XmlConfig {
+    self.attr_prefix = Some(prefix.into());
+    self
+  }
+
+  /// Merged attribute hint
+  ///
+  /// Write keys named `name` as attributes of their element, instead of child elements. This reads
+  /// JSON built with [JsonConfig::merge_attrs] without an attribute prefix, when the attribute
+  /// names are known. Can be called several times to add hints.
+  ///
+  /// ```rust
+  /// use xml2json_rs::XmlConfig;
+  ///
+  /// let mut builder = XmlConfig::new()
+  ///   .attr_hint("id")
+  ///   .attr_hint("lang")
+  ///   .finalize();
+  /// let xml = builder.build_from_json_string(r#"{"a":{"id":["1"],"b":["c"]}}"#);
+  /// assert_eq!(
+  ///   xml.unwrap(),
+  ///   r#"<?xml version="1.0"?><a id="1"><b>c</b></a>"#
+  /// );
+  /// ```
+  ///
+  /// (No hints by default)
+  ///
+  /// [JsonConfig::merge_attrs]: crate::JsonConfig::merge_attrs
+  pub fn attr_hint<T: Into<String>>(&mut self, name: T) -> &mut XmlConfig {
+    self.attr_hints.push(name.into());
+    self
+  }
+
+  /// Mapping convention
+  ///
+  /// Build XML with another [Convention] than node-xml2js. Only the declaration, namespace,
+  /// rendering and canonicalization options apply to it. Conventions are not part of serialized
+  /// configurations.
+  ///
+  /// (`None` by default, the [Xml2Js] convention configured by this `XmlConfig`)
+  ///
+  /// [Xml2Js]: crate::Xml2Js
+  pub fn convention<C: Convention + 'static>(&mut self, convention: C) -> &mut XmlConfig {
+    self.convention = Some(Arc::new(convention));
+    self
+  }
+
+  /// Finalize configuration options and build an XmlBuilder instance, failing with every conflict
+  /// between them: empty or identical keys, an empty `root_name` or a rendering `indent_char`
+  /// which isn't whitespace.
+  ///
+  /// ```rust
+  /// use xml2json_rs::{Indentation, XmlConfig};
+  ///
+  /// let error = XmlConfig::new()
+  ///   .charkey("")
+  ///   .rendering(Indentation::new(b'-', 2))
+  ///   .try_finalize();
+  /// assert_eq!(
+  ///   error.unwrap_err().details(),
+  ///   "charkey is empty; indent_char '-' is not whitespace"
+  /// );
+  /// ```
+  pub fn try_finalize(&self) -> Result<XmlBuilder, Error> {
+    let builder = self.finalize();
+    utils::check_conflicts(builder.conflicts())?;
+    Ok(builder)
+  }
+
   /// Finalize configuration options and build an XmlBuilder instance
   pub fn finalize(&self) -> XmlBuilder {
-    let writer = if let Some(indentation) = &self.rendering {
-      Writer::new_with_indent(Cursor::new(Vec::new()), indentation.indent_char, indentation.indent_size)
-    } else {
-      Writer::new(Cursor::new(Vec::new()))
-    };
-
-    let decl = self.decl.clone().unwrap_or_default();
+    let value_policy = self.values.clone().unwrap_or_default();
+    let mut bindings = self.namespaces.clone();
+    // `xsi:nil` uses the conventional prefix unless the namespace or prefix is bound already
+    if value_policy.null == NullPolicy::Nil && !bindings.iter().any(|(p, u)| p == "xsi" || u == namespace::XSI_NAMESPACE) {
+      bindings.push(("xsi".to_owned(), namespace::XSI_NAMESPACE.to_owned()));
+    }
 
+    let xml = XmlOptions {
+      attrkey:     self.attrkey.clone().unwrap_or_else(|| "$".to_owned()),
+      charkey:     self.charkey.clone().unwrap_or_else(|| "_".to_owned()),
+      nskey:       self.nskey.clone().unwrap_or_else(|| "$ns".to_owned()),
+      childkey:    self.childkey.clone().unwrap_or_else(|| "$$".to_owned()),
+      empty_tag:   self.empty_tag.clone().unwrap_or_else(|| "".to_owned()),
+      root_name:   self.root_name.clone().unwrap_or_else(|| "root".to_owned()),
+      names:       self.names.clone().unwrap_or(NamePolicy::Verbatim),
+      values:      value_policy,
+      collisions:  self.collisions.clone().unwrap_or(CollisionPolicy::Merge),
+      attr_prefix: self.attr_prefix.clone().unwrap_or_default(),
+      attr_hints:  self.attr_hints.clone()
+    };
     XmlBuilder {
-      root_name: self.root_name.clone().unwrap_or_else(|| "root".to_owned()),
-      attrkey: self.attrkey.clone().unwrap_or_else(|| "$".to_owned()),
-      charkey: self.charkey.clone().unwrap_or_else(|| "_".to_owned()),
-      decl,
-      writer: Rc::new(RefCell::new(writer)),
-      indent: self.rendering.clone()
+      xml2js:     Xml2Js {
+        json: JsonOptions::default(),
+        xml
+      },
+      decl:       self.decl.clone().unwrap_or_default(),
+      json_decl:  self.json_decl.unwrap_or(false),
+      renderer:   Renderer::new(self.rendering.clone()),
+      canonical:  self.canonical,
+      namespaces: bindings,
+      convention: self.convention.clone()
     }
   }
 }
 
 /// XML builder
+#[derive(Debug)]
 pub struct XmlBuilder {
-  attrkey:   String,
-  charkey:   String,
-  root_name: String,
-  decl:      Declaration,
-  writer:    Rc<RefCell<Writer<Cursor<Vec<u8>>>>>,
-  indent:    Option<Indentation>
+  xml2js:     Xml2Js,
+  decl:       Declaration,
+  json_decl:  bool,
+  renderer:   Renderer,
+  canonical:  Option<Canonicalization>,
+  namespaces: Vec<(String, String)>,
+  convention: Option<Arc<dyn Convention>>
 }
 
 impl Default for XmlBuilder {
   fn default() -> XmlBuilder {
     XmlBuilder {
-      root_name: "root".to_owned(),
-      attrkey:   "$".to_owned(),
-      charkey:   "_".to_owned(),
-      decl:      Declaration::default(),
-      writer:    Rc::new(RefCell::new(Writer::new(Cursor::new(Vec::new())))),
-      indent:    None
+      xml2js:     Xml2Js::default(),
+      decl:       Declaration::default(),
+      json_decl:  false,
+      renderer:   Renderer::new(None),
+      canonical:  None,
+      namespaces: Vec::new(),
+      convention: None
     }
   }
 }
 
-/// Tag attributes type. A vector of key, value tuples which are each byte arrays
-type TagAttrs<'a> = Vec<(&'a [u8], &'a [u8])>;
-
 impl XmlBuilder {
-  // Check if key is an attribute key
-  fn is_attrkey(&self, key: &str) -> bool {
-    self.attrkey == *key
-  }
-
-  // Check if key is a character key
-  fn is_charkey(&self, key: &str) -> bool {
-    self.charkey == *key
-  }
-
-  // Get all a attributes at node. If successful, returns a vector of (name, value) attributes
-  fn tag_attributes<'a>(&self, node: &'a JsonValue) -> Result<TagAttrs<'a>, Error> {
-    // Node should either be an object {} or a wrapped object [{}]
-    // If it's an array, unwrap it and call self recursively
-    if let Some(array) = node.as_array() {
-      if array.len() == 1 {
-        let child = array.iter().next().unwrap_or(&JsonValue::Null);
-        self.tag_attributes(child)
-      } else {
-        Ok(Vec::new())
-      }
-    } else {
-      let mut attrs = Vec::new();
-      if let Some(attrs_value) = node.get(&self.attrkey) {
-        if let Some(object) = attrs_value.as_object() {
-          for (name, value) in object {
-            let attr = value
-              .as_str()
-              .ok_or_else(|| Error::new(ErrorKind::Syntax, "Expected attribute to be a string."))?;
-            attrs.push((name.as_bytes(), attr.as_bytes()));
-          }
-        }
-      }
-      Ok(attrs)
-    }
+  // Options of the Xml2Js convention
+  pub(crate) fn options(&self) -> &XmlOptions {
+    &self.xml2js.xml
   }
 
-  // Self closing tags are elements that don't contain text or other elements
-  fn is_empty_tag(&self, node: &JsonValue) -> bool {
-    // Check if child is an empty string, if not recursively check it's child
-    if let Some(array) = node.as_array() {
-      if let Some(child) = array.iter().next() {
-        return child.is_string() && utils::json_is_empty(child) || self.is_empty_tag(child);
-      }
-    } else if let Some(object) = node.as_object() {
-      for (k, v) in object.iter() {
-        if !self.is_attrkey(k) && !utils::json_is_empty(v) {
-          return false;
-        }
+  // Conflicts between the configuration options
+  fn conflicts(&self) -> Vec<String> {
+    let options = self.options();
+    let mut conflicts = utils::key_conflicts(&[
+      ("attrkey", options.attrkey.as_str()),
+      ("charkey", options.charkey.as_str()),
+      ("nskey", options.nskey.as_str()),
+      ("childkey", options.childkey.as_str())
+    ]);
+    if options.root_name.is_empty() {
+      conflicts.push("root_name is empty".to_owned());
+    }
+    if let Some(indentation) = self.renderer.indentation() {
+      if !matches!(indentation.indent_char, b' ' | b'\t' | b'\r' | b'\n') {
+        conflicts.push(format!("indent_char {:?} is not whitespace", char::from(indentation.indent_char)));
       }
-      return true;
     }
-    utils::json_is_empty(node)
+    if options.collisions == CollisionPolicy::Escape(String::new()) {
+      conflicts.push("collisions escape prefix is empty".to_owned());
+    }
+    conflicts
   }
 
-  // Write XML declaration
-  fn write_xml_decl(&mut self) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    writer.write_event(Event::Decl(self.decl.as_bytes_decl())).map_err(|e| e.into())
+  /// Key attributes are read from
+  pub fn attrkey(&self) -> &str {
+    &self.options().attrkey
   }
 
-  // Write element's start tag including any attributes
-  fn write_start_tag(&mut self, key: &str, node: &JsonValue) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
+  /// Key character data is read from
+  pub fn charkey(&self) -> &str {
+    &self.options().charkey
+  }
 
-    // Initialize the tag with key value
-    let mut tag = BytesStart::owned(key.to_owned(), key.len());
+  /// Key namespace objects are read from
+  pub fn nskey(&self) -> &str {
+    &self.options().nskey
+  }
 
-    // Write any attributes
-    let attributes = self.tag_attributes(node)?;
-    for attr in attributes {
-      tag.push_attribute(attr);
-    }
+  /// Key children are read from
+  pub fn childkey(&self) -> &str {
+    &self.options().childkey
+  }
 
-    // Write the tag as either empty / self-closing (<element />) or as a start tag (<element>)
-    let tag_is_empty = self.is_empty_tag(node);
-    if tag_is_empty {
-      writer.write_event(Event::Empty(tag)).map_err(|e| e.into())
-    } else {
-      writer.write_event(Event::Start(tag)).map_err(|e| e.into())
-    }
+  /// Value written as an empty element
+  pub fn empty_tag(&self) -> &str {
+    &self.options().empty_tag
   }
 
-  // Write text
-  fn write_text(&mut self, text: &str) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    let text_content = BytesText::from_plain_str(text);
-    writer.write_event(Event::Text(text_content)).map_err(|e| e.into())
+  /// Name of the document element
+  pub fn root_name(&self) -> &str {
+    &self.options().root_name
   }
 
-  // Write element's end tag if the element wasn't self-closing
-  fn write_end_tag(&mut self, key: &str, node: &JsonValue) -> Result<(), Error> {
-    // If the tag was self-closing, do not write an end tag
-    if self.is_empty_tag(node) {
-      return Ok(());
-    }
+  /// XML declaration
+  pub fn decl(&self) -> &Declaration {
+    &self.decl
+  }
 
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    let tag_end = BytesEnd::owned(key.as_bytes().into());
-    writer.write_event(Event::End(tag_end)).map_err(|e| e.into())
+  /// Whether only the declaration held in the JSON is written
+  pub fn json_decl(&self) -> bool {
+    self.json_decl
   }
 
-  // Write a string without triggering any indentation heuristics
-  fn write_raw(&mut self, value: &str) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    writer.write(value.as_bytes()).map_err(|e| e.into())
+  /// Rendering indentation options, `None` for compact output
+  pub fn rendering(&self) -> Option<&Indentation> {
+    self.renderer.indentation()
   }
 
-  // Write an indentation. Used when quick-xml's indentation heuristic doesn't
-  // have the context to properly indent
-  fn write_indent(&mut self) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    writer.write_indent().map_err(|e| e.into())
+  /// Canonical XML flavour, if canonical output is written
+  pub fn canonicalization(&self) -> Option<Canonicalization> {
+    self.canonical
   }
 
-  /// Write end of file
-  fn write_eof(&mut self) -> Result<(), Error> {
-    let mut writer_ref = self.writer.borrow_mut();
-    let writer = writer_ref.deref_mut();
-    writer.write_event(Event::Eof).map_err(|e| e.into())
+  /// Policy for keys which aren't valid XML names
+  pub fn name_policy(&self) -> &NamePolicy {
+    &self.options().names
   }
 
-  // A leaf node is an object that contains no keys apart from attrkey or charkey
-  fn is_leaf_node(&self, node: &JsonMap<String, JsonValue>) -> bool {
-    let normal_keys: Vec<&str> = node
-      .iter()
-      .filter(|&(k, _)| !self.is_charkey(k) && !self.is_attrkey(k))
-      .map(|(k, _)| k.as_ref())
-      .collect();
-    if normal_keys.is_empty() {
-      return true;
-    }
-    false
-  }
-
-  // Recursively traverse JSON while writing XML
-  fn traverse(&mut self, node: &JsonValue, parent_key: Option<String>) -> Result<(), Error> {
-    if let Some(object) = node.as_object() {
-      // Iterate over child object elements
-      for (key, child) in object {
-        // Traverse if the parent is not an attribute and not a character key
-        let pk = parent_key.clone().unwrap_or_else(|| "".to_owned());
-        if !self.is_attrkey(&pk) && !self.is_charkey(&pk) {
-          if self.is_charkey(&key) {
-            if self.indent.is_some() && !self.is_leaf_node(object) {
-              if let Some(s) = child.as_str() {
-                // Write indentation for a case quick-xml's auto-indent heuristic doesn't cover
-                self.write_indent()?;
-                self.write_raw(s)?;
-              }
-            } else {
-              self.traverse(child, Some(key.to_owned()))?;
-            }
-          }
-          // If we're not at an attribute and child is an object, write start tag, traverse and continue
-          else if !self.is_attrkey(&key) {
-            if !child.is_array() {
-              self.write_start_tag(key, child)?;
-              self.traverse(child, None)?;
-              self.write_end_tag(key, child)?;
-            } else {
-              self.traverse(child, Some(key.to_owned()))?;
-            }
-            continue;
-          } else {
-            self.traverse(child, Some(key.to_owned()))?;
-          }
-        }
-      }
-    } else if let Some(array) = node.as_array() {
-      // Iterate over child array elements
-      for child in array {
-        if let Some(ref pk) = parent_key.as_ref() {
-          self.write_start_tag(pk, child)?;
-          self.traverse(child, None)?;
-          self.write_end_tag(pk, child)?;
-        } else {
-          self.traverse(child, None)?;
-        }
-      }
-    } else {
-      let node_s = utils::to_string_raw(node);
-      if !node_s.is_empty() {
-        self.write_text(&node_s)?;
-      }
-    }
+  /// Typed JSON value options
+  pub fn values(&self) -> &ValuePolicy {
+    &self.options().values
+  }
 
-    Ok(())
+  /// Policy for names that collide with `attrkey` or `charkey`
+  pub fn collisions(&self) -> &CollisionPolicy {
+    &self.options().collisions
   }
 
-  /// Build XML from a JSON value
-  pub fn build_from_json(&mut self, root: &JsonValue) -> Result<String, Error> {
-    // As per node-xml2js - if the root name "root" is used, then it is not added to the produced xml
-    // document. It's unclear if this is a bug or not. Keeping this behavior for now for parity reasons
-    let explicit_root = self.root_name != *"root" || utils::json_object_key_len(root) > 1;
-    let root_name = self.root_name.clone();
+  /// Prefix of merged attribute names
+  pub fn attr_prefix(&self) -> &str {
+    &self.options().attr_prefix
+  }
 
-    self.write_xml_decl()?;
+  /// Keys written as attributes
+  pub fn attr_hints(&self) -> &[String] {
+    &self.options().attr_hints
+  }
 
-    // If an explicit root is set, write that before the root defined in JSON
-    if explicit_root {
-      self.write_start_tag(&root_name, root)?;
+  /// Namespace prefix bindings
+  pub fn namespaces(&self) -> &[(String, String)] {
+    &self.namespaces
+  }
+
+  /// Mapping convention, the [Xml2Js] convention configured by this builder unless another one
+  /// is set
+  ///
+  /// [Xml2Js]: crate::Xml2Js
+  pub fn convention(&self) -> &dyn Convention {
+    match self.convention {
+      Some(ref convention) => convention.as_ref(),
+      None => &self.xml2js
     }
+  }
 
-    self.traverse(root, Some(root_name.clone()))?;
+  // Check if XML is built with the Xml2Js convention configured by this builder
+  pub(crate) fn is_xml2js(&self) -> bool {
+    self.convention.is_none()
+  }
 
-    if explicit_root {
-      self.write_end_tag(&root_name, root)?;
+  /// Build XML from a JSON value
+  pub fn build_from_json(&mut self, root: &JsonValue) -> Result<String, Error> {
+    let nodes = self.convention().to_xml(root)?;
+    self.render(nodes)
+  }
+
+  // Write out the top level nodes of a document into `writer`, with namespace prefixes resolved.
+  // The names built by conventions other than Xml2Js are checked first: invalid names are escaped
+  // with NamePolicy::Escape and rejected otherwise, since they are never written verbatim
+  pub(crate) fn write<W: Write>(&self, writer: &mut W, mut nodes: Vec<Content>) -> Result<(), Error> {
+    let policy = match self.options().names {
+      NamePolicy::Escape => NamePolicy::Escape,
+      _ => NamePolicy::Reject
+    };
+    let resolver = NamespaceResolver::new(&self.namespaces);
+    for node in nodes.iter_mut() {
+      if let Content::Element(e) = node {
+        if !self.is_xml2js() {
+          check_names(&policy, e, "")?;
+        }
+        resolver.resolve(e);
+      }
     }
 
-    self.write_eof()?;
+    match self.canonical {
+      Some(c) => c.write(writer, &nodes)?,
+      None if self.json_decl => self.renderer.write(writer, None, &nodes)?,
+      None => self.renderer.write(writer, Some(&self.decl), &nodes)?
+    }
+    Ok(())
+  }
 
-    let mut writer_guard = self.writer.borrow_mut();
-    let writer_ref = writer_guard.deref_mut();
-    let result = writer_ref.inner().get_ref();
-    String::from_utf8(result.to_vec()).map_err(|e| e.into())
+  // Write out the top level nodes of a document as a string
+  pub(crate) fn render(&self, nodes: Vec<Content>) -> Result<String, Error> {
+    let mut buffer = Vec::new();
+    self.write(&mut buffer, nodes)?;
+    Ok(String::from_utf8(buffer)?)
   }
 
   /// Build XML from a JSON string
@@ -503,6 +1032,39 @@ impl XmlBuilder {
   }
 }
 
+// Apply `policy` to the names of `element`, its attributes and its descendants, given as their
+// path in the document. The names must also be qualified names: one colon at most, between a
+// prefix and a local part, and none in the local part of Clark notation names
+fn check_names(policy: &NamePolicy, element: &mut Element, parent: &str) -> Result<(), Error> {
+  let path = format!("{}/{}", parent, element.name);
+  element.name = check_qname(policy, mem::take(&mut element.name), &path)?;
+  for (name, _) in element.attrs.iter_mut() {
+    let attr_path = format!("{}/@{}", path, name);
+    *name = check_qname(policy, mem::take(name), &attr_path)?;
+  }
+  for child in element.children.iter_mut() {
+    if let Content::Element(e) = child {
+      check_names(policy, e, &path)?;
+    }
+  }
+  Ok(())
+}
+
+fn check_qname(policy: &NamePolicy, name: String, path: &str) -> Result<String, Error> {
+  let name = check_name(policy, name, path)?;
+  let qualified = match namespace::parse_clark(&name) {
+    Some((_, local)) => !local.contains(':'),
+    None => match name.find(':') {
+      Some(i) => i > 0 && i + 1 < name.len() && !name[i + 1 ..].contains(':'),
+      None => true
+    }
+  };
+  if !qualified {
+    return Err(Error::new(ErrorKind::Syntax, format!("Invalid XML name {:?} at {}", name, path)));
+  }
+  Ok(name)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -518,146 +1080,496 @@ mod tests {
   }
 
   #[test]
-  fn leaf_node1() {
-    let builder = XmlBuilder::default();
-    let node = json!({});
-    let is_leaf = builder.is_leaf_node(&node.as_object().unwrap());
-    assert!(is_leaf);
+  fn rendering_inline_text_false() {
+    let mut builder = XmlConfig::new().rendering(Indentation::new(b' ', 2).inline_text(false)).finalize();
+    let xml = builder.build_from_json(&json!({"a":{"b":"text"}})).unwrap();
+    assert_eq!(xml, "<?xml version=\"1.0\"?>\n<a>\n  <b>\n    text\n  </b>\n</a>");
   }
 
   #[test]
-  fn leaf_node2() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"{"$": {}, "_": {}}"#).unwrap();
-    let is_leaf = builder.is_leaf_node(&node);
-    assert!(is_leaf);
+  fn rendering_attr_wrap_count() {
+    let mut builder = XmlConfig::new()
+      .rendering(Indentation::new(b' ', 2).attr_wrap(AttrWrap::Count(1)))
+      .finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"$":{"x":"1"},"b":{"$":{"x":"1","y":"2"},"_":"text"}}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      "<?xml version=\"1.0\"?>\n<a x=\"1\">\n  <b\n    x=\"1\"\n    y=\"2\">text</b>\n</a>"
+    );
   }
 
   #[test]
-  fn leaf_node3() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"{"a": {}}"#).unwrap();
-    let is_leaf = builder.is_leaf_node(&node);
-    assert!(!is_leaf);
+  fn rendering_attr_wrap_width() {
+    let mut builder = XmlConfig::new()
+      .rendering(Indentation::new(b' ', 2).attr_wrap(AttrWrap::Width(16)))
+      .finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"$":{"x":"1"},"b":{"$":{"x":"1","y":"2"}}}}))
+      .unwrap();
+    assert_eq!(xml, "<?xml version=\"1.0\"?>\n<a x=\"1\">\n  <b\n    x=\"1\"\n    y=\"2\"/>\n</a>");
   }
 
   #[test]
-  fn leaf_node4() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"{"$": {}, "_": {}, "a": "b"}"#).unwrap();
-    let is_leaf = builder.is_leaf_node(&node);
-    assert!(!is_leaf);
+  fn rendering_space_before_slash_final_newline() {
+    let mut builder = XmlConfig::new()
+      .rendering(Indentation::new(b' ', 2).space_before_slash(true).final_newline(true))
+      .finalize();
+    let xml = builder.build_from_json(&json!({"a":{"b":"","c":"d"}})).unwrap();
+    assert_eq!(xml, "<?xml version=\"1.0\"?>\n<a>\n  <b />\n  <c>d</c>\n</a>\n");
   }
 
   #[test]
-  fn empty_tag1() {
-    let builder = XmlBuilder::default();
-    let node = json!({});
-    let is_empty = builder.is_empty_tag(&node);
-    assert!(is_empty);
+  fn build_twice() {
+    let mut builder = XmlBuilder::default();
+    let first = builder.build_from_json_string(r#"{"foo":"bar"}"#).unwrap();
+    let second = builder.build_from_json_string(r#"{"foo":"bar"}"#).unwrap();
+    assert_eq!(first, second);
   }
 
   #[test]
-  fn empty_tag2() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"}}]"#).unwrap();
-    let is_empty = builder.is_empty_tag(&node);
-    assert!(is_empty);
+  fn rendering_mixed_content() {
+    let mut builder = XmlConfig::new().rendering(Indentation::new(b' ', 2)).finalize();
+    let xml = builder
+      .build_from_json(&json!({"p":{"a":{"_":"x < y ","b":{"c":"d"}},"e":"f"}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      "<?xml version=\"1.0\"?>\n<p>\n  <a>x &lt; y <b><c>d</c></b></a>\n  <e>f</e>\n</p>"
+    );
   }
 
   #[test]
-  fn empty_tag3() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"{"$":{"desc":"nodata"},"_":""}"#).unwrap();
-    let is_empty = builder.is_empty_tag(&node);
-    assert!(is_empty);
+  fn rendering_xml_space_preserve() {
+    let mut builder = XmlConfig::new().rendering(Indentation::new(b' ', 2)).finalize();
+    let json = json!({"p":{"a":{"$":{"xml:space":"preserve"},"b":{"c":"d"},"e":{"$":{"xml:space":"default"},"f":"g"}}}});
+    let xml = builder.build_from_json(&json).unwrap();
+    assert_eq!(
+      xml,
+      "<?xml version=\"1.0\"?>\n<p>\n  <a xml:space=\"preserve\"><b><c>d</c></b><e xml:space=\"default\">\n      <f>g</f>\n    \
+       </e></a>\n</p>"
+    );
   }
 
   #[test]
-  fn empty_tag4() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"},"_":"A"}]"#).unwrap();
-    let is_empty = builder.is_empty_tag(&node);
-    assert!(!is_empty);
+  fn namespace_clark_configured() {
+    let mut builder = XmlConfig::new().namespace("a", "urn:a").finalize();
+    let xml = builder
+      .build_from_json(&json!({"{urn:a}x":{"$":{"{urn:a}y":"1","z":"2"},"{urn:a}w":"v"}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a:x xmlns:a="urn:a" a:y="1" z="2"><a:w>v</a:w></a:x>"#);
   }
 
   #[test]
-  fn empty_tag5() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"[{"$":{"desc":"nodata"},"A":{"B":"C"}}]"#).unwrap();
-    let is_empty = builder.is_empty_tag(&node);
-    assert!(!is_empty);
+  fn namespace_clark_generated() {
+    let mut builder = XmlConfig::new().finalize();
+    let xml = builder
+      .build_from_json(&json!({"x":{"$":{"xmlns:ns0":"urn:taken"},"{urn:a}w":"v","{urn:b}w":"v"}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><x xmlns:ns1="urn:a" xmlns:ns2="urn:b" xmlns:ns0="urn:taken"><ns1:w>v</ns1:w><ns2:w>v</ns2:w></x>"#
+    );
   }
 
   #[test]
-  fn tag_attributes1() {
-    let builder = XmlBuilder::default();
-    let node = json!({});
-    let attrs = builder.tag_attributes(&node).unwrap();
-    assert!(attrs.is_empty());
+  fn namespace_default() {
+    let mut builder = XmlConfig::new().namespace("", "urn:a").finalize();
+    let xml = builder
+      .build_from_json(&json!({"{urn:a}x":{"{urn:a}y":"1","{urn:b}z":{"$":{"{urn:a}q":"2"},"{}n":"3"}}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><x xmlns="urn:a" xmlns:ns0="urn:b" xmlns:ns1="urn:a"><y>1</y><ns0:z ns1:q="2"><n xmlns="">3</n></ns0:z></x>"#
+    );
   }
 
   #[test]
-  fn tag_attributes2() {
-    let builder = XmlBuilder::default();
-    let node = serde_json::from_str(r#"{"$":{"A":"B","C":"D"}}"#).unwrap();
-    let attrs = builder.tag_attributes(&node).unwrap();
-    assert!(!attrs.is_empty());
-    let mut expected = vec![("A", "B"), ("C", "D")];
-    expected.reverse(); // lazy alternative to a veqdeque
-    for attr in attrs {
-      let (k, v) = attr;
-      let a_key = std::str::from_utf8(k).unwrap();
-      let a_val = std::str::from_utf8(v).unwrap();
-      let (e_key, e_val) = expected.pop().unwrap();
-      assert_eq!(e_key, a_key);
-      assert_eq!(e_val, a_val);
-    }
+  fn namespace_declared_prefix() {
+    let mut builder = XmlConfig::new().finalize();
+    let xml = builder
+      .build_from_json(&json!({"p:x":{"$":{"xmlns:p":"urn:p"},"{urn:p}y":"1"}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><p:x xmlns:p="urn:p"><p:y>1</p:y></p:x>"#);
   }
 
   #[test]
-  fn tag_attributes3() {
-    let builder = XmlConfig::new().attrkey("^").finalize();
-    let node = serde_json::from_str(r#"{"^":{"A":"B","C":"D"}}"#).unwrap();
-    let attrs = builder.tag_attributes(&node).unwrap();
-    assert!(!attrs.is_empty());
-    let mut expected = vec![("A", "B"), ("C", "D")];
-    expected.reverse(); // lazy alternative to a veqdeque
-    for attr in attrs {
-      let (k, v) = attr;
-      let a_key = std::str::from_utf8(k).unwrap();
-      let a_val = std::str::from_utf8(v).unwrap();
-      let (e_key, e_val) = expected.pop().unwrap();
-      assert_eq!(e_key, a_key);
-      assert_eq!(e_val, a_val);
-    }
+  fn namespace_shadowed_prefix() {
+    // `p` is bound to another namespace deeper in the tree, so it can't be declared on the root
+    let mut builder = XmlConfig::new().finalize();
+    let xml = builder
+      .build_from_json(&json!({"x":{"$":{"xmlns:p":"urn:p"},"y":{"$":{"xmlns:p":"urn:q"},"{urn:p}z":"1"}}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><x xmlns:ns0="urn:p" xmlns:p="urn:p"><y xmlns:p="urn:q"><ns0:z>1</ns0:z></y></x>"#
+    );
+
+    // A configured prefix of another namespace isn't reused, nor is a shadowed configured prefix
+    let mut builder = XmlConfig::new().namespace("p", "urn:q").namespace("r", "urn:r").finalize();
+    let xml = builder
+      .build_from_json(&json!({"x":{"$":{"xmlns:p":"urn:p"},"{urn:p}y":"1","z":{"$":{"xmlns:r":"urn:s"},"{urn:r}w":"2"}}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><x xmlns:ns0="urn:p" xmlns:ns1="urn:r" xmlns:p="urn:p"><ns0:y>1</ns0:y><z xmlns:r="urn:s"><ns1:w>2</ns1:w></z></x>"#
+    );
+  }
+
+  #[test]
+  fn namespace_configured_prefix_verbatim() {
+    let mut builder = XmlConfig::new().namespace("p", "urn:p").namespace("q", "urn:q").finalize();
+    let xml = builder.build_from_json(&json!({"p:x":{"$":{"q:a":"1"},"y":"1"}})).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><p:x xmlns:p="urn:p" xmlns:q="urn:q" q:a="1"><y>1</y></p:x>"#
+    );
+  }
+
+  #[test]
+  fn namespace_nskey() {
+    // Shape of node-xml2js output with `xmlns: true`
+    let json = json!({"pfx:top":{
+      "$":{
+        "xmlns:pfx":{"name":"xmlns:pfx","value":"http://foo.com","prefix":"xmlns","local":"pfx","uri":"http://www.w3.org/2000/xmlns/"},
+        "pfx:attr":{"name":"pfx:attr","value":"baz","prefix":"pfx","local":"attr","uri":"http://foo.com"}
+      },
+      "$ns":{"local":"top","uri":"http://foo.com"},
+      "middle":[{"$ns":{"local":"middle","uri":"http://foo.com"}}]
+    }});
+    let mut builder = XmlConfig::new().finalize();
+    let xml = builder.build_from_json(&json).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><pfx:middle/></pfx:top>"#
+    );
+  }
+
+  #[test]
+  fn canonical_output() {
+    let mut builder = XmlConfig::new()
+      .canonicalization(Canonicalization::Inclusive)
+      .rendering(Indentation::default())
+      .finalize();
+    let xml = builder
+      .build_from_json_string(r#"{"a":{"$":{"z":"x\ny","b":"2"},"c":"","d":["<&>"]}}"#)
+      .unwrap();
+    assert_eq!(xml, r#"<a b="2" z="x&#xA;y"><c></c><d>&lt;&amp;&gt;</d></a>"#);
+  }
+
+  #[test]
+  fn canonical_namespaces() {
+    let json = r#"{"{urn:a}a":{"{urn:b}b":{"$":{"{urn:c}x":"1","y":"2"}}}}"#;
+    let mut builder = XmlConfig::new()
+      .namespace("", "urn:a")
+      .canonicalization(Canonicalization::Inclusive)
+      .finalize();
+    assert_eq!(
+      builder.build_from_json_string(json).unwrap(),
+      r#"<a xmlns="urn:a" xmlns:ns0="urn:b" xmlns:ns1="urn:c"><ns0:b y="2" ns1:x="1"></ns0:b></a>"#
+    );
+
+    let mut builder = XmlConfig::new()
+      .namespace("", "urn:a")
+      .canonicalization(Canonicalization::Exclusive)
+      .finalize();
+    assert_eq!(
+      builder.build_from_json_string(json).unwrap(),
+      r#"<a xmlns="urn:a"><ns0:b xmlns:ns0="urn:b" xmlns:ns1="urn:c" y="2" ns1:x="1"></ns0:b></a>"#
+    );
+  }
+
+  #[test]
+  fn name_policy_verbatim() {
+    let mut builder = XmlBuilder::default();
+    let xml = builder.build_from_json_string(r#"{"a":{"first name":"b"}}"#).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a><first name>b</first name></a>"#);
   }
 
   #[test]
-  fn attrkey1() {
-    let builder = XmlBuilder::default();
-    let is_key = builder.is_attrkey(&"$".to_owned());
-    assert!(is_key);
+  fn name_policy_reject() {
+    let mut builder = XmlConfig::new().name_policy(NamePolicy::Reject).finalize();
+    let error = builder
+      .build_from_json_string(r#"{"a":{"b":[{"c":"d"},{"2021":"e"}]}}"#)
+      .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Syntax);
+    assert_eq!(error.details(), r#"Invalid XML name "2021" at /a/b/1/2021"#);
+
+    let error = builder.build_from_json_string(r#"{"a":{"$":{"a<b":"c"}}}"#).unwrap_err();
+    assert_eq!(error.details(), r#"Invalid XML name "a<b" at /a/$/a<b"#);
+
+    let error = builder.build_from_json_string(r#"{"a":{"x/y z":"c"}}"#).unwrap_err();
+    assert_eq!(error.details(), r#"Invalid XML name "x/y z" at /a/x~1y z"#);
+
+    let xml = builder
+      .build_from_json_string(r#"{"soap:Envelope":{"$":{"xml:lang":"en"},"_":"a"}}"#)
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><soap:Envelope xml:lang="en">a</soap:Envelope>"#);
   }
 
   #[test]
-  fn attrkey2() {
-    let builder = XmlConfig::new().attrkey("^").finalize();
-    assert!(builder.is_attrkey(&"^".to_owned()));
-    assert!(!builder.is_attrkey(&"$".to_owned()));
+  fn name_policy_escape() {
+    let mut builder = XmlConfig::new().name_policy(NamePolicy::Escape).finalize();
+    let xml = builder
+      .build_from_json_string(r#"{"a":{"$":{"a<b":"c"},"2021":"d","_x0020_":"e","{urn:x}x y":"f"}}"#)
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><a xmlns:ns0="urn:x" a_x003C_b="c"><_x0032_021>d</_x0032_021><_x005F_x0020_>e</_x005F_x0020_><ns0:x_x0020_y>f</ns0:x_x0020_y></a>"#
+    );
+
+    assert!(builder.build_from_json_string(r#"{"a":{"":"b"}}"#).is_err());
   }
 
   #[test]
-  fn charkey1() {
-    let builder = XmlBuilder::default();
-    let is_key = builder.is_charkey(&"_".to_owned());
-    assert!(is_key);
+  fn values_default() {
+    let mut builder = XmlBuilder::default();
+    let xml = builder.build_from_json(&json!({"a":{"b":null,"c":[[1,2],[3]]}})).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a><b>null</b><c>12</c><c>3</c></a>"#);
+
+    let error = builder.build_from_json(&json!({"a":{"$":{"b":1}}})).unwrap_err();
+    assert_eq!(error.details(), "Expected attribute to be a string.");
   }
 
   #[test]
-  fn charkey2() {
-    let builder = XmlConfig::new().charkey("^").finalize();
-    assert!(builder.is_charkey(&"^".to_owned()));
-    assert!(!builder.is_charkey(&"_".to_owned()));
+  fn values_scalar_attrs() {
+    let mut builder = XmlConfig::new().values(ValuePolicy::new().scalar_attrs(true)).finalize();
+    let xml = builder.build_from_json(&json!({"a":{"$":{"b":1.5,"c":false,"d":null}}})).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a b="1.5" c="false" d="null"/>"#);
+
+    let error = builder.build_from_json(&json!({"a":{"$":{"b":[1]}}})).unwrap_err();
+    assert_eq!(error.details(), "Expected attribute to be a string.");
+  }
+
+  #[test]
+  fn values_null_omit() {
+    let mut builder = XmlConfig::new().values(ValuePolicy::new().null(NullPolicy::Omit)).finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"$":{"x":null},"b":null,"c":[null,"d"],"_":null}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a><c>d</c></a>"#);
+  }
+
+  #[test]
+  fn values_null_empty() {
+    let mut builder = XmlConfig::new().values(ValuePolicy::new().null(NullPolicy::Empty)).finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"$":{"x":null},"b":null,"c":[null,"d"]}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a x=""><b/><c/><c>d</c></a>"#);
+  }
+
+  #[test]
+  fn values_null_nil() {
+    let mut builder = XmlConfig::new().values(ValuePolicy::new().null(NullPolicy::Nil)).finalize();
+    let xml = builder.build_from_json(&json!({"a":{"$":{"x":null},"b":null}})).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><a xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance"><b xsi:nil="true"/></a>"#
+    );
+
+    let mut builder = XmlConfig::new()
+      .namespace("i", "http://www.w3.org/2001/XMLSchema-instance")
+      .values(ValuePolicy::new().null(NullPolicy::Nil))
+      .finalize();
+    let xml = builder.build_from_json(&json!({"a":{"b":null}})).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><a xmlns:i="http://www.w3.org/2001/XMLSchema-instance"><b i:nil="true"/></a>"#
+    );
+  }
+
+  #[test]
+  fn values_array_item() {
+    let mut builder = XmlConfig::new().values(ValuePolicy::new().array_item("item")).finalize();
+    let xml = builder.build_from_json(&json!({"a":{"b":[[1,[2,3]],[]],"c":[4]}})).unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><a><b><item>1</item><item><item>2</item><item>3</item></item></b><b/><c>4</c></a>"#
+    );
+  }
+
+  #[test]
+  fn collisions_escape() {
+    let mut builder = XmlConfig::new()
+      .attrkey("attrs")
+      .collisions(CollisionPolicy::Escape("~".to_owned()))
+      .finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"attrs":{"x":"y"},"~attrs":["b"],"~_":["","c"],"_x":[""],"~~c":"d"}}))
+      .unwrap();
+    assert_eq!(
+      xml,
+      r#"<?xml version="1.0"?><a x="y"><attrs>b</attrs><_/><_>c</_><_x/><~c>d</~c></a>"#
+    );
+  }
+
+  #[test]
+  fn collisions_reject() {
+    let mut builder = XmlConfig::new().collisions(CollisionPolicy::Reject).finalize();
+    let error = builder.build_from_json(&json!({"a":{"b":{"$":"text"}}})).unwrap_err();
+    assert_eq!(error.details(), "Expected an object of attributes at /a/b/$.");
+
+    let error = builder.build_from_json(&json!({"a":{"_":["b",{"c":"d"}]}})).unwrap_err();
+    assert_eq!(error.details(), "Expected character data at /a/_.");
+
+    let xml = builder.build_from_json(&json!({"a":{"$":{"x":"y"},"_":["b",1]}})).unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a x="y">b1</a>"#);
+  }
+
+  #[test]
+  fn invalid_comment() {
+    let mut builder = XmlConfig::lossless().finalize();
+    let error = builder
+      .build_from_json(&json!({"a":{"$$":[{"#name":"__comment__","_":"b--c"}]}}))
+      .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Syntax);
+    assert_eq!(error.details(), "Invalid comment at /a/$$/0/_.");
+    assert!(builder
+      .build_from_json(&json!({"a":{"$$":[{"#name":"__comment__","_":"b-"}]}}))
+      .is_err());
+    assert!(builder
+      .build_from_json(&json!({"a":{"$$":[{"#name":"__comment__","_":"b - c"}]}}))
+      .is_ok());
+  }
+
+  #[test]
+  fn invalid_instruction_and_doctype() {
+    let mut builder = XmlConfig::lossless().finalize();
+    let node = |name, text| json!({"$$":[{"#name":name,"_":text},{"#name":"a"}]});
+    let error = builder.build_from_json(&node("__instruction__", "x a?><evil/><?y")).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Syntax);
+    assert_eq!(error.details(), "Invalid processing instruction at /$$/0/_.");
+    assert!(builder.build_from_json(&node("__instruction__", "xml version=\"1.0\"")).is_err());
+    assert!(builder.build_from_json(&node("__instruction__", " a")).is_err());
+    let xml = builder
+      .build_from_json(&node("__instruction__", "xml-stylesheet href=\"a?b\""))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml-stylesheet href="a?b"?><a/>"#);
+
+    let error = builder
+      .build_from_json(&node("__doctype__", "a [<!ENTITY x \"y\">]> <evil"))
+      .unwrap_err();
+    assert_eq!(error.details(), "Invalid doctype at /$$/0/_.");
+    assert!(builder.build_from_json(&node("__doctype__", "a SYSTEM \"a.dtd")).is_err());
+    assert!(builder.build_from_json(&node("__doctype__", "a [<!ENTITY x \"y\">")).is_err());
+    assert!(builder.build_from_json(&node("__doctype__", "<a")).is_err());
+    let doctype = r#"a [<!ENTITY x "]>"><!-- '> --><?p ]>?>]"#;
+    let xml = builder.build_from_json(&node("__doctype__", doctype)).unwrap();
+    assert_eq!(xml, format!("<!DOCTYPE {}><a/>", doctype));
+  }
+
+  #[test]
+  fn empty_tag() {
+    let mut builder = XmlConfig::new().empty_tag("EMPTY").finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"b":"EMPTY","c":["EMPTY","d"],"_":"EMPTY"}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a><b/><c/><c>d</c>EMPTY</a>"#);
+  }
+
+  #[test]
+  fn attr_prefix() {
+    let mut builder = XmlConfig::new().attr_prefix("@").finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"@id":["1"],"id":["2"],"b":[{"@x":"y","_":"c"}]}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a id="1"><id>2</id><b x="y">c</b></a>"#);
+
+    let error = builder.build_from_json(&json!({"a":{"@id":["1","2"]}})).unwrap_err();
+    assert_eq!(error.details(), "Expected attribute to be a string.");
+  }
+
+  #[test]
+  fn attr_hint() {
+    let mut builder = XmlConfig::new().attr_hint("id").finalize();
+    let xml = builder
+      .build_from_json(&json!({"a":{"$":{"x":"y"},"id":["1"],"b":{"id":"2","_":"c"}}}))
+      .unwrap();
+    assert_eq!(xml, r#"<?xml version="1.0"?><a x="y" id="1"><b id="2">c</b></a>"#);
+  }
+
+  #[test]
+  fn try_finalize() {
+    assert!(XmlConfig::new().try_finalize().is_ok());
+    assert!(XmlConfig::new().rendering(Indentation::new(b'\t', 1)).try_finalize().is_ok());
+
+    let error = XmlConfig::new()
+      .attrkey("_")
+      .nskey("$$")
+      .root_name("")
+      .collisions(CollisionPolicy::Escape("".to_owned()))
+      .try_finalize()
+      .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+    assert_eq!(
+      error.details(),
+      r#"attrkey and charkey are both "_"; nskey and childkey are both "$$"; root_name is empty; collisions escape prefix is empty"#
+    );
+  }
+
+  #[test]
+  fn getters() {
+    let builder = XmlConfig::new()
+      .root_name("a")
+      .rendering(Indentation::new(b'\t', 1))
+      .namespace("p", "urn:p")
+      .finalize();
+    assert_eq!(builder.root_name(), "a");
+    assert_eq!(builder.attrkey(), "$");
+    assert_eq!(builder.rendering().map(|i| i.indent_char), Some(b'\t'));
+    assert_eq!(builder.canonicalization(), None);
+    assert_eq!(builder.namespaces(), &[("p".to_owned(), "urn:p".to_owned())]);
+  }
+
+  #[test]
+  fn config_from_json() {
+    let mut config = XmlConfig::new();
+    config
+      .decl(Declaration::new(Version::XML11, Some(Encoding::UTF8), Some(true)))
+      .canonicalization(Canonicalization::Exclusive)
+      .name_policy(NamePolicy::Escape)
+      .rendering(Indentation::new(b'\t', 1))
+      .attr_hint("id");
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(json["decl"], json!({"version":"1.1","encoding":"UTF-8","standalone":true}));
+    assert_eq!(json["canonicalization"], "exclusive");
+    assert_eq!(json["name_policy"], "escape");
+    assert_eq!(json["rendering"]["indent_char"], "\t");
+
+    let builder = XmlConfig::from_json_str(&json.to_string()).unwrap().finalize();
+    assert_eq!(builder.canonicalization(), Some(Canonicalization::Exclusive));
+    assert_eq!(builder.rendering().map(|i| i.indent_char), Some(b'\t'));
+    assert_eq!(builder.attr_hints(), &["id".to_owned()]);
+
+    let error = XmlConfig::from_json_str(r#"{"rendering": {"indent": 2}}"#).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::Config);
+    assert!(error.details().starts_with("unknown field `indent`, expected one of `indent_char`"));
+
+    let error = XmlConfig::from_json_str(r#"{"rendering": {"indent_char": "  "}}"#).unwrap_err();
+    assert!(error
+      .details()
+      .starts_with(r#"invalid indent_char "  ", expected one ASCII character"#));
+  }
+
+  #[test]
+  fn config_from_path() {
+    let path = std::env::temp_dir().join("xml2json-rs-xml-config.toml");
+    std::fs::write(&path, "root_name = \"doc\"\n\n[rendering]\nindent_char = \"\\t\"\n").unwrap();
+    let result = XmlConfig::from_path(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    #[cfg(feature = "toml")]
+    {
+      let builder = result.unwrap().finalize();
+      assert_eq!(builder.root_name(), "doc");
+      assert_eq!(builder.rendering().map(|i| i.indent_char), Some(b'\t'));
+    }
+    #[cfg(not(feature = "toml"))]
+    assert_eq!(
+      result.unwrap_err().details(),
+      format!("{}: TOML configurations require the `toml` feature", path.display())
+    );
   }
 }
@@ -0,0 +1,159 @@
+"""Generate xmltodict parity tests for every XML file of the test data.
+
+Usage: python3 parity.py
+"""
+
+import hashlib
+import json
+import os
+
+import xmltodict
+
+DEFAULT_OPTION_VALUES = {
+    "attr_prefix": "@",
+    "cdata_key": "#text",
+    "force_list": (),
+    "process_namespaces": False,
+    "namespaces": None,
+    "xml_attribs": True,
+    "strip_whitespace": True,
+}
+
+VARIATIONS = {
+    "attr_prefix": ["_"],
+    "cdata_key": ["_text"],
+    "force_list": [("item", "cd:artist")],
+    "process_namespaces": [True],
+    "namespaces": [{"http://www.w3.org/1999/02/22-rdf-syntax-ns#": "rdf", "http://foo.com": ""}],
+    "xml_attribs": [False],
+    "strip_whitespace": [False],
+}
+
+
+def variation_name(value):
+    if value is True:
+        return ""
+    if isinstance(value, (tuple, dict)):
+        return "custom"
+    return str(value).lower().replace("_", "").replace("#", "")
+
+
+def option_variations(fname):
+    variations = [("default", dict(DEFAULT_OPTION_VALUES))]
+    for key, values in VARIATIONS.items():
+        for value in values:
+            options = dict(DEFAULT_OPTION_VALUES)
+            options[key] = value
+            # Short names of namespaces only apply to processed namespaces
+            if key == "namespaces":
+                options["process_namespaces"] = True
+            name = "_".join(part for part in [key, variation_name(value)] if part)
+            variations.append((name, options))
+    return variations
+
+
+def rust_string(value):
+    hashes = "##" if '"#' in value else "#"
+    return 'r{0}"{1}"{0}'.format(hashes, value)
+
+
+def convention(options):
+    calls = ["XmlToDict::new()"]
+    if options["attr_prefix"] != DEFAULT_OPTION_VALUES["attr_prefix"]:
+        calls.append('.attr_prefix("{}")'.format(options["attr_prefix"]))
+    if options["cdata_key"] != DEFAULT_OPTION_VALUES["cdata_key"]:
+        calls.append('.cdata_key("{}")'.format(options["cdata_key"]))
+    for key in options["force_list"]:
+        calls.append('.force_list("{}")'.format(key))
+    if options["process_namespaces"]:
+        calls.append(".process_namespaces(true)")
+    for uri, short in (options["namespaces"] or {}).items():
+        calls.append('.namespace("{}", "{}")'.format(uri, short))
+    if not options["xml_attribs"]:
+        calls.append(".xml_attribs(false)")
+    if not options["strip_whitespace"]:
+        calls.append(".strip_whitespace(false)")
+    return "".join(calls)
+
+
+def parse(data, options):
+    return xmltodict.parse(
+        data,
+        attr_prefix=options["attr_prefix"],
+        cdata_key=options["cdata_key"],
+        force_list=options["force_list"],
+        process_namespaces=options["process_namespaces"],
+        namespaces=options["namespaces"],
+        xml_attribs=options["xml_attribs"],
+        strip_whitespace=options["strip_whitespace"],
+    )
+
+
+def write_parse_test(name, fname, options, output):
+    print("#[test]")
+    print("fn {}() {{".format(name))
+    print('  let xml = load_xml("tests/data/{}.xml");'.format(fname))
+    print("  let expected = {};".format(rust_string(output)))
+    print("")
+    print("  let json_builder = JsonConfig::new().convention({}).finalize();".format(convention(options)))
+    print("  let result = json_builder.build_string_from_xml(&xml);")
+    print("")
+    print('  let actual = result.expect("Error building JSON.");')
+    print("  assert_eq!(expected, actual);")
+    print("}")
+
+
+def write_unparse_test(name, options, json_input, output):
+    print("#[test]")
+    print("fn {}() {{".format(name))
+    print("  let json = {};".format(rust_string(json_input)))
+    print("  let expected = {};".format(rust_string('<?xml version="1.0"?>' + output)))
+    print("")
+    print("  let mut xml_builder = XmlConfig::new().convention({}).finalize();".format(convention(options)))
+    print("  let result = xml_builder.build_from_json_string(json);")
+    print("")
+    print('  let actual = result.expect("Error building XML.");')
+    print("  assert_eq!(expected, actual);")
+    print("}")
+
+
+def generate():
+    data_dir = os.path.join(os.path.dirname(os.path.abspath(__file__)), "../../data")
+    xml_files = sorted(f for f in os.listdir(data_dir) if f.endswith(".xml"))
+
+    for file_name in xml_files:
+        fname = file_name[: -len(".xml")]
+        with open(os.path.join(data_dir, file_name), encoding="utf-8") as f:
+            data = f.read()
+
+        # Only produce tests for unique JSON output, as the node generator does
+        hashes = set()
+        for name, options in option_variations(fname):
+            result = parse(data, options)
+            output = json.dumps(result, ensure_ascii=False, separators=(",", ":"))
+            digest = hashlib.sha256(output.encode("utf-8")).hexdigest()
+            if digest in hashes:
+                continue
+            hashes.add(digest)
+
+            print("")
+            write_parse_test("parse_{}_{}".format(fname, name), fname, options, output)
+            print("")
+
+            # xmltodict writes processed names as they are, which isn't well-formed XML
+            if options["process_namespaces"]:
+                continue
+            xml = xmltodict.unparse(
+                result,
+                full_document=False,
+                short_empty_elements=True,
+                attr_prefix=options["attr_prefix"],
+                cdata_key=options["cdata_key"],
+            )
+            print("")
+            write_unparse_test("unparse_{}_{}".format(fname, name), options, output, xml)
+            print("")
+
+
+if __name__ == "__main__":
+    generate()
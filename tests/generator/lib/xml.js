@@ -20,7 +20,7 @@ const defaultOptionValues = {
 const variations = {
   pretty: [false],
   indent: ["\t", ""],
-  newline: [],
+  newline: ["\r\n"],
   version: ["1.1"],
   encoding: [],
   standalone: [false],
@@ -28,6 +28,10 @@ const variations = {
   doctype: []
 };
 
+// node-xml2js breaks mixed content onto indented lines, which changes its text. Indented tests of
+// these documents are hand-written in tests/integration/xml.rs instead
+const mixedContent = ["complex", "lists"];
+
 const nestedOptsFromFlat = (opts) => {
   return {
     renderOptions: {
@@ -59,7 +63,7 @@ const getOptionVariations = (fileName) => {
   _.each(variations, (arr, key) => {
     _.each(arr, (value) => {
       let options = _.clone(defaultOptionValues);
-      let vname = _.toString(value).replace(" ", "space").replace("\t", "tab").replace(".", "");
+      let vname = _.toString(value).replace(" ", "space").replace("\t", "tab").replace("\r\n", "crlf").replace(".", "");
       if (vname === "") {
         vname = "empty";
       }
@@ -86,7 +90,11 @@ const printRustRender = (state) => {
       if(ch == "\t") {
         ch = "\\t";
       }
-      console.log("    .rendering(Indentation::new(b'" + ch + "',", len + "))");
+      let newline = "";
+      if(state.render.newline === "\r\n") {
+        newline = ".newline(Newline::CRLF)";
+      }
+      console.log("    .rendering(Indentation::new(b'" + ch + "',", len + ")" + newline + ")");
     }
   }
 };
@@ -183,6 +191,9 @@ module.exports = {
       let variations = getOptionVariations(fileName);
       let hashes = new Set();
       async.forEach(variations, (input, nextInner) => {
+        if(_.includes(mixedContent, input.fname) && input.renderOptions.pretty) {
+          return nextInner();
+        }
         let options = _.clone(input);
         let builder = new xml2js.Builder({
           rootName: input.options.rootName,
@@ -214,6 +225,9 @@ module.exports = {
         console.log("#[test]");
         console.log(`fn ${input.name}() {`);
         console.log(`  let object = load_json("tests/data/${input.fname}.json");`);
+        if(options.renderOptions.newline !== "\n") {
+          console.log(`  let expected = ${JSON.stringify(result)};`);
+        } else
         if(options.renderOptions.pretty === false) {
           console.log(`  let expected = "${result.split("\"").join("\\\"")}" ;`);
         } else {
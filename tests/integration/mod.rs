@@ -1,2 +1,3 @@
 mod json;
 mod xml;
+mod xmltodict;
@@ -1,4 +1,4 @@
-use xml2json_rs::{Declaration, Encoding, Indentation, Version, XmlConfig};
+use xml2json_rs::{Declaration, Encoding, Indentation, Newline, Version, XmlConfig};
 
 use serde_json::Value as JsonValue;
 
@@ -15,23 +15,73 @@ pub fn load_json(file_path: &str) -> JsonValue {
   serde_json::from_str(&contents).unwrap()
 }
 
-// === generated tests ===
-
+// node-xml2js breaks mixed content onto indented lines, changing its text. These expectations
+// write it inline instead, so the generator leaves out the indented tests of documents with mixed
+// content
 #[test]
-fn build_cds_default() {
-  let object = load_json("tests/data/cds.json");
+fn build_complex_default() {
+  let object = load_json("tests/data/complex.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-      <cd:artist>Death Grips</cd:artist>
-      <cd:country>USA</cd:country>
-      <cd:company>Third Worlds</cd:company>
-      <cd:price>00.00</cd:price>
-      <cd:year>2012</cd:year>
-    </rdf:Description>
-  </rdf:RDF>"#
+  <sample>
+    <simpletest>data</simpletest>
+    <simpletest2>data</simpletest2>
+    <chartest desc="Test for CHARs">Character data here!</chartest>
+    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+    <nochartest desc="No data" misc="false"/>
+    <nochildrentest desc="No data" misc="false"/>
+    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+    <listtest attr="Attribute">
+      <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+      <item>Qux.</item>
+      <item>Quux.</item>
+      <single>Single</single>
+    </listtest>
+    <arraytest>
+      <item>
+        <subitem>Baz.</subitem>
+      </item>
+      <item>
+        <subitem>Foo.</subitem>
+        <subitem>Bar.</subitem>
+      </item>
+    </arraytest>
+    <emptytest/>
+    <tagcasetest>
+      <tAg>something</tAg>
+      <TAG>something else</TAG>
+      <tag>something third</tag>
+    </tagcasetest>
+    <ordertest>
+      <one>1</one>
+      <one>4</one>
+      <two>2</two>
+      <two>5</two>
+      <three>3</three>
+      <three>6</three>
+    </ordertest>
+    <validatortest>
+      <emptyarray/>
+      <oneitemarray>
+        <item>Bar.</item>
+      </oneitemarray>
+      <numbertest>42</numbertest>
+      <stringtest>43</stringtest>
+    </validatortest>
+    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+      <middle xmlns="http://bar.com"/>
+    </pfx:top>
+    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+    <tagNameProcessTest/>
+    <valueProcessTest>some value</valueProcessTest>
+    <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+    <emptytestanother>
+  
+      </emptytestanother>
+  </sample>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -46,35 +96,69 @@ fn build_cds_default() {
 }
 
 #[test]
-fn build_cds_pretty_false() {
-  let object = load_json("tests/data/cds.json");
-  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" xmlns:cd=\"http://www.recshop.fake/cd#\"><rdf:Description rdf:about=\"http://www.recshop.fake/cd/The Money Store\"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>" ;
-
-  let mut xml_builder = XmlConfig::new()
-    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
-    .root_name("root")
-    .finalize();
-  let result = xml_builder.build_from_json(&object);
-
-  let actual = result.expect("Error building XML");
-  assert_eq!(expected, actual);
-}
-
-#[test]
-fn build_cds_indent_tab() {
-  let object = load_json("tests/data/cds.json");
+fn build_complex_indent_tab() {
+  let object = load_json("tests/data/complex.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-  	<rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-  		<cd:artist>Death Grips</cd:artist>
-  		<cd:country>USA</cd:country>
-  		<cd:company>Third Worlds</cd:company>
-  		<cd:price>00.00</cd:price>
-  		<cd:year>2012</cd:year>
-  	</rdf:Description>
-  </rdf:RDF>"#
+  <sample>
+  	<simpletest>data</simpletest>
+  	<simpletest2>data</simpletest2>
+  	<chartest desc="Test for CHARs">Character data here!</chartest>
+  	<cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+  	<cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+  	<nochartest desc="No data" misc="false"/>
+  	<nochildrentest desc="No data" misc="false"/>
+  	<whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+  	<listtest attr="Attribute">
+  		<item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+  		<item>Qux.</item>
+  		<item>Quux.</item>
+  		<single>Single</single>
+  	</listtest>
+  	<arraytest>
+  		<item>
+  			<subitem>Baz.</subitem>
+  		</item>
+  		<item>
+  			<subitem>Foo.</subitem>
+  			<subitem>Bar.</subitem>
+  		</item>
+  	</arraytest>
+  	<emptytest/>
+  	<tagcasetest>
+  		<tAg>something</tAg>
+  		<TAG>something else</TAG>
+  		<tag>something third</tag>
+  	</tagcasetest>
+  	<ordertest>
+  		<one>1</one>
+  		<one>4</one>
+  		<two>2</two>
+  		<two>5</two>
+  		<three>3</three>
+  		<three>6</three>
+  	</ordertest>
+  	<validatortest>
+  		<emptyarray/>
+  		<oneitemarray>
+  			<item>Bar.</item>
+  		</oneitemarray>
+  		<numbertest>42</numbertest>
+  		<stringtest>43</stringtest>
+  	</validatortest>
+  	<pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+  		<middle xmlns="http://bar.com"/>
+  	</pfx:top>
+  	<attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+  	<attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+  	<tagNameProcessTest/>
+  	<valueProcessTest>some value</valueProcessTest>
+  	<textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+  	<emptytestanother>
+  
+      </emptytestanother>
+  </sample>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -89,20 +173,69 @@ fn build_cds_indent_tab() {
 }
 
 #[test]
-fn build_cds_indent_empty() {
-  let object = load_json("tests/data/cds.json");
+fn build_complex_indent_empty() {
+  let object = load_json("tests/data/complex.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-  <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-  <cd:artist>Death Grips</cd:artist>
-  <cd:country>USA</cd:country>
-  <cd:company>Third Worlds</cd:company>
-  <cd:price>00.00</cd:price>
-  <cd:year>2012</cd:year>
-  </rdf:Description>
-  </rdf:RDF>"#
+  <sample>
+  <simpletest>data</simpletest>
+  <simpletest2>data</simpletest2>
+  <chartest desc="Test for CHARs">Character data here!</chartest>
+  <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+  <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+  <nochartest desc="No data" misc="false"/>
+  <nochildrentest desc="No data" misc="false"/>
+  <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+  <listtest attr="Attribute">
+  <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+  <item>Qux.</item>
+  <item>Quux.</item>
+  <single>Single</single>
+  </listtest>
+  <arraytest>
+  <item>
+  <subitem>Baz.</subitem>
+  </item>
+  <item>
+  <subitem>Foo.</subitem>
+  <subitem>Bar.</subitem>
+  </item>
+  </arraytest>
+  <emptytest/>
+  <tagcasetest>
+  <tAg>something</tAg>
+  <TAG>something else</TAG>
+  <tag>something third</tag>
+  </tagcasetest>
+  <ordertest>
+  <one>1</one>
+  <one>4</one>
+  <two>2</two>
+  <two>5</two>
+  <three>3</three>
+  <three>6</three>
+  </ordertest>
+  <validatortest>
+  <emptyarray/>
+  <oneitemarray>
+  <item>Bar.</item>
+  </oneitemarray>
+  <numbertest>42</numbertest>
+  <stringtest>43</stringtest>
+  </validatortest>
+  <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+  <middle xmlns="http://bar.com"/>
+  </pfx:top>
+  <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+  <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+  <tagNameProcessTest/>
+  <valueProcessTest>some value</valueProcessTest>
+  <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+  <emptytestanother>
+  
+      </emptytestanother>
+  </sample>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -117,25 +250,13 @@ fn build_cds_indent_empty() {
 }
 
 #[test]
-fn build_cds_version_11() {
-  let object = load_json("tests/data/cds.json");
-  let expected = indoc!(
-    r#"
-  <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
-  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-      <cd:artist>Death Grips</cd:artist>
-      <cd:country>USA</cd:country>
-      <cd:company>Third Worlds</cd:company>
-      <cd:price>00.00</cd:price>
-      <cd:year>2012</cd:year>
-    </rdf:Description>
-  </rdf:RDF>"#
-  );
+fn build_complex_newline_crlf() {
+  let object = load_json("tests/data/complex.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<sample>\r\n  <simpletest>data</simpletest>\r\n  <simpletest2>data</simpletest2>\r\n  <chartest desc=\"Test for CHARs\">Character data here!</chartest>\r\n  <cdatatest desc=\"Test for CDATA\" misc=\"true\">CDATA here!</cdatatest>\r\n  <cdatawhitespacetest desc=\"Test for CDATA with whitespace\" misc=\"true\"/>\r\n  <nochartest desc=\"No data\" misc=\"false\"/>\r\n  <nochildrentest desc=\"No data\" misc=\"false\"/>\r\n  <whitespacetest desc=\"Test for       normalizing and trimming\">Line One Line Two</whitespacetest>\r\n  <listtest attr=\"Attribute\">\r\n    <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>\r\n    <item>Qux.</item>\r\n    <item>Quux.</item>\r\n    <single>Single</single>\r\n  </listtest>\r\n  <arraytest>\r\n    <item>\r\n      <subitem>Baz.</subitem>\r\n    </item>\r\n    <item>\r\n      <subitem>Foo.</subitem>\r\n      <subitem>Bar.</subitem>\r\n    </item>\r\n  </arraytest>\r\n  <emptytest/>\r\n  <tagcasetest>\r\n    <tAg>something</tAg>\r\n    <TAG>something else</TAG>\r\n    <tag>something third</tag>\r\n  </tagcasetest>\r\n  <ordertest>\r\n    <one>1</one>\r\n    <one>4</one>\r\n    <two>2</two>\r\n    <two>5</two>\r\n    <three>3</three>\r\n    <three>6</three>\r\n  </ordertest>\r\n  <validatortest>\r\n    <emptyarray/>\r\n    <oneitemarray>\r\n      <item>Bar.</item>\r\n    </oneitemarray>\r\n    <numbertest>42</numbertest>\r\n    <stringtest>43</stringtest>\r\n  </validatortest>\r\n  <pfx:top xmlns:pfx=\"http://foo.com\" pfx:attr=\"baz\">\r\n    <middle xmlns=\"http://bar.com\"/>\r\n  </pfx:top>\r\n  <attrNameProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowercaseattr=\"lowercaseattrvalue\"/>\r\n  <attrValueProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowerCaseAttr=\"lowercaseattrvalue\"/>\r\n  <tagNameProcessTest/>\r\n  <valueProcessTest>some value</valueProcessTest>\r\n  <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>\r\n  <emptytestanother>\n\n    </emptytestanother>\r\n</sample>";
 
   let mut xml_builder = XmlConfig::new()
-    .rendering(Indentation::new(b' ', 2))
-    .decl(Declaration::new(Version::XML11, Some(Encoding::UTF8), Some(true)))
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
     .root_name("root")
     .finalize();
   let result = xml_builder.build_from_json(&object);
@@ -145,20 +266,146 @@ fn build_cds_version_11() {
 }
 
 #[test]
-fn build_cds_standalone_false() {
-  let object = load_json("tests/data/cds.json");
+fn build_complex_version_11() {
+  let object = load_json("tests/data/complex.json");
   let expected = indoc!(
     r#"
-  <?xml version="1.0" encoding="UTF-8" standalone="no"?>
-  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-      <cd:artist>Death Grips</cd:artist>
-      <cd:country>USA</cd:country>
-      <cd:company>Third Worlds</cd:company>
-      <cd:price>00.00</cd:price>
-      <cd:year>2012</cd:year>
-    </rdf:Description>
-  </rdf:RDF>"#
+  <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
+  <sample>
+    <simpletest>data</simpletest>
+    <simpletest2>data</simpletest2>
+    <chartest desc="Test for CHARs">Character data here!</chartest>
+    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+    <nochartest desc="No data" misc="false"/>
+    <nochildrentest desc="No data" misc="false"/>
+    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+    <listtest attr="Attribute">
+      <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+      <item>Qux.</item>
+      <item>Quux.</item>
+      <single>Single</single>
+    </listtest>
+    <arraytest>
+      <item>
+        <subitem>Baz.</subitem>
+      </item>
+      <item>
+        <subitem>Foo.</subitem>
+        <subitem>Bar.</subitem>
+      </item>
+    </arraytest>
+    <emptytest/>
+    <tagcasetest>
+      <tAg>something</tAg>
+      <TAG>something else</TAG>
+      <tag>something third</tag>
+    </tagcasetest>
+    <ordertest>
+      <one>1</one>
+      <one>4</one>
+      <two>2</two>
+      <two>5</two>
+      <three>3</three>
+      <three>6</three>
+    </ordertest>
+    <validatortest>
+      <emptyarray/>
+      <oneitemarray>
+        <item>Bar.</item>
+      </oneitemarray>
+      <numbertest>42</numbertest>
+      <stringtest>43</stringtest>
+    </validatortest>
+    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+      <middle xmlns="http://bar.com"/>
+    </pfx:top>
+    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+    <tagNameProcessTest/>
+    <valueProcessTest>some value</valueProcessTest>
+    <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+    <emptytestanother>
+  
+      </emptytestanother>
+  </sample>"#
+  );
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2))
+    .decl(Declaration::new(Version::XML11, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_complex_standalone_false() {
+  let object = load_json("tests/data/complex.json");
+  let expected = indoc!(
+    r#"
+  <?xml version="1.0" encoding="UTF-8" standalone="no"?>
+  <sample>
+    <simpletest>data</simpletest>
+    <simpletest2>data</simpletest2>
+    <chartest desc="Test for CHARs">Character data here!</chartest>
+    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+    <nochartest desc="No data" misc="false"/>
+    <nochildrentest desc="No data" misc="false"/>
+    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+    <listtest attr="Attribute">
+      <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+      <item>Qux.</item>
+      <item>Quux.</item>
+      <single>Single</single>
+    </listtest>
+    <arraytest>
+      <item>
+        <subitem>Baz.</subitem>
+      </item>
+      <item>
+        <subitem>Foo.</subitem>
+        <subitem>Bar.</subitem>
+      </item>
+    </arraytest>
+    <emptytest/>
+    <tagcasetest>
+      <tAg>something</tAg>
+      <TAG>something else</TAG>
+      <tag>something third</tag>
+    </tagcasetest>
+    <ordertest>
+      <one>1</one>
+      <one>4</one>
+      <two>2</two>
+      <two>5</two>
+      <three>3</three>
+      <three>6</three>
+    </ordertest>
+    <validatortest>
+      <emptyarray/>
+      <oneitemarray>
+        <item>Bar.</item>
+      </oneitemarray>
+      <numbertest>42</numbertest>
+      <stringtest>43</stringtest>
+    </validatortest>
+    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+      <middle xmlns="http://bar.com"/>
+    </pfx:top>
+    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+    <tagNameProcessTest/>
+    <valueProcessTest>some value</valueProcessTest>
+    <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+    <emptytestanother>
+  
+      </emptytestanother>
+  </sample>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -173,21 +420,70 @@ fn build_cds_standalone_false() {
 }
 
 #[test]
-fn build_cds_root_name_object() {
-  let object = load_json("tests/data/cds.json");
+fn build_complex_root_name_object() {
+  let object = load_json("tests/data/complex.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
   <object>
-    <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
-      <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
-        <cd:artist>Death Grips</cd:artist>
-        <cd:country>USA</cd:country>
-        <cd:company>Third Worlds</cd:company>
-        <cd:price>00.00</cd:price>
-        <cd:year>2012</cd:year>
-      </rdf:Description>
-    </rdf:RDF>
+    <sample>
+      <simpletest>data</simpletest>
+      <simpletest2>data</simpletest2>
+      <chartest desc="Test for CHARs">Character data here!</chartest>
+      <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
+      <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
+      <nochartest desc="No data" misc="false"/>
+      <nochildrentest desc="No data" misc="false"/>
+      <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
+      <listtest attr="Attribute">
+        <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item>
+        <item>Qux.</item>
+        <item>Quux.</item>
+        <single>Single</single>
+      </listtest>
+      <arraytest>
+        <item>
+          <subitem>Baz.</subitem>
+        </item>
+        <item>
+          <subitem>Foo.</subitem>
+          <subitem>Bar.</subitem>
+        </item>
+      </arraytest>
+      <emptytest/>
+      <tagcasetest>
+        <tAg>something</tAg>
+        <TAG>something else</TAG>
+        <tag>something third</tag>
+      </tagcasetest>
+      <ordertest>
+        <one>1</one>
+        <one>4</one>
+        <two>2</two>
+        <two>5</two>
+        <three>3</three>
+        <three>6</three>
+      </ordertest>
+      <validatortest>
+        <emptyarray/>
+        <oneitemarray>
+          <item>Bar.</item>
+        </oneitemarray>
+        <numbertest>42</numbertest>
+        <stringtest>43</stringtest>
+      </validatortest>
+      <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
+        <middle xmlns="http://bar.com"/>
+      </pfx:top>
+      <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
+      <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
+      <tagNameProcessTest/>
+      <valueProcessTest>some value</valueProcessTest>
+      <textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest>
+      <emptytestanother>
+  
+      </emptytestanother>
+    </sample>
   </object>"#
   );
 
@@ -203,20 +499,16 @@ fn build_cds_root_name_object() {
 }
 
 #[test]
-fn build_childobj_default() {
-  let object = load_json("tests/data/childobj.json");
+fn build_lists_default() {
+  let object = load_json("tests/data/lists.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-    <SOAP-ENV:Body>
-      <ns1:createWorkflowProof>
-        <SessionID>sid</SessionID>
-        <OwnerID>0</OwnerID>
-        <Hash>browns</Hash>
-      </ns1:createWorkflowProof>
-    </SOAP-ENV:Body>
-  </SOAP-ENV:Envelope>"#
+  <outer>
+    <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+    <item>Qux.</item>
+    <item>Quux.</item>
+  </outer>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -231,13 +523,20 @@ fn build_childobj_default() {
 }
 
 #[test]
-fn build_childobj_pretty_false() {
-  let object = load_json("tests/data/childobj.json");
-  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><SOAP-ENV:Envelope xmlns:SOAP-ENV=\"env\" \
-                  xmlns:ns1=\"ns1\"><SOAP-ENV:Body><ns1:createWorkflowProof><SessionID>sid</SessionID><OwnerID>0</OwnerID><Hash>browns</\
-                  Hash></ns1:createWorkflowProof></SOAP-ENV:Body></SOAP-ENV:Envelope>";
+fn build_lists_indent_tab() {
+  let object = load_json("tests/data/lists.json");
+  let expected = indoc!(
+    r#"
+  <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+  <outer>
+  	<item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+  	<item>Qux.</item>
+  	<item>Quux.</item>
+  </outer>"#
+  );
 
   let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b'\t', 1))
     .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
     .root_name("root")
     .finalize();
@@ -248,24 +547,20 @@ fn build_childobj_pretty_false() {
 }
 
 #[test]
-fn build_childobj_indent_tab() {
-  let object = load_json("tests/data/childobj.json");
+fn build_lists_indent_empty() {
+  let object = load_json("tests/data/lists.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-  	<SOAP-ENV:Body>
-  		<ns1:createWorkflowProof>
-  			<SessionID>sid</SessionID>
-  			<OwnerID>0</OwnerID>
-  			<Hash>browns</Hash>
-  		</ns1:createWorkflowProof>
-  	</SOAP-ENV:Body>
-  </SOAP-ENV:Envelope>"#
+  <outer>
+  <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+  <item>Qux.</item>
+  <item>Quux.</item>
+  </outer>"#
   );
 
   let mut xml_builder = XmlConfig::new()
-    .rendering(Indentation::new(b'\t', 1))
+    .rendering(Indentation::new(b' ', 0))
     .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
     .root_name("root")
     .finalize();
@@ -276,24 +571,14 @@ fn build_childobj_indent_tab() {
 }
 
 #[test]
-fn build_childobj_indent_empty() {
-  let object = load_json("tests/data/childobj.json");
-  let expected = indoc!(
-    r#"
-  <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-  <SOAP-ENV:Body>
-  <ns1:createWorkflowProof>
-  <SessionID>sid</SessionID>
-  <OwnerID>0</OwnerID>
-  <Hash>browns</Hash>
-  </ns1:createWorkflowProof>
-  </SOAP-ENV:Body>
-  </SOAP-ENV:Envelope>"#
-  );
+fn build_lists_newline_crlf() {
+  let object = load_json("tests/data/lists.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<outer>\r\n  <item>This is character \
+                  data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>\r\n  \
+                  <item>Qux.</item>\r\n  <item>Quux.</item>\r\n</outer>";
 
   let mut xml_builder = XmlConfig::new()
-    .rendering(Indentation::new(b' ', 0))
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
     .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
     .root_name("root")
     .finalize();
@@ -304,20 +589,16 @@ fn build_childobj_indent_empty() {
 }
 
 #[test]
-fn build_childobj_version_11() {
-  let object = load_json("tests/data/childobj.json");
+fn build_lists_version_11() {
+  let object = load_json("tests/data/lists.json");
   let expected = indoc!(
     r#"
   <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
-  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-    <SOAP-ENV:Body>
-      <ns1:createWorkflowProof>
-        <SessionID>sid</SessionID>
-        <OwnerID>0</OwnerID>
-        <Hash>browns</Hash>
-      </ns1:createWorkflowProof>
-    </SOAP-ENV:Body>
-  </SOAP-ENV:Envelope>"#
+  <outer>
+    <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+    <item>Qux.</item>
+    <item>Quux.</item>
+  </outer>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -332,20 +613,16 @@ fn build_childobj_version_11() {
 }
 
 #[test]
-fn build_childobj_standalone_false() {
-  let object = load_json("tests/data/childobj.json");
+fn build_lists_standalone_false() {
+  let object = load_json("tests/data/lists.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="no"?>
-  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-    <SOAP-ENV:Body>
-      <ns1:createWorkflowProof>
-        <SessionID>sid</SessionID>
-        <OwnerID>0</OwnerID>
-        <Hash>browns</Hash>
-      </ns1:createWorkflowProof>
-    </SOAP-ENV:Body>
-  </SOAP-ENV:Envelope>"#
+  <outer>
+    <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+    <item>Qux.</item>
+    <item>Quux.</item>
+  </outer>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -360,21 +637,17 @@ fn build_childobj_standalone_false() {
 }
 
 #[test]
-fn build_childobj_root_name_object() {
-  let object = load_json("tests/data/childobj.json");
+fn build_lists_root_name_object() {
+  let object = load_json("tests/data/lists.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
   <object>
-    <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
-      <SOAP-ENV:Body>
-        <ns1:createWorkflowProof>
-          <SessionID>sid</SessionID>
-          <OwnerID>0</OwnerID>
-          <Hash>browns</Hash>
-        </ns1:createWorkflowProof>
-      </SOAP-ENV:Body>
-    </SOAP-ENV:Envelope>
+    <outer>
+      <item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item>
+      <item>Qux.</item>
+      <item>Quux.</item>
+    </outer>
   </object>"#
   );
 
@@ -389,80 +662,23 @@ fn build_childobj_root_name_object() {
   assert_eq!(expected, actual);
 }
 
+// === generated tests ===
+
 #[test]
-fn build_complex_default() {
-  let object = load_json("tests/data/complex.json");
+fn build_cds_default() {
+  let object = load_json("tests/data/cds.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <sample>
-    <simpletest>data</simpletest>
-    <simpletest2>data</simpletest2>
-    <chartest desc="Test for CHARs">Character data here!</chartest>
-    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-    <nochartest desc="No data" misc="false"/>
-    <nochildrentest desc="No data" misc="false"/>
-    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-    <listtest attr="Attribute">
-      <item>
-        This is character data!
-        <subitem>Foo(1)</subitem>
-        <subitem>Foo(2)</subitem>
-        <subitem>Foo(3)</subitem>
-        <subitem>Foo(4)</subitem>
-      </item>
-      <item>Qux.</item>
-      <item>Quux.</item>
-      <single>Single</single>
-    </listtest>
-    <arraytest>
-      <item>
-        <subitem>Baz.</subitem>
-      </item>
-      <item>
-        <subitem>Foo.</subitem>
-        <subitem>Bar.</subitem>
-      </item>
-    </arraytest>
-    <emptytest/>
-    <tagcasetest>
-      <tAg>something</tAg>
-      <TAG>something else</TAG>
-      <tag>something third</tag>
-    </tagcasetest>
-    <ordertest>
-      <one>1</one>
-      <one>4</one>
-      <two>2</two>
-      <two>5</two>
-      <three>3</three>
-      <three>6</three>
-    </ordertest>
-    <validatortest>
-      <emptyarray/>
-      <oneitemarray>
-        <item>Bar.</item>
-      </oneitemarray>
-      <numbertest>42</numbertest>
-      <stringtest>43</stringtest>
-    </validatortest>
-    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-      <middle xmlns="http://bar.com"/>
-    </pfx:top>
-    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-    <tagNameProcessTest/>
-    <valueProcessTest>some value</valueProcessTest>
-    <textordertest>
-      this is text with in the middle
-      <b>markup</b>
-      <em>like this</em>
-    </textordertest>
-    <emptytestanother>
-  
-      </emptytestanother>
-  </sample>"#
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+      <cd:artist>Death Grips</cd:artist>
+      <cd:country>USA</cd:country>
+      <cd:company>Third Worlds</cd:company>
+      <cd:price>00.00</cd:price>
+      <cd:year>2012</cd:year>
+    </rdf:Description>
+  </rdf:RDF>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -477,11 +693,9 @@ fn build_complex_default() {
 }
 
 #[test]
-fn build_complex_pretty_false() {
-  let object = load_json("tests/data/complex.json");
-  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc=\"Test for CHARs\">Character data here!</chartest><cdatatest desc=\"Test for CDATA\" misc=\"true\">CDATA here!</cdatatest><cdatawhitespacetest desc=\"Test for CDATA with whitespace\" misc=\"true\"/><nochartest desc=\"No data\" misc=\"false\"/><nochildrentest desc=\"No data\" misc=\"false\"/><whitespacetest desc=\"Test for       normalizing and trimming\">Line One Line Two</whitespacetest><listtest attr=\"Attribute\"><item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx=\"http://foo.com\" pfx:attr=\"baz\"><middle xmlns=\"http://bar.com\"/></pfx:top><attrNameProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowercaseattr=\"lowercaseattrvalue\"/><attrValueProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowerCaseAttr=\"lowercaseattrvalue\"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest><emptytestanother>
-
-    </emptytestanother></sample>" ;
+fn build_cds_pretty_false() {
+  let object = load_json("tests/data/cds.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" xmlns:cd=\"http://www.recshop.fake/cd#\"><rdf:Description rdf:about=\"http://www.recshop.fake/cd/The Money Store\"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>" ;
 
   let mut xml_builder = XmlConfig::new()
     .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
@@ -494,79 +708,20 @@ fn build_complex_pretty_false() {
 }
 
 #[test]
-fn build_complex_indent_tab() {
-  let object = load_json("tests/data/complex.json");
+fn build_cds_indent_tab() {
+  let object = load_json("tests/data/cds.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <sample>
-  	<simpletest>data</simpletest>
-  	<simpletest2>data</simpletest2>
-  	<chartest desc="Test for CHARs">Character data here!</chartest>
-  	<cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-  	<cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-  	<nochartest desc="No data" misc="false"/>
-  	<nochildrentest desc="No data" misc="false"/>
-  	<whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-  	<listtest attr="Attribute">
-  		<item>
-  			This is character data!
-  			<subitem>Foo(1)</subitem>
-  			<subitem>Foo(2)</subitem>
-  			<subitem>Foo(3)</subitem>
-  			<subitem>Foo(4)</subitem>
-  		</item>
-  		<item>Qux.</item>
-  		<item>Quux.</item>
-  		<single>Single</single>
-  	</listtest>
-  	<arraytest>
-  		<item>
-  			<subitem>Baz.</subitem>
-  		</item>
-  		<item>
-  			<subitem>Foo.</subitem>
-  			<subitem>Bar.</subitem>
-  		</item>
-  	</arraytest>
-  	<emptytest/>
-  	<tagcasetest>
-  		<tAg>something</tAg>
-  		<TAG>something else</TAG>
-  		<tag>something third</tag>
-  	</tagcasetest>
-  	<ordertest>
-  		<one>1</one>
-  		<one>4</one>
-  		<two>2</two>
-  		<two>5</two>
-  		<three>3</three>
-  		<three>6</three>
-  	</ordertest>
-  	<validatortest>
-  		<emptyarray/>
-  		<oneitemarray>
-  			<item>Bar.</item>
-  		</oneitemarray>
-  		<numbertest>42</numbertest>
-  		<stringtest>43</stringtest>
-  	</validatortest>
-  	<pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-  		<middle xmlns="http://bar.com"/>
-  	</pfx:top>
-  	<attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-  	<attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-  	<tagNameProcessTest/>
-  	<valueProcessTest>some value</valueProcessTest>
-  	<textordertest>
-  		this is text with in the middle
-  		<b>markup</b>
-  		<em>like this</em>
-  	</textordertest>
-  	<emptytestanother>
-  
-      </emptytestanother>
-  </sample>"#
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+  	<rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+  		<cd:artist>Death Grips</cd:artist>
+  		<cd:country>USA</cd:country>
+  		<cd:company>Third Worlds</cd:company>
+  		<cd:price>00.00</cd:price>
+  		<cd:year>2012</cd:year>
+  	</rdf:Description>
+  </rdf:RDF>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -581,79 +736,20 @@ fn build_complex_indent_tab() {
 }
 
 #[test]
-fn build_complex_indent_empty() {
-  let object = load_json("tests/data/complex.json");
+fn build_cds_indent_empty() {
+  let object = load_json("tests/data/cds.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <sample>
-  <simpletest>data</simpletest>
-  <simpletest2>data</simpletest2>
-  <chartest desc="Test for CHARs">Character data here!</chartest>
-  <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-  <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-  <nochartest desc="No data" misc="false"/>
-  <nochildrentest desc="No data" misc="false"/>
-  <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-  <listtest attr="Attribute">
-  <item>
-  This is character data!
-  <subitem>Foo(1)</subitem>
-  <subitem>Foo(2)</subitem>
-  <subitem>Foo(3)</subitem>
-  <subitem>Foo(4)</subitem>
-  </item>
-  <item>Qux.</item>
-  <item>Quux.</item>
-  <single>Single</single>
-  </listtest>
-  <arraytest>
-  <item>
-  <subitem>Baz.</subitem>
-  </item>
-  <item>
-  <subitem>Foo.</subitem>
-  <subitem>Bar.</subitem>
-  </item>
-  </arraytest>
-  <emptytest/>
-  <tagcasetest>
-  <tAg>something</tAg>
-  <TAG>something else</TAG>
-  <tag>something third</tag>
-  </tagcasetest>
-  <ordertest>
-  <one>1</one>
-  <one>4</one>
-  <two>2</two>
-  <two>5</two>
-  <three>3</three>
-  <three>6</three>
-  </ordertest>
-  <validatortest>
-  <emptyarray/>
-  <oneitemarray>
-  <item>Bar.</item>
-  </oneitemarray>
-  <numbertest>42</numbertest>
-  <stringtest>43</stringtest>
-  </validatortest>
-  <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-  <middle xmlns="http://bar.com"/>
-  </pfx:top>
-  <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-  <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-  <tagNameProcessTest/>
-  <valueProcessTest>some value</valueProcessTest>
-  <textordertest>
-  this is text with in the middle
-  <b>markup</b>
-  <em>like this</em>
-  </textordertest>
-  <emptytestanother>
-  
-      </emptytestanother>
-  </sample>"#
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+  <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+  <cd:artist>Death Grips</cd:artist>
+  <cd:country>USA</cd:country>
+  <cd:company>Third Worlds</cd:company>
+  <cd:price>00.00</cd:price>
+  <cd:year>2012</cd:year>
+  </rdf:Description>
+  </rdf:RDF>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -668,84 +764,13 @@ fn build_complex_indent_empty() {
 }
 
 #[test]
-fn build_complex_version_11() {
-  let object = load_json("tests/data/complex.json");
-  let expected = indoc!(
-    r#"
-  <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
-  <sample>
-    <simpletest>data</simpletest>
-    <simpletest2>data</simpletest2>
-    <chartest desc="Test for CHARs">Character data here!</chartest>
-    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-    <nochartest desc="No data" misc="false"/>
-    <nochildrentest desc="No data" misc="false"/>
-    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-    <listtest attr="Attribute">
-      <item>
-        This is character data!
-        <subitem>Foo(1)</subitem>
-        <subitem>Foo(2)</subitem>
-        <subitem>Foo(3)</subitem>
-        <subitem>Foo(4)</subitem>
-      </item>
-      <item>Qux.</item>
-      <item>Quux.</item>
-      <single>Single</single>
-    </listtest>
-    <arraytest>
-      <item>
-        <subitem>Baz.</subitem>
-      </item>
-      <item>
-        <subitem>Foo.</subitem>
-        <subitem>Bar.</subitem>
-      </item>
-    </arraytest>
-    <emptytest/>
-    <tagcasetest>
-      <tAg>something</tAg>
-      <TAG>something else</TAG>
-      <tag>something third</tag>
-    </tagcasetest>
-    <ordertest>
-      <one>1</one>
-      <one>4</one>
-      <two>2</two>
-      <two>5</two>
-      <three>3</three>
-      <three>6</three>
-    </ordertest>
-    <validatortest>
-      <emptyarray/>
-      <oneitemarray>
-        <item>Bar.</item>
-      </oneitemarray>
-      <numbertest>42</numbertest>
-      <stringtest>43</stringtest>
-    </validatortest>
-    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-      <middle xmlns="http://bar.com"/>
-    </pfx:top>
-    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-    <tagNameProcessTest/>
-    <valueProcessTest>some value</valueProcessTest>
-    <textordertest>
-      this is text with in the middle
-      <b>markup</b>
-      <em>like this</em>
-    </textordertest>
-    <emptytestanother>
-  
-      </emptytestanother>
-  </sample>"#
-  );
+fn build_cds_newline_crlf() {
+  let object = load_json("tests/data/cds.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\" xmlns:cd=\"http://www.recshop.fake/cd#\">\r\n  <rdf:Description rdf:about=\"http://www.recshop.fake/cd/The Money Store\">\r\n    <cd:artist>Death Grips</cd:artist>\r\n    <cd:country>USA</cd:country>\r\n    <cd:company>Third Worlds</cd:company>\r\n    <cd:price>00.00</cd:price>\r\n    <cd:year>2012</cd:year>\r\n  </rdf:Description>\r\n</rdf:RDF>";
 
   let mut xml_builder = XmlConfig::new()
-    .rendering(Indentation::new(b' ', 2))
-    .decl(Declaration::new(Version::XML11, Some(Encoding::UTF8), Some(true)))
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
     .root_name("root")
     .finalize();
   let result = xml_builder.build_from_json(&object);
@@ -755,167 +780,77 @@ fn build_complex_version_11() {
 }
 
 #[test]
-fn build_complex_standalone_false() {
-  let object = load_json("tests/data/complex.json");
+fn build_cds_version_11() {
+  let object = load_json("tests/data/cds.json");
   let expected = indoc!(
     r#"
-  <?xml version="1.0" encoding="UTF-8" standalone="no"?>
-  <sample>
-    <simpletest>data</simpletest>
-    <simpletest2>data</simpletest2>
-    <chartest desc="Test for CHARs">Character data here!</chartest>
-    <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-    <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-    <nochartest desc="No data" misc="false"/>
-    <nochildrentest desc="No data" misc="false"/>
-    <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-    <listtest attr="Attribute">
-      <item>
-        This is character data!
-        <subitem>Foo(1)</subitem>
-        <subitem>Foo(2)</subitem>
-        <subitem>Foo(3)</subitem>
-        <subitem>Foo(4)</subitem>
-      </item>
-      <item>Qux.</item>
-      <item>Quux.</item>
-      <single>Single</single>
-    </listtest>
-    <arraytest>
-      <item>
-        <subitem>Baz.</subitem>
-      </item>
-      <item>
-        <subitem>Foo.</subitem>
-        <subitem>Bar.</subitem>
-      </item>
-    </arraytest>
-    <emptytest/>
-    <tagcasetest>
-      <tAg>something</tAg>
-      <TAG>something else</TAG>
-      <tag>something third</tag>
-    </tagcasetest>
-    <ordertest>
-      <one>1</one>
-      <one>4</one>
-      <two>2</two>
-      <two>5</two>
-      <three>3</three>
-      <three>6</three>
-    </ordertest>
-    <validatortest>
-      <emptyarray/>
-      <oneitemarray>
-        <item>Bar.</item>
-      </oneitemarray>
-      <numbertest>42</numbertest>
-      <stringtest>43</stringtest>
-    </validatortest>
-    <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-      <middle xmlns="http://bar.com"/>
-    </pfx:top>
-    <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-    <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-    <tagNameProcessTest/>
-    <valueProcessTest>some value</valueProcessTest>
-    <textordertest>
-      this is text with in the middle
-      <b>markup</b>
-      <em>like this</em>
-    </textordertest>
-    <emptytestanother>
-  
-      </emptytestanother>
-  </sample>"#
+  <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+      <cd:artist>Death Grips</cd:artist>
+      <cd:country>USA</cd:country>
+      <cd:company>Third Worlds</cd:company>
+      <cd:price>00.00</cd:price>
+      <cd:year>2012</cd:year>
+    </rdf:Description>
+  </rdf:RDF>"#
   );
 
   let mut xml_builder = XmlConfig::new()
-    .rendering(Indentation::new(b' ', 2))
-    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(false)))
-    .root_name("root")
-    .finalize();
-  let result = xml_builder.build_from_json(&object);
-
-  let actual = result.expect("Error building XML");
-  assert_eq!(expected, actual);
-}
-
-#[test]
-fn build_complex_root_name_object() {
-  let object = load_json("tests/data/complex.json");
-  let expected = indoc!(
-    r#"
-  <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <object>
-    <sample>
-      <simpletest>data</simpletest>
-      <simpletest2>data</simpletest2>
-      <chartest desc="Test for CHARs">Character data here!</chartest>
-      <cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest>
-      <cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/>
-      <nochartest desc="No data" misc="false"/>
-      <nochildrentest desc="No data" misc="false"/>
-      <whitespacetest desc="Test for       normalizing and trimming">Line One Line Two</whitespacetest>
-      <listtest attr="Attribute">
-        <item>
-          This is character data!
-          <subitem>Foo(1)</subitem>
-          <subitem>Foo(2)</subitem>
-          <subitem>Foo(3)</subitem>
-          <subitem>Foo(4)</subitem>
-        </item>
-        <item>Qux.</item>
-        <item>Quux.</item>
-        <single>Single</single>
-      </listtest>
-      <arraytest>
-        <item>
-          <subitem>Baz.</subitem>
-        </item>
-        <item>
-          <subitem>Foo.</subitem>
-          <subitem>Bar.</subitem>
-        </item>
-      </arraytest>
-      <emptytest/>
-      <tagcasetest>
-        <tAg>something</tAg>
-        <TAG>something else</TAG>
-        <tag>something third</tag>
-      </tagcasetest>
-      <ordertest>
-        <one>1</one>
-        <one>4</one>
-        <two>2</two>
-        <two>5</two>
-        <three>3</three>
-        <three>6</three>
-      </ordertest>
-      <validatortest>
-        <emptyarray/>
-        <oneitemarray>
-          <item>Bar.</item>
-        </oneitemarray>
-        <numbertest>42</numbertest>
-        <stringtest>43</stringtest>
-      </validatortest>
-      <pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz">
-        <middle xmlns="http://bar.com"/>
-      </pfx:top>
-      <attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/>
-      <attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/>
-      <tagNameProcessTest/>
-      <valueProcessTest>some value</valueProcessTest>
-      <textordertest>
-        this is text with in the middle
-        <b>markup</b>
-        <em>like this</em>
-      </textordertest>
-      <emptytestanother>
-  
-      </emptytestanother>
-    </sample>
+    .rendering(Indentation::new(b' ', 2))
+    .decl(Declaration::new(Version::XML11, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_cds_standalone_false() {
+  let object = load_json("tests/data/cds.json");
+  let expected = indoc!(
+    r#"
+  <?xml version="1.0" encoding="UTF-8" standalone="no"?>
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+    <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+      <cd:artist>Death Grips</cd:artist>
+      <cd:country>USA</cd:country>
+      <cd:company>Third Worlds</cd:company>
+      <cd:price>00.00</cd:price>
+      <cd:year>2012</cd:year>
+    </rdf:Description>
+  </rdf:RDF>"#
+  );
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(false)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_cds_root_name_object() {
+  let object = load_json("tests/data/cds.json");
+  let expected = indoc!(
+    r#"
+  <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+  <object>
+    <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#">
+      <rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store">
+        <cd:artist>Death Grips</cd:artist>
+        <cd:country>USA</cd:country>
+        <cd:company>Third Worlds</cd:company>
+        <cd:price>00.00</cd:price>
+        <cd:year>2012</cd:year>
+      </rdf:Description>
+    </rdf:RDF>
   </object>"#
   );
 
@@ -931,22 +866,20 @@ fn build_complex_root_name_object() {
 }
 
 #[test]
-fn build_lists_default() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_default() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <outer>
-    <item>
-      This is character data!
-      <subitem>Foo(1)</subitem>
-      <subitem>Foo(2)</subitem>
-      <subitem>Foo(3)</subitem>
-      <subitem>4</subitem>
-    </item>
-    <item>Qux.</item>
-    <item>Quux.</item>
-  </outer>"#
+  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+    <SOAP-ENV:Body>
+      <ns1:createWorkflowProof>
+        <SessionID>sid</SessionID>
+        <OwnerID>0</OwnerID>
+        <Hash>browns</Hash>
+      </ns1:createWorkflowProof>
+    </SOAP-ENV:Body>
+  </SOAP-ENV:Envelope>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -961,11 +894,11 @@ fn build_lists_default() {
 }
 
 #[test]
-fn build_lists_pretty_false() {
-  let object = load_json("tests/data/lists.json");
-  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><outer><item>This is character \
-                  data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item><item>Qux.</\
-                  item><item>Quux.</item></outer>";
+fn build_childobj_pretty_false() {
+  let object = load_json("tests/data/childobj.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><SOAP-ENV:Envelope xmlns:SOAP-ENV=\"env\" \
+                  xmlns:ns1=\"ns1\"><SOAP-ENV:Body><ns1:createWorkflowProof><SessionID>sid</SessionID><OwnerID>0</OwnerID><Hash>browns</\
+                  Hash></ns1:createWorkflowProof></SOAP-ENV:Body></SOAP-ENV:Envelope>";
 
   let mut xml_builder = XmlConfig::new()
     .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
@@ -978,22 +911,20 @@ fn build_lists_pretty_false() {
 }
 
 #[test]
-fn build_lists_indent_tab() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_indent_tab() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <outer>
-  	<item>
-  		This is character data!
-  		<subitem>Foo(1)</subitem>
-  		<subitem>Foo(2)</subitem>
-  		<subitem>Foo(3)</subitem>
-  		<subitem>4</subitem>
-  	</item>
-  	<item>Qux.</item>
-  	<item>Quux.</item>
-  </outer>"#
+  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+  	<SOAP-ENV:Body>
+  		<ns1:createWorkflowProof>
+  			<SessionID>sid</SessionID>
+  			<OwnerID>0</OwnerID>
+  			<Hash>browns</Hash>
+  		</ns1:createWorkflowProof>
+  	</SOAP-ENV:Body>
+  </SOAP-ENV:Envelope>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -1008,22 +939,20 @@ fn build_lists_indent_tab() {
 }
 
 #[test]
-fn build_lists_indent_empty() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_indent_empty() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
-  <outer>
-  <item>
-  This is character data!
-  <subitem>Foo(1)</subitem>
-  <subitem>Foo(2)</subitem>
-  <subitem>Foo(3)</subitem>
-  <subitem>4</subitem>
-  </item>
-  <item>Qux.</item>
-  <item>Quux.</item>
-  </outer>"#
+  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+  <SOAP-ENV:Body>
+  <ns1:createWorkflowProof>
+  <SessionID>sid</SessionID>
+  <OwnerID>0</OwnerID>
+  <Hash>browns</Hash>
+  </ns1:createWorkflowProof>
+  </SOAP-ENV:Body>
+  </SOAP-ENV:Envelope>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -1038,22 +967,39 @@ fn build_lists_indent_empty() {
 }
 
 #[test]
-fn build_lists_version_11() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_newline_crlf() {
+  let object = load_json("tests/data/childobj.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<SOAP-ENV:Envelope xmlns:SOAP-ENV=\"env\" \
+                  xmlns:ns1=\"ns1\">\r\n  <SOAP-ENV:Body>\r\n    <ns1:createWorkflowProof>\r\n      <SessionID>sid</SessionID>\r\n      \
+                  <OwnerID>0</OwnerID>\r\n      <Hash>browns</Hash>\r\n    </ns1:createWorkflowProof>\r\n  \
+                  </SOAP-ENV:Body>\r\n</SOAP-ENV:Envelope>";
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_childobj_version_11() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.1" encoding="UTF-8" standalone="yes"?>
-  <outer>
-    <item>
-      This is character data!
-      <subitem>Foo(1)</subitem>
-      <subitem>Foo(2)</subitem>
-      <subitem>Foo(3)</subitem>
-      <subitem>4</subitem>
-    </item>
-    <item>Qux.</item>
-    <item>Quux.</item>
-  </outer>"#
+  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+    <SOAP-ENV:Body>
+      <ns1:createWorkflowProof>
+        <SessionID>sid</SessionID>
+        <OwnerID>0</OwnerID>
+        <Hash>browns</Hash>
+      </ns1:createWorkflowProof>
+    </SOAP-ENV:Body>
+  </SOAP-ENV:Envelope>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -1068,22 +1014,20 @@ fn build_lists_version_11() {
 }
 
 #[test]
-fn build_lists_standalone_false() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_standalone_false() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="no"?>
-  <outer>
-    <item>
-      This is character data!
-      <subitem>Foo(1)</subitem>
-      <subitem>Foo(2)</subitem>
-      <subitem>Foo(3)</subitem>
-      <subitem>4</subitem>
-    </item>
-    <item>Qux.</item>
-    <item>Quux.</item>
-  </outer>"#
+  <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+    <SOAP-ENV:Body>
+      <ns1:createWorkflowProof>
+        <SessionID>sid</SessionID>
+        <OwnerID>0</OwnerID>
+        <Hash>browns</Hash>
+      </ns1:createWorkflowProof>
+    </SOAP-ENV:Body>
+  </SOAP-ENV:Envelope>"#
   );
 
   let mut xml_builder = XmlConfig::new()
@@ -1098,23 +1042,21 @@ fn build_lists_standalone_false() {
 }
 
 #[test]
-fn build_lists_root_name_object() {
-  let object = load_json("tests/data/lists.json");
+fn build_childobj_root_name_object() {
+  let object = load_json("tests/data/childobj.json");
   let expected = indoc!(
     r#"
   <?xml version="1.0" encoding="UTF-8" standalone="yes"?>
   <object>
-    <outer>
-      <item>
-        This is character data!
-        <subitem>Foo(1)</subitem>
-        <subitem>Foo(2)</subitem>
-        <subitem>Foo(3)</subitem>
-        <subitem>4</subitem>
-      </item>
-      <item>Qux.</item>
-      <item>Quux.</item>
-    </outer>
+    <SOAP-ENV:Envelope xmlns:SOAP-ENV="env" xmlns:ns1="ns1">
+      <SOAP-ENV:Body>
+        <ns1:createWorkflowProof>
+          <SessionID>sid</SessionID>
+          <OwnerID>0</OwnerID>
+          <Hash>browns</Hash>
+        </ns1:createWorkflowProof>
+      </SOAP-ENV:Body>
+    </SOAP-ENV:Envelope>
   </object>"#
   );
 
@@ -1129,6 +1071,40 @@ fn build_lists_root_name_object() {
   assert_eq!(expected, actual);
 }
 
+#[test]
+fn build_complex_pretty_false() {
+  let object = load_json("tests/data/complex.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc=\"Test for CHARs\">Character data here!</chartest><cdatatest desc=\"Test for CDATA\" misc=\"true\">CDATA here!</cdatatest><cdatawhitespacetest desc=\"Test for CDATA with whitespace\" misc=\"true\"/><nochartest desc=\"No data\" misc=\"false\"/><nochildrentest desc=\"No data\" misc=\"false\"/><whitespacetest desc=\"Test for       normalizing and trimming\">Line One Line Two</whitespacetest><listtest attr=\"Attribute\"><item>This is character data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem></item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx=\"http://foo.com\" pfx:attr=\"baz\"><middle xmlns=\"http://bar.com\"/></pfx:top><attrNameProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowercaseattr=\"lowercaseattrvalue\"/><attrValueProcessTest camelCaseAttr=\"camelCaseAttrValue\" lowerCaseAttr=\"lowercaseattrvalue\"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest>this is text with in the middle<b>markup</b><em>like this</em></textordertest><emptytestanother>
+
+    </emptytestanother></sample>" ;
+
+  let mut xml_builder = XmlConfig::new()
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn build_lists_pretty_false() {
+  let object = load_json("tests/data/lists.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?><outer><item>This is character \
+                  data!<subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem></item><item>Qux.</\
+                  item><item>Quux.</item></outer>";
+
+  let mut xml_builder = XmlConfig::new()
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
 #[test]
 fn build_multiroot_default() {
   let object = load_json("tests/data/multiroot.json");
@@ -1213,6 +1189,23 @@ fn build_multiroot_indent_empty() {
   assert_eq!(expected, actual);
 }
 
+#[test]
+fn build_multiroot_newline_crlf() {
+  let object = load_json("tests/data/multiroot.json");
+  let expected =
+    "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<root>\r\n  <key1>Value1</key1>\r\n  <key2>Value2</key2>\r\n</root>";
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
 #[test]
 fn build_multiroot_version_11() {
   let object = load_json("tests/data/multiroot.json");
@@ -1317,6 +1310,22 @@ fn build_singleroot_pretty_false() {
   assert_eq!(expected, actual);
 }
 
+#[test]
+fn build_singleroot_newline_crlf() {
+  let object = load_json("tests/data/singleroot.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<foo>bar</foo>";
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
 #[test]
 fn build_singleroot_version_11() {
   let object = load_json("tests/data/singleroot.json");
@@ -1526,6 +1535,22 @@ fn build_soap_indent_empty() {
   assert_eq!(expected, actual);
 }
 
+#[test]
+fn build_soap_newline_crlf() {
+  let object = load_json("tests/data/soap.json");
+  let expected = "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\r\n<SOAP-ENV:Envelope xmlns:SOAP-ENV=\"http://schemas.xmlsoap.org/soap/envelope/\" xmlns:ns1=\"https://paper-street.soap.com/\">\r\n  <SOAP-ENV:Body>\r\n    <ns1:createWorkflowProof>\r\n      <SessionID>sid</SessionID>\r\n      <Stages>\r\n        <item>\r\n          <stage_reviewers>\r\n            <item>\r\n              <email>robert.paulson@gmail.com</email>\r\n            </item>\r\n          </stage_reviewers>\r\n          <name>Stage 1</name>\r\n        </item>\r\n        <item>\r\n          <stage_reviewers>\r\n            <item>\r\n              <email>tyler.durden@gmail.com</email>\r\n            </item>\r\n          </stage_reviewers>\r\n          <name>Stage 2</name>\r\n        </item>\r\n      </Stages>\r\n    </ns1:createWorkflowProof>\r\n  </SOAP-ENV:Body>\r\n</SOAP-ENV:Envelope>";
+
+  let mut xml_builder = XmlConfig::new()
+    .rendering(Indentation::new(b' ', 2).newline(Newline::CRLF))
+    .decl(Declaration::new(Version::XML10, Some(Encoding::UTF8), Some(true)))
+    .root_name("root")
+    .finalize();
+  let result = xml_builder.build_from_json(&object);
+
+  let actual = result.expect("Error building XML");
+  assert_eq!(expected, actual);
+}
+
 #[test]
 fn build_soap_version_11() {
   let object = load_json("tests/data/soap.json");
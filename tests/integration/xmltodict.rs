@@ -0,0 +1,596 @@
+use xml2json_rs::{JsonConfig, XmlConfig, XmlToDict};
+
+use pretty_assertions::assert_eq;
+
+use super::json::load_xml;
+
+// Generated with xmltodict, see tests/generator/lib/parity.py. XML is compared after the
+// XmlBuilder's declaration, as xmltodict writes its own
+
+// === generated tests ===
+
+#[test]
+fn parse_cds_default() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new()).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_cds_default() {
+  let json = r#"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+  let expected = r#"<?xml version="1.0"?><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#"><rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new()).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_attr_prefix() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"rdf:RDF":{"_xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","_xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"_rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_cds_attr_prefix() {
+  let json = r#"{"rdf:RDF":{"_xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","_xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"_rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+  let expected = r#"<?xml version="1.0"?><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#"><rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_force_list_custom() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":["Death Grips"],"cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new()
+    .convention(XmlToDict::new().force_list("item").force_list("cd:artist"))
+    .finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_cds_force_list_custom() {
+  let json = r#"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":["Death Grips"],"cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+  let expected = r#"<?xml version="1.0"?><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#"><rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>"#;
+
+  let mut xml_builder = XmlConfig::new()
+    .convention(XmlToDict::new().force_list("item").force_list("cd:artist"))
+    .finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_process_namespaces() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"http://www.w3.org/1999/02/22-rdf-syntax-ns#:RDF":{"@xmlns":{"rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","cd":"http://www.recshop.fake/cd#"},"http://www.w3.org/1999/02/22-rdf-syntax-ns#:Description":{"@http://www.w3.org/1999/02/22-rdf-syntax-ns#:about":"http://www.recshop.fake/cd/The Money Store","http://www.recshop.fake/cd#:artist":"Death Grips","http://www.recshop.fake/cd#:country":"USA","http://www.recshop.fake/cd#:company":"Third Worlds","http://www.recshop.fake/cd#:price":"00.00","http://www.recshop.fake/cd#:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().process_namespaces(true)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_namespaces_custom() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"rdf:RDF":{"@xmlns":{"rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","cd":"http://www.recshop.fake/cd#"},"rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","http://www.recshop.fake/cd#:artist":"Death Grips","http://www.recshop.fake/cd#:country":"USA","http://www.recshop.fake/cd#:company":"Third Worlds","http://www.recshop.fake/cd#:price":"00.00","http://www.recshop.fake/cd#:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new()
+    .convention(
+      XmlToDict::new()
+        .process_namespaces(true)
+        .namespace("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "rdf")
+        .namespace("http://foo.com", "")
+    )
+    .finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_xml_attribs_false() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r#"{"rdf:RDF":{"rdf:Description":{"cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_cds_xml_attribs_false() {
+  let json = r#"{"rdf:RDF":{"rdf:Description":{"cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012"}}}"#;
+  let expected = r#"<?xml version="1.0"?><rdf:RDF><rdf:Description><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year></rdf:Description></rdf:RDF>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_cds_strip_whitespace_false() {
+  let xml = load_xml("tests/data/cds.xml");
+  let expected = r##"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012","#text":"\n  \n  \n  \n  \n  \n"},"#text":"\n\n\n\n"}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_cds_strip_whitespace_false() {
+  let json = r##"{"rdf:RDF":{"@xmlns:rdf":"http://www.w3.org/1999/02/22-rdf-syntax-ns#","@xmlns:cd":"http://www.recshop.fake/cd#","rdf:Description":{"@rdf:about":"http://www.recshop.fake/cd/The Money Store","cd:artist":"Death Grips","cd:country":"USA","cd:company":"Third Worlds","cd:price":"00.00","cd:year":"2012","#text":"\n  \n  \n  \n  \n  \n"},"#text":"\n\n\n\n"}}"##;
+  let expected = r#"<?xml version="1.0"?><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#" xmlns:cd="http://www.recshop.fake/cd#"><rdf:Description rdf:about="http://www.recshop.fake/cd/The Money Store"><cd:artist>Death Grips</cd:artist><cd:country>USA</cd:country><cd:company>Third Worlds</cd:company><cd:price>00.00</cd:price><cd:year>2012</cd:year>
+  
+  
+  
+  
+  
+</rdf:Description>
+
+
+
+</rdf:RDF>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_default() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new()).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_default() {
+  let json = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc="Test for CHARs">Character data here!</chartest><cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest><cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/><nochartest desc="No data" misc="false"/><nochildrentest desc="No data" misc="false"/><whitespacetest desc="Test for       normalizing and trimming">Line One
+        Line Two</whitespacetest><listtest attr="Attribute"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>This  is
+            
+            character
+            
+            data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><middle xmlns="http://bar.com"/></pfx:top><attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/><attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother/></sample>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new()).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_attr_prefix() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"_desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"_desc":"Test for CDATA","_misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"_desc":"Test for CDATA with whitespace","_misc":"true"},"nochartest":{"_desc":"No data","_misc":"false"},"nochildrentest":{"_desc":"No data","_misc":"false"},"whitespacetest":{"_desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"_attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"_xmlns:pfx":"http://foo.com","_pfx:attr":"baz","middle":{"_xmlns":"http://bar.com"}},"attrNameProcessTest":{"_camelCaseAttr":"camelCaseAttrValue","_lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"_camelCaseAttr":"camelCaseAttrValue","_lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_attr_prefix() {
+  let json = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"_desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"_desc":"Test for CDATA","_misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"_desc":"Test for CDATA with whitespace","_misc":"true"},"nochartest":{"_desc":"No data","_misc":"false"},"nochildrentest":{"_desc":"No data","_misc":"false"},"whitespacetest":{"_desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"_attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"_xmlns:pfx":"http://foo.com","_pfx:attr":"baz","middle":{"_xmlns":"http://bar.com"}},"attrNameProcessTest":{"_camelCaseAttr":"camelCaseAttrValue","_lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"_camelCaseAttr":"camelCaseAttrValue","_lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc="Test for CHARs">Character data here!</chartest><cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest><cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/><nochartest desc="No data" misc="false"/><nochildrentest desc="No data" misc="false"/><whitespacetest desc="Test for       normalizing and trimming">Line One
+        Line Two</whitespacetest><listtest attr="Attribute"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>This  is
+            
+            character
+            
+            data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><middle xmlns="http://bar.com"/></pfx:top><attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/><attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother/></sample>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_cdata_key_text() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r#"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","_text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","_text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","_text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"_text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","_text":"this is text with     in the middle"},"emptytestanother":null}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().cdata_key("_text")).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_cdata_key_text() {
+  let json = r#"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","_text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","_text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","_text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"_text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","_text":"this is text with     in the middle"},"emptytestanother":null}}"#;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc="Test for CHARs">Character data here!</chartest><cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest><cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/><nochartest desc="No data" misc="false"/><nochildrentest desc="No data" misc="false"/><whitespacetest desc="Test for       normalizing and trimming">Line One
+        Line Two</whitespacetest><listtest attr="Attribute"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>This  is
+            
+            character
+            
+            data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><middle xmlns="http://bar.com"/></pfx:top><attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/><attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother/></sample>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().cdata_key("_text")).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_force_list_custom() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":["Bar."]},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new()
+    .convention(XmlToDict::new().force_list("item").force_list("cd:artist"))
+    .finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_force_list_custom() {
+  let json = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":["Bar."]},"numbertest":"42","stringtest":"43"},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest desc="Test for CHARs">Character data here!</chartest><cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest><cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true"/><nochartest desc="No data" misc="false"/><nochildrentest desc="No data" misc="false"/><whitespacetest desc="Test for       normalizing and trimming">Line One
+        Line Two</whitespacetest><listtest attr="Attribute"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>This  is
+            
+            character
+            
+            data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><middle xmlns="http://bar.com"/></pfx:top><attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/><attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother/></sample>"#;
+
+  let mut xml_builder = XmlConfig::new()
+    .convention(XmlToDict::new().force_list("item").force_list("cd:artist"))
+    .finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_process_namespaces() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"http://foo.com:top":{"@http://foo.com:attr":"baz","@xmlns":{"pfx":"http://foo.com"},"http://bar.com:middle":{"@xmlns":{"":"http://bar.com"}}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().process_namespaces(true)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_namespaces_custom() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true"},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"Line One\n        Line Two"},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"top":{"@attr":"baz","@xmlns":{"pfx":"http://foo.com"},"http://bar.com:middle":{"@xmlns":{"":"http://bar.com"}}},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new()
+    .convention(
+      XmlToDict::new()
+        .process_namespaces(true)
+        .namespace("http://www.w3.org/1999/02/22-rdf-syntax-ns#", "rdf")
+        .namespace("http://foo.com", "")
+    )
+    .finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_xml_attribs_false() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":"Character data here!","cdatatest":"CDATA here!","cdatawhitespacetest":null,"nochartest":null,"nochildrentest":null,"whitespacetest":"Line One\n        Line Two","listtest":{"item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"middle":null},"attrNameProcessTest":null,"attrValueProcessTest":null,"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_xml_attribs_false() {
+  let json = r##"{"sample":{"simpletest":"data","simpletest2":"data","chartest":"Character data here!","cdatatest":"CDATA here!","cdatawhitespacetest":null,"nochartest":null,"nochildrentest":null,"whitespacetest":"Line One\n        Line Two","listtest":{"item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"This  is\n            \n            character\n            \n            data!"},"Qux.","Quux."],"single":"Single"},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}]},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third"},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"]},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar."},"numbertest":"42","stringtest":"43"},"pfx:top":{"middle":null},"attrNameProcessTest":null,"attrValueProcessTest":null,"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":null}}"##;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>data</simpletest><simpletest2>data</simpletest2><chartest>Character data here!</chartest><cdatatest>CDATA here!</cdatatest><cdatawhitespacetest/><nochartest/><nochildrentest/><whitespacetest>Line One
+        Line Two</whitespacetest><listtest><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>This  is
+            
+            character
+            
+            data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item></arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag></tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three></ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item></oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest></validatortest><pfx:top><middle/></pfx:top><attrNameProcessTest/><attrValueProcessTest/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother/></sample>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_complex_strip_whitespace_false() {
+  let xml = load_xml("tests/data/complex.xml");
+  let expected = r##"{"sample":{"simpletest":"\n      data\n    ","simpletest2":"\n      data\n    ","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true","#text":"   "},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"\n        Line One\n        Line Two\n    "},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"\n            This  is\n            \n            character\n            \n            data!\n            \n        "},"Qux.","Quux."],"single":"Single","#text":"\n        \n        \n        \n        \n    "},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}],"#text":"\n        \n        \n    "},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third","#text":"\n        \n        \n        \n    "},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"],"#text":"\n        \n        \n        \n        \n        \n        \n    "},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar.","#text":"\n            \n        "},"numbertest":"42","stringtest":"43","#text":"\n        \n        \n        \n        \n    "},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"},"#text":"\n        \n    "},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":"\n\n    ","#text":"\n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n"}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_complex_strip_whitespace_false() {
+  let json = r##"{"sample":{"simpletest":"\n      data\n    ","simpletest2":"\n      data\n    ","chartest":{"@desc":"Test for CHARs","#text":"Character data here!"},"cdatatest":{"@desc":"Test for CDATA","@misc":"true","#text":"CDATA here!"},"cdatawhitespacetest":{"@desc":"Test for CDATA with whitespace","@misc":"true","#text":"   "},"nochartest":{"@desc":"No data","@misc":"false"},"nochildrentest":{"@desc":"No data","@misc":"false"},"whitespacetest":{"@desc":"Test for       normalizing and trimming","#text":"\n        Line One\n        Line Two\n    "},"listtest":{"@attr":"Attribute","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","Foo(4)"],"#text":"\n            This  is\n            \n            character\n            \n            data!\n            \n        "},"Qux.","Quux."],"single":"Single","#text":"\n        \n        \n        \n        \n    "},"arraytest":{"item":[{"subitem":"Baz."},{"subitem":["Foo.","Bar."]}],"#text":"\n        \n        \n    "},"emptytest":null,"tagcasetest":{"tAg":"something","TAG":"something else","tag":"something third","#text":"\n        \n        \n        \n    "},"ordertest":{"one":["1","4"],"two":["2","5"],"three":["3","6"],"#text":"\n        \n        \n        \n        \n        \n        \n    "},"validatortest":{"emptyarray":null,"oneitemarray":{"item":"Bar.","#text":"\n            \n        "},"numbertest":"42","stringtest":"43","#text":"\n        \n        \n        \n        \n    "},"pfx:top":{"@xmlns:pfx":"http://foo.com","@pfx:attr":"baz","middle":{"@xmlns":"http://bar.com"},"#text":"\n        \n    "},"attrNameProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowercaseattr":"lowercaseattrvalue"},"attrValueProcessTest":{"@camelCaseAttr":"camelCaseAttrValue","@lowerCaseAttr":"lowercaseattrvalue"},"tagNameProcessTest":null,"valueProcessTest":"some value","textordertest":{"b":"markup","em":"like this","#text":"this is text with     in the middle"},"emptytestanother":"\n\n    ","#text":"\n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n    \n"}}"##;
+  let expected = r#"<?xml version="1.0"?><sample><simpletest>
+      data
+    </simpletest><simpletest2>
+      data
+    </simpletest2><chartest desc="Test for CHARs">Character data here!</chartest><cdatatest desc="Test for CDATA" misc="true">CDATA here!</cdatatest><cdatawhitespacetest desc="Test for CDATA with whitespace" misc="true">   </cdatawhitespacetest><nochartest desc="No data" misc="false"/><nochildrentest desc="No data" misc="false"/><whitespacetest desc="Test for       normalizing and trimming">
+        Line One
+        Line Two
+    </whitespacetest><listtest attr="Attribute"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>Foo(4)</subitem>
+            This  is
+            
+            character
+            
+            data!
+            
+        </item><item>Qux.</item><item>Quux.</item><single>Single</single>
+        
+        
+        
+        
+    </listtest><arraytest><item><subitem>Baz.</subitem></item><item><subitem>Foo.</subitem><subitem>Bar.</subitem></item>
+        
+        
+    </arraytest><emptytest/><tagcasetest><tAg>something</tAg><TAG>something else</TAG><tag>something third</tag>
+        
+        
+        
+    </tagcasetest><ordertest><one>1</one><one>4</one><two>2</two><two>5</two><three>3</three><three>6</three>
+        
+        
+        
+        
+        
+        
+    </ordertest><validatortest><emptyarray/><oneitemarray><item>Bar.</item>
+            
+        </oneitemarray><numbertest>42</numbertest><stringtest>43</stringtest>
+        
+        
+        
+        
+    </validatortest><pfx:top xmlns:pfx="http://foo.com" pfx:attr="baz"><middle xmlns="http://bar.com"/>
+        
+    </pfx:top><attrNameProcessTest camelCaseAttr="camelCaseAttrValue" lowercaseattr="lowercaseattrvalue"/><attrValueProcessTest camelCaseAttr="camelCaseAttrValue" lowerCaseAttr="lowercaseattrvalue"/><tagNameProcessTest/><valueProcessTest>some value</valueProcessTest><textordertest><b>markup</b><em>like this</em>this is text with     in the middle</textordertest><emptytestanother>
+
+    </emptytestanother>
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+    
+</sample>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_lists_default() {
+  let xml = load_xml("tests/data/lists.xml");
+  let expected = r##"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new()).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_lists_default() {
+  let json = r##"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+  let expected = r#"<?xml version="1.0"?><outer><key>value</key><listtest attr="Attribute" another="attrbte"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem>This  is
+          
+          character
+          
+          data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest></outer>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new()).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_lists_attr_prefix() {
+  let xml = load_xml("tests/data/lists.xml");
+  let expected = r##"{"outer":{"key":"value","listtest":{"_attr":"Attribute","_another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_lists_attr_prefix() {
+  let json = r##"{"outer":{"key":"value","listtest":{"_attr":"Attribute","_another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+  let expected = r#"<?xml version="1.0"?><outer><key>value</key><listtest attr="Attribute" another="attrbte"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem>This  is
+          
+          character
+          
+          data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest></outer>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().attr_prefix("_")).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_lists_cdata_key_text() {
+  let xml = load_xml("tests/data/lists.xml");
+  let expected = r#"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"_text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"#;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().cdata_key("_text")).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_lists_cdata_key_text() {
+  let json = r#"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"_text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"#;
+  let expected = r#"<?xml version="1.0"?><outer><key>value</key><listtest attr="Attribute" another="attrbte"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem>This  is
+          
+          character
+          
+          data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest></outer>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().cdata_key("_text")).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_lists_xml_attribs_false() {
+  let xml = load_xml("tests/data/lists.xml");
+  let expected = r##"{"outer":{"key":"value","listtest":{"item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_lists_xml_attribs_false() {
+  let json = r##"{"outer":{"key":"value","listtest":{"item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"This  is\n          \n          character\n          \n          data!"},"Qux.","Quux."],"single":"Single"}}}"##;
+  let expected = r#"<?xml version="1.0"?><outer><key>value</key><listtest><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem>This  is
+          
+          character
+          
+          data!</item><item>Qux.</item><item>Quux.</item><single>Single</single></listtest></outer>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().xml_attribs(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn parse_lists_strip_whitespace_false() {
+  let xml = load_xml("tests/data/lists.xml");
+  let expected = r##"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"\n          This  is\n          \n          character\n          \n          data!\n          \n      "},"Qux.","Quux."],"single":"Single","#text":"\n      \n      \n      \n      \n  "},"#text":"\n  \n  \n"}}"##;
+
+  let json_builder = JsonConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = json_builder.build_string_from_xml(&xml);
+
+  let actual = result.expect("Error building JSON.");
+  assert_eq!(expected, actual);
+}
+
+#[test]
+fn unparse_lists_strip_whitespace_false() {
+  let json = r##"{"outer":{"key":"value","listtest":{"@attr":"Attribute","@another":"attrbte","item":[{"subitem":["Foo(1)","Foo(2)","Foo(3)","4"],"#text":"\n          This  is\n          \n          character\n          \n          data!\n          \n      "},"Qux.","Quux."],"single":"Single","#text":"\n      \n      \n      \n      \n  "},"#text":"\n  \n  \n"}}"##;
+  let expected = r#"<?xml version="1.0"?><outer><key>value</key><listtest attr="Attribute" another="attrbte"><item><subitem>Foo(1)</subitem><subitem>Foo(2)</subitem><subitem>Foo(3)</subitem><subitem>4</subitem>
+          This  is
+          
+          character
+          
+          data!
+          
+      </item><item>Qux.</item><item>Quux.</item><single>Single</single>
+      
+      
+      
+      
+  </listtest>
+  
+  
+</outer>"#;
+
+  let mut xml_builder = XmlConfig::new().convention(XmlToDict::new().strip_whitespace(false)).finalize();
+  let result = xml_builder.build_from_json_string(json);
+
+  let actual = result.expect("Error building XML.");
+  assert_eq!(expected, actual);
+}